@@ -4,6 +4,7 @@ import path from "node:path";
 const ROOT = process.cwd();
 const TAURI_LIB = path.join(ROOT, "src-tauri/src/lib.rs");
 const COMMANDS_DIR = path.join(ROOT, "src-tauri/src/commands");
+const TAURI_EVENTS = path.join(ROOT, "src-tauri/src/events.rs");
 const TYPES_FILE = path.join(ROOT, "src/types/index.ts");
 const OUTPUT_FILE = path.join(ROOT, "src/core/ipc.generated.ts");
 
@@ -17,7 +18,7 @@ function listRustFiles() {
     .filter((name) => name.endsWith(".rs"))
     .map((name) => path.join(COMMANDS_DIR, name));
 
-  return [TAURI_LIB, ...commandFiles];
+  return [TAURI_LIB, TAURI_EVENTS, ...commandFiles];
 }
 
 function splitTopLevel(input, delimiter = ",") {
@@ -64,6 +65,20 @@ function snakeToCamel(name) {
   return name.replace(/_([a-z])/g, (_, c) => c.toUpperCase());
 }
 
+function renameVariant(name, rule) {
+  const words = name.replace(/([a-z0-9])([A-Z])/g, "$1 $2").split(" ");
+  switch (rule) {
+    case "lowercase":
+      return name.toLowerCase();
+    case "snake_case":
+      return words.map((w) => w.toLowerCase()).join("_");
+    case "kebab-case":
+      return words.map((w) => w.toLowerCase()).join("-");
+    default:
+      return name;
+  }
+}
+
 function extractKnownTypes() {
   const source = read(TYPES_FILE);
   const types = new Set();
@@ -122,6 +137,51 @@ function extractStructs(rustSource) {
   return structs;
 }
 
+// Unit-only enums become string literal unions, honouring serde rename_all.
+function extractEnums(rustSource) {
+  const enums = new Map();
+  const enumRegex = /((?:#\[[^\]]*\]\s*)*)pub enum\s+(\w+)\s*\{([\s\S]*?)\}/g;
+  let match;
+
+  while ((match = enumRegex.exec(rustSource)) !== null) {
+    const attrs = match[1];
+    const enumName = match[2];
+    const body = match[3];
+    const rename = attrs.match(/rename_all\s*=\s*"([^"]+)"/);
+    const variants = [];
+    let unitOnly = true;
+
+    for (const raw of body.split("\n")) {
+      const line = raw.replace(/\/\/.*$/, "").trim();
+      if (!line || line.startsWith("#")) continue;
+      const variant = line.match(/^(\w+),?$/);
+      if (!variant) {
+        unitOnly = false;
+        break;
+      }
+      variants.push(renameVariant(variant[1], rename ? rename[1] : null));
+    }
+
+    if (unitOnly && variants.length > 0) {
+      enums.set(enumName, variants);
+    }
+  }
+
+  return enums;
+}
+
+function extractEvents(rustSource) {
+  const events = [];
+  const eventRegex = /impl AppEvent for\s+(\w+)\s*\{\s*const NAME: &'static str = "([^"]+)";/g;
+  let match;
+
+  while ((match = eventRegex.exec(rustSource)) !== null) {
+    events.push({ payload: match[1], name: match[2] });
+  }
+
+  return events;
+}
+
 function extractCommands(rustSource) {
   const commands = new Map();
   const commandRegex = /#\[tauri::command\]\s*(?:pub\s+)?(?:async\s+)?fn\s+(\w+)\s*\(([^)]*)\)\s*(?:->\s*([^\{]+))?\s*\{/g;
@@ -186,6 +246,11 @@ function rustTypeToTs(rustType, knownTypes, generatedStructs) {
     return `${rustTypeToTs(inner, knownTypes, generatedStructs)}[]`;
   }
 
+  const map = t.match(/^(?:HashMap|BTreeMap)<\s*String\s*,\s*(.+)>$/);
+  if (map) {
+    return `Record<string, ${rustTypeToTs(map[1], knownTypes, generatedStructs)}>`;
+  }
+
   if (knownTypes.has(t) || generatedStructs.has(t)) {
     return t;
   }
@@ -197,13 +262,23 @@ function buildGeneratedFile({
   commands,
   registeredNames,
   structs,
+  enums,
+  events,
   knownTypes,
 }) {
-  const generatedStructs = new Set(structs.keys());
+  const generatedStructs = new Set([...structs.keys(), ...enums.keys()]);
   const commandEntries = [];
   const usedKnownTypes = new Set();
   const inlineStructs = new Set();
 
+  function useType(tsType) {
+    const base = tsType.replace(/ \| null$/, "").replace(/\[\]$/, "");
+    if (knownTypes.has(base)) usedKnownTypes.add(base);
+    if (generatedStructs.has(base) && !knownTypes.has(base)) {
+      inlineStructs.add(base);
+    }
+  }
+
   for (const commandName of registeredNames) {
     const command = commands.get(commandName);
     if (!command) {
@@ -223,28 +298,12 @@ function buildGeneratedFile({
       const fieldName = snakeToCamel(param.name);
       const tsType = rustTypeToTs(param.rustType, knownTypes, generatedStructs);
       argFields.push({ name: fieldName, tsType });
-
-      if (knownTypes.has(tsType)) usedKnownTypes.add(tsType);
-      if (generatedStructs.has(tsType) && !knownTypes.has(tsType)) {
-        inlineStructs.add(tsType);
-      }
+      useType(tsType);
     }
 
     const unwrappedReturn = unwrapResult(command.returnTypeRaw || "()");
     const returnType = rustTypeToTs(unwrappedReturn, knownTypes, generatedStructs);
-
-    if (knownTypes.has(returnType)) usedKnownTypes.add(returnType);
-    if (generatedStructs.has(returnType) && !knownTypes.has(returnType)) {
-      inlineStructs.add(returnType);
-    }
-
-    if (returnType.endsWith("[]")) {
-      const inner = returnType.slice(0, -2);
-      if (knownTypes.has(inner)) usedKnownTypes.add(inner);
-      if (generatedStructs.has(inner) && !knownTypes.has(inner)) {
-        inlineStructs.add(inner);
-      }
-    }
+    useType(returnType);
 
     commandEntries.push({
       name: commandName,
@@ -253,6 +312,27 @@ function buildGeneratedFile({
     });
   }
 
+  for (const event of events) {
+    if (!structs.has(event.payload)) {
+      throw new Error(`Event '${event.name}' payload '${event.payload}' has no struct definition.`);
+    }
+    useType(event.payload);
+  }
+
+  // Pull in types referenced from fields of types already included.
+  const pending = [...inlineStructs];
+  while (pending.length > 0) {
+    const fields = structs.get(pending.pop()) || [];
+    for (const field of fields) {
+      const before = inlineStructs.size;
+      const tsType = rustTypeToTs(field.rustType, knownTypes, generatedStructs);
+      useType(tsType);
+      if (inlineStructs.size > before) {
+        pending.push(tsType.replace(/ \| null$/, "").replace(/\[\]$/, ""));
+      }
+    }
+  }
+
   const lines = [];
   lines.push("/**");
   lines.push(" * AUTO-GENERATED FILE. DO NOT EDIT.");
@@ -266,9 +346,16 @@ function buildGeneratedFile({
     lines.push("");
   }
 
-  for (const structName of [...inlineStructs].sort()) {
-    const fields = structs.get(structName) || [];
-    lines.push(`export interface ${structName} {`);
+  for (const typeName of [...inlineStructs].sort()) {
+    if (enums.has(typeName)) {
+      const variants = enums.get(typeName).map((v) => `\"${v}\"`).join(" | ");
+      lines.push(`export type ${typeName} = ${variants};`);
+      lines.push("");
+      continue;
+    }
+
+    const fields = structs.get(typeName) || [];
+    lines.push(`export interface ${typeName} {`);
     for (const field of fields) {
       const fieldType = rustTypeToTs(field.rustType, knownTypes, generatedStructs);
       lines.push(`  ${field.name}: ${fieldType};`);
@@ -304,6 +391,20 @@ function buildGeneratedFile({
   lines.push("}");
   lines.push("");
 
+  lines.push("export const EVENT_NAMES = [");
+  for (const event of events) {
+    lines.push(`  \"${event.name}\",`);
+  }
+  lines.push("] as const;");
+  lines.push("");
+
+  lines.push("export interface EventPayloads {");
+  for (const event of events) {
+    lines.push(`  \"${event.name}\": ${event.payload};`);
+  }
+  lines.push("}");
+  lines.push("");
+
   return `${lines.join("\n")}\n`;
 }
 
@@ -314,7 +415,9 @@ function main() {
   const registeredNames = extractRegisteredCommands();
 
   const mergedStructs = new Map();
+  const mergedEnums = new Map();
   const mergedCommands = new Map();
+  const mergedEvents = [];
 
   for (const file of rustFiles) {
     const source = read(file);
@@ -325,6 +428,12 @@ function main() {
       mergedStructs.set(name, fields);
     }
 
+    for (const [name, variants] of extractEnums(source).entries()) {
+      mergedEnums.set(name, variants);
+    }
+
+    mergedEvents.push(...extractEvents(source));
+
     for (const [name, command] of commands.entries()) {
       mergedCommands.set(name, command);
     }
@@ -334,6 +443,8 @@ function main() {
     commands: mergedCommands,
     registeredNames,
     structs: mergedStructs,
+    enums: mergedEnums,
+    events: mergedEvents,
     knownTypes,
   });
 
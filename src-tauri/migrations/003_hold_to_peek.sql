@@ -0,0 +1,3 @@
+ALTER TABLE app_settings ADD COLUMN hold_modifier TEXT NOT NULL DEFAULT 'command';
+ALTER TABLE app_settings ADD COLUMN hold_duration_ms INTEGER NOT NULL DEFAULT 1000;
+ALTER TABLE app_settings ADD COLUMN hold_hide_on_release INTEGER NOT NULL DEFAULT 1;
@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS hotkey_bindings (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  shortcut TEXT NOT NULL UNIQUE,
+  action TEXT NOT NULL CHECK (action IN ('show_category', 'copy_item')),
+  category_id INTEGER REFERENCES categories(id) ON DELETE CASCADE,
+  item_id INTEGER REFERENCES items(id) ON DELETE CASCADE,
+  created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
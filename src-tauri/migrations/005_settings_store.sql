@@ -0,0 +1,27 @@
+CREATE TABLE IF NOT EXISTS settings (
+  key TEXT PRIMARY KEY,
+  value TEXT NOT NULL,
+  updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE TABLE IF NOT EXISTS settings_meta (
+  id INTEGER PRIMARY KEY CHECK (id = 1),
+  version INTEGER NOT NULL
+);
+
+INSERT INTO settings (key, value)
+SELECT 'locale', json_quote(locale) FROM app_settings WHERE id = 1
+UNION ALL
+SELECT 'launch_on_login', CASE WHEN launch_on_login THEN 'true' ELSE 'false' END FROM app_settings WHERE id = 1
+UNION ALL
+SELECT 'theme', json_quote(theme) FROM app_settings WHERE id = 1
+UNION ALL
+SELECT 'hold_modifier', json_quote(hold_modifier) FROM app_settings WHERE id = 1
+UNION ALL
+SELECT 'hold_duration_ms', json_quote(hold_duration_ms) FROM app_settings WHERE id = 1
+UNION ALL
+SELECT 'hold_hide_on_release', CASE WHEN hold_hide_on_release THEN 'true' ELSE 'false' END FROM app_settings WHERE id = 1;
+
+INSERT INTO settings_meta (id, version) VALUES (1, 1);
+
+DROP TABLE app_settings;
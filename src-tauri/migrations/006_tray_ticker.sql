@@ -0,0 +1,4 @@
+CREATE TABLE IF NOT EXISTS ticker_items (
+  item_id INTEGER PRIMARY KEY REFERENCES items(id) ON DELETE CASCADE,
+  sort_order INTEGER NOT NULL DEFAULT 0
+);
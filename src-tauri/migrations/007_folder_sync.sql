@@ -0,0 +1,31 @@
+-- Ids that identify the same category or item on every device. Assigned
+-- lazily by the sync engine, since ADD COLUMN cannot use a random default.
+ALTER TABLE categories ADD COLUMN sync_id TEXT;
+ALTER TABLE items ADD COLUMN sync_id TEXT;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_categories_sync_id ON categories(sync_id);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_items_sync_id ON items(sync_id);
+
+-- Each entity as of the last sync: the base for three-way merges.
+CREATE TABLE IF NOT EXISTS sync_base (
+  entity TEXT NOT NULL,
+  sync_id TEXT NOT NULL,
+  record TEXT NOT NULL,
+  PRIMARY KEY (entity, sync_id)
+);
+
+-- Fields edited on two devices at once; the losing version is kept here.
+CREATE TABLE IF NOT EXISTS sync_conflicts (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  entity TEXT NOT NULL,
+  sync_id TEXT NOT NULL,
+  local TEXT NOT NULL,
+  remote TEXT NOT NULL,
+  kept TEXT NOT NULL,
+  detected_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE TABLE IF NOT EXISTS sync_device (
+  id INTEGER PRIMARY KEY CHECK (id = 1),
+  device_id TEXT NOT NULL
+);
+INSERT OR IGNORE INTO sync_device (id, device_id) VALUES (1, lower(hex(randomblob(16))));
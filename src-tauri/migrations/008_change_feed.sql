@@ -0,0 +1,41 @@
+-- Every insert, update and delete of a category or item, in order. `seq` is
+-- the cursor handed out by get_changes_since.
+CREATE TABLE IF NOT EXISTS changes (
+  seq INTEGER PRIMARY KEY AUTOINCREMENT,
+  entity TEXT NOT NULL CHECK (entity IN ('category', 'item')),
+  entity_id INTEGER NOT NULL,
+  op TEXT NOT NULL CHECK (op IN ('upsert', 'delete')),
+  changed_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+CREATE INDEX IF NOT EXISTS idx_changes_entity ON changes(entity, entity_id, seq);
+
+-- Rows that exist already count as changed at the start of the feed.
+INSERT INTO changes (entity, entity_id, op) SELECT 'category', id, 'upsert' FROM categories;
+INSERT INTO changes (entity, entity_id, op) SELECT 'item', id, 'upsert' FROM items;
+
+CREATE TRIGGER IF NOT EXISTS categories_change_insert AFTER INSERT ON categories
+BEGIN
+  INSERT INTO changes (entity, entity_id, op) VALUES ('category', NEW.id, 'upsert');
+END;
+CREATE TRIGGER IF NOT EXISTS categories_change_update AFTER UPDATE ON categories
+BEGIN
+  INSERT INTO changes (entity, entity_id, op) VALUES ('category', NEW.id, 'upsert');
+END;
+CREATE TRIGGER IF NOT EXISTS categories_change_delete AFTER DELETE ON categories
+BEGIN
+  INSERT INTO changes (entity, entity_id, op) VALUES ('category', OLD.id, 'delete');
+END;
+
+-- Cascading deletes from categories fire these too.
+CREATE TRIGGER IF NOT EXISTS items_change_insert AFTER INSERT ON items
+BEGIN
+  INSERT INTO changes (entity, entity_id, op) VALUES ('item', NEW.id, 'upsert');
+END;
+CREATE TRIGGER IF NOT EXISTS items_change_update AFTER UPDATE ON items
+BEGIN
+  INSERT INTO changes (entity, entity_id, op) VALUES ('item', NEW.id, 'upsert');
+END;
+CREATE TRIGGER IF NOT EXISTS items_change_delete AFTER DELETE ON items
+BEGIN
+  INSERT INTO changes (entity, entity_id, op) VALUES ('item', OLD.id, 'delete');
+END;
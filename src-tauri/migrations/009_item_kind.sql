@@ -0,0 +1,3 @@
+-- What an item's value is: plain `text` to copy, or a `link` (a URL) to
+-- open, such as an imported browser bookmark.
+ALTER TABLE items ADD COLUMN kind TEXT NOT NULL DEFAULT 'text';
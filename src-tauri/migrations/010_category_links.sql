@@ -0,0 +1,9 @@
+-- Categories that mirror a Markdown file. Their items are rewritten from the
+-- file whenever it changes and are read-only in the main window. `error`
+-- holds why the file could not be read at the last attempt.
+CREATE TABLE IF NOT EXISTS category_links (
+  category_id INTEGER PRIMARY KEY REFERENCES categories(id) ON DELETE CASCADE,
+  path TEXT NOT NULL,
+  error TEXT,
+  synced_at TEXT
+);
@@ -0,0 +1,28 @@
+-- Earlier versions of each item, written whenever its label or value
+-- changes, however it was changed (the app, the CLI, sync, imports).
+CREATE TABLE IF NOT EXISTS item_revisions (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  item_id INTEGER NOT NULL REFERENCES items(id) ON DELETE CASCADE,
+  label TEXT NOT NULL,
+  value TEXT NOT NULL,
+  created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+CREATE INDEX IF NOT EXISTS idx_item_revisions_item ON item_revisions(item_id, id);
+
+-- Keeps the newest `item_revision_limit` revisions of the item. The
+-- fallback must match the setting's default in settings_schema.rs, which
+-- is not stored until it is changed.
+CREATE TRIGGER IF NOT EXISTS items_revision_update AFTER UPDATE OF label, value ON items
+WHEN OLD.label IS NOT NEW.label OR OLD.value IS NOT NEW.value
+BEGIN
+  INSERT INTO item_revisions (item_id, label, value) VALUES (OLD.id, OLD.label, OLD.value);
+  DELETE FROM item_revisions
+  WHERE item_id = OLD.id
+    AND id NOT IN (
+      SELECT id FROM item_revisions WHERE item_id = OLD.id ORDER BY id DESC
+      LIMIT COALESCE(
+        (SELECT CAST(value AS INTEGER) FROM settings WHERE key = 'item_revision_limit'),
+        20
+      )
+    );
+END;
@@ -0,0 +1,12 @@
+-- When a temporary item (a room code, a guest Wi-Fi password) stops being
+-- useful, as `YYYY-MM-DD HH:MM:SS` UTC; NULL keeps it forever. Expired items
+-- are hidden at once and cleaned up by a background task.
+ALTER TABLE items ADD COLUMN expires_at TEXT;
+CREATE INDEX IF NOT EXISTS idx_items_expires_at ON items(expires_at) WHERE expires_at IS NOT NULL;
+
+-- The category the expiry cleanup moves items to. Kept by id, since its name
+-- is localized and may be renamed; deleting the category forgets it.
+CREATE TABLE IF NOT EXISTS expired_category (
+  id INTEGER PRIMARY KEY CHECK (id = 1),
+  category_id INTEGER NOT NULL REFERENCES categories(id) ON DELETE CASCADE
+);
@@ -0,0 +1,10 @@
+-- The HTTP API token, kept apart from `settings` so it is never sent to the
+-- webviews with them. It moves here from the settings store.
+CREATE TABLE IF NOT EXISTS http_api_token (
+  id INTEGER PRIMARY KEY CHECK (id = 1),
+  token TEXT NOT NULL
+);
+INSERT OR IGNORE INTO http_api_token (id, token)
+SELECT 1, CASE WHEN json_valid(value) THEN json_extract(value, '$') ELSE value END
+FROM settings WHERE key = 'http_api_token';
+DELETE FROM settings WHERE key = 'http_api_token';
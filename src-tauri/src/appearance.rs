@@ -0,0 +1,178 @@
+//! Applies the `theme` setting to the native windows and tracks the
+//! effective theme, which for `system` follows the OS appearance.
+
+use std::sync::Mutex;
+
+use tauri::{AppHandle, Manager};
+
+use crate::commands::appearance::{EffectiveTheme, Theme};
+use crate::events::{self, EffectiveThemeChanged};
+
+/// Pure bookkeeping for the effective theme. The mutators return the new
+/// effective theme only when it changed, so callers emit one event per
+/// actual change no matter how many windows report it.
+#[derive(Debug)]
+pub struct ThemeState {
+    setting: Theme,
+    system: EffectiveTheme,
+    effective: EffectiveTheme,
+}
+
+impl ThemeState {
+    pub fn new(setting: Theme, system: EffectiveTheme) -> Self {
+        Self {
+            setting,
+            system,
+            effective: resolve(setting, system),
+        }
+    }
+
+    pub fn effective(&self) -> EffectiveTheme {
+        self.effective
+    }
+
+    pub fn set_setting(&mut self, setting: Theme) -> Option<EffectiveTheme> {
+        self.setting = setting;
+        self.update()
+    }
+
+    /// Record the appearance reported by a window. While an explicit theme
+    /// is set, windows report that theme rather than the OS one, so the
+    /// report is ignored.
+    pub fn set_system(&mut self, system: EffectiveTheme) -> Option<EffectiveTheme> {
+        if self.setting != Theme::System {
+            return None;
+        }
+        self.system = system;
+        self.update()
+    }
+
+    fn update(&mut self) -> Option<EffectiveTheme> {
+        let effective = resolve(self.setting, self.system);
+        if effective == self.effective {
+            return None;
+        }
+        self.effective = effective;
+        Some(effective)
+    }
+}
+
+pub fn resolve(setting: Theme, system: EffectiveTheme) -> EffectiveTheme {
+    match setting {
+        Theme::System => system,
+        Theme::Light => EffectiveTheme::Light,
+        Theme::Dark => EffectiveTheme::Dark,
+    }
+}
+
+/// Managed state wrapping [`ThemeState`].
+pub struct Appearance(Mutex<ThemeState>);
+
+impl Appearance {
+    pub fn new(setting: Theme, system: EffectiveTheme) -> Self {
+        Self(Mutex::new(ThemeState::new(setting, system)))
+    }
+
+    pub fn effective(&self) -> EffectiveTheme {
+        self.0.lock().unwrap().effective()
+    }
+}
+
+fn to_native(theme: Theme) -> Option<tauri::Theme> {
+    match theme {
+        Theme::System => None,
+        Theme::Light => Some(tauri::Theme::Light),
+        Theme::Dark => Some(tauri::Theme::Dark),
+    }
+}
+
+fn from_native(theme: tauri::Theme) -> EffectiveTheme {
+    match theme {
+        tauri::Theme::Dark => EffectiveTheme::Dark,
+        _ => EffectiveTheme::Light,
+    }
+}
+
+/// Current OS appearance as seen by the main window.
+pub fn system_theme(app: &AppHandle) -> EffectiveTheme {
+    app.get_webview_window("main")
+        .and_then(|window| window.theme().ok())
+        .map(from_native)
+        .unwrap_or(EffectiveTheme::Light)
+}
+
+/// Set the native theme of every webview window and emit the effective
+/// theme if it changed.
+pub fn apply_theme(app: &AppHandle, theme: Theme) {
+    for (label, window) in app.webview_windows() {
+        if let Err(err) = window.set_theme(to_native(theme)) {
+            eprintln!("failed to set theme on {label}: {err}");
+        }
+    }
+
+    // Windows now follow the OS again; pick up its current value. Read before
+    // locking: `window.theme()` waits on the main thread, which may itself be
+    // waiting for the lock in `on_window_theme_changed`.
+    let system = (theme == Theme::System).then(|| system_theme(app));
+    let changed = {
+        let appearance = app.state::<Appearance>();
+        let mut state = appearance.inner().0.lock().unwrap();
+        let changed = state.set_setting(theme);
+        match system {
+            Some(system) => state.set_system(system).or(changed),
+            None => changed,
+        }
+    };
+    if let Some(theme) = changed {
+        events::emit(app, EffectiveThemeChanged { theme });
+    }
+}
+
+/// Handle a window's `ThemeChanged` event.
+pub fn on_window_theme_changed(app: &AppHandle, theme: tauri::Theme) {
+    // Windows can report their theme before setup has managed the state.
+    let Some(appearance) = app.try_state::<Appearance>() else {
+        return;
+    };
+    let changed = appearance
+        .inner()
+        .0
+        .lock()
+        .unwrap()
+        .set_system(from_native(theme));
+    if let Some(theme) = changed {
+        events::emit(app, EffectiveThemeChanged { theme });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn explicit_theme_overrides_system() {
+        let mut state = ThemeState::new(Theme::Dark, EffectiveTheme::Light);
+        assert_eq!(state.effective(), EffectiveTheme::Dark);
+
+        // Windows report the forced theme; that is not an OS change.
+        assert_eq!(state.set_system(EffectiveTheme::Dark), None);
+        assert_eq!(state.set_setting(Theme::Light), Some(EffectiveTheme::Light));
+    }
+
+    #[test]
+    fn system_theme_follows_os_and_reports_each_change_once() {
+        let mut state = ThemeState::new(Theme::System, EffectiveTheme::Light);
+
+        assert_eq!(
+            state.set_system(EffectiveTheme::Dark),
+            Some(EffectiveTheme::Dark)
+        );
+        // The same change reported by another window.
+        assert_eq!(state.set_system(EffectiveTheme::Dark), None);
+
+        // Switching to an explicit theme that matches changes nothing.
+        assert_eq!(state.set_setting(Theme::Dark), None);
+        assert_eq!(state.set_setting(Theme::Light), Some(EffectiveTheme::Light));
+        assert_eq!(state.set_setting(Theme::System), Some(EffectiveTheme::Dark));
+    }
+}
@@ -0,0 +1,310 @@
+//! Launch-on-login support: generates the per-user login item for the
+//! current platform and reconciles it with the `launch_on_login` setting.
+//!
+//! macOS uses a LaunchAgent plist in `~/Library/LaunchAgents`, Linux an XDG
+//! autostart entry in `~/.config/autostart`. Everything here works on plain
+//! paths so it can be exercised against a temporary home directory.
+
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoginItemKind {
+    LaunchAgent,
+    XdgAutostart,
+}
+
+impl LoginItemKind {
+    pub fn current() -> Option<Self> {
+        if cfg!(target_os = "macos") {
+            Some(LoginItemKind::LaunchAgent)
+        } else if cfg!(target_os = "linux") {
+            Some(LoginItemKind::XdgAutostart)
+        } else {
+            None
+        }
+    }
+}
+
+/// The file that makes the OS start the app at login.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoginItem {
+    pub path: PathBuf,
+    pub contents: String,
+}
+
+impl LoginItem {
+    /// `config_home` is only used for XDG entries and defaults to
+    /// `<home>/.config` when `None`.
+    pub fn new(
+        kind: LoginItemKind,
+        identifier: &str,
+        home: &Path,
+        config_home: Option<&Path>,
+        exe: &Path,
+    ) -> Self {
+        match kind {
+            LoginItemKind::LaunchAgent => Self {
+                path: home
+                    .join("Library/LaunchAgents")
+                    .join(format!("{identifier}.plist")),
+                contents: launch_agent_plist(identifier, exe),
+            },
+            LoginItemKind::XdgAutostart => Self {
+                path: config_home
+                    .map(Path::to_path_buf)
+                    .unwrap_or_else(|| home.join(".config"))
+                    .join("autostart")
+                    .join(format!("{identifier}.desktop")),
+                contents: desktop_entry(exe),
+            },
+        }
+    }
+
+    pub fn is_installed(&self) -> bool {
+        self.path.is_file()
+    }
+
+    fn is_current(&self) -> bool {
+        fs::read_to_string(&self.path).is_ok_and(|existing| existing == self.contents)
+    }
+}
+
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+pub fn launch_agent_plist(identifier: &str, exe: &Path) -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+  <key>Label</key>
+  <string>{label}</string>
+  <key>ProgramArguments</key>
+  <array>
+    <string>{exe}</string>
+  </array>
+  <key>RunAtLoad</key>
+  <true/>
+  <key>ProcessType</key>
+  <string>Interactive</string>
+</dict>
+</plist>
+"#,
+        label = xml_escape(identifier),
+        exe = xml_escape(&exe.to_string_lossy()),
+    )
+}
+
+/// Quote an `Exec` argument per the Desktop Entry spec when needed.
+fn desktop_exec_arg(arg: &str) -> String {
+    let needs_quoting = arg
+        .chars()
+        .any(|c| c.is_whitespace() || "\"'\\><~|&;$*?#()`".contains(c));
+    if !needs_quoting {
+        return arg.to_string();
+    }
+    let mut quoted = String::from("\"");
+    for c in arg.chars() {
+        if matches!(c, '"' | '`' | '$' | '\\') {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+    quoted.push('"');
+    quoted
+}
+
+pub fn desktop_entry(exe: &Path) -> String {
+    format!(
+        "[Desktop Entry]\nType=Application\nName=Peeky\nExec={}\nTerminal=false\nX-GNOME-Autostart-enabled=true\n",
+        desktop_exec_arg(&exe.to_string_lossy()),
+    )
+}
+
+/// The login item for the running executable and current user, or `None`
+/// where launch-on-login is not supported.
+pub fn for_current_user(identifier: &str) -> Option<LoginItem> {
+    let kind = LoginItemKind::current()?;
+    let home = env::var_os("HOME").map(PathBuf::from)?;
+    let config_home = env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute());
+    let exe = env::current_exe().ok()?;
+    Some(LoginItem::new(
+        kind,
+        identifier,
+        &home,
+        config_home.as_deref(),
+        &exe,
+    ))
+}
+
+/// How the installed login item differs from the setting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Drift {
+    /// Enabled in settings but the login item is gone.
+    RemovedExternally,
+    /// Disabled in settings but a login item exists.
+    AddedExternally,
+    /// Installed, but points at an old executable or has been edited.
+    Outdated,
+}
+
+pub fn detect_drift(item: &LoginItem, enabled: bool) -> Option<Drift> {
+    match (enabled, item.is_installed()) {
+        (true, false) => Some(Drift::RemovedExternally),
+        (false, true) => Some(Drift::AddedExternally),
+        (true, true) if !item.is_current() => Some(Drift::Outdated),
+        _ => None,
+    }
+}
+
+/// Make the OS state match `enabled`: write (or rewrite) the login item, or
+/// remove it.
+pub fn apply(item: &LoginItem, enabled: bool) -> io::Result<()> {
+    if enabled {
+        if item.is_current() {
+            return Ok(());
+        }
+        if let Some(parent) = item.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&item.path, &item.contents)
+    } else {
+        match fs::remove_file(&item.path) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Startup reconciliation. A login item the user added or removed behind our
+/// back is left alone and reported, so their choice is not silently undone;
+/// an outdated item (e.g. the app was moved) is rewritten.
+pub fn reconcile_on_startup(item: &LoginItem, enabled: bool) -> io::Result<Option<Drift>> {
+    match detect_drift(item, enabled) {
+        Some(Drift::Outdated) => apply(item, enabled).map(|_| None),
+        drift => Ok(drift),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ID: &str = "com.peeky.app";
+
+    fn item(kind: LoginItemKind, home: &Path, exe: &str) -> LoginItem {
+        LoginItem::new(kind, ID, home, None, Path::new(exe))
+    }
+
+    #[test]
+    fn launch_agent_lives_in_library_and_escapes_paths() {
+        let home = tempfile::tempdir().unwrap();
+        let agent = item(
+            LoginItemKind::LaunchAgent,
+            home.path(),
+            "/Applications/Peeky & Co.app/Contents/MacOS/peeky",
+        );
+
+        assert_eq!(
+            agent.path,
+            home.path().join("Library/LaunchAgents/com.peeky.app.plist")
+        );
+        assert!(agent.contents.contains("<string>com.peeky.app</string>"));
+        assert!(agent
+            .contents
+            .contains("<string>/Applications/Peeky &amp; Co.app/Contents/MacOS/peeky</string>"));
+        assert!(agent.contents.contains("<key>RunAtLoad</key>\n  <true/>"));
+    }
+
+    #[test]
+    fn desktop_entry_respects_config_home_and_quotes_exec() {
+        let home = tempfile::tempdir().unwrap();
+        let config = home.path().join("xdg");
+        let entry = LoginItem::new(
+            LoginItemKind::XdgAutostart,
+            ID,
+            home.path(),
+            Some(&config),
+            Path::new("/opt/My Apps/peeky"),
+        );
+
+        assert_eq!(entry.path, config.join("autostart/com.peeky.app.desktop"));
+        assert!(entry.contents.contains("Exec=\"/opt/My Apps/peeky\"\n"));
+
+        let plain = item(LoginItemKind::XdgAutostart, home.path(), "/usr/bin/peeky");
+        assert_eq!(
+            plain.path,
+            home.path().join(".config/autostart/com.peeky.app.desktop")
+        );
+        assert!(plain.contents.contains("Exec=/usr/bin/peeky\n"));
+    }
+
+    #[test]
+    fn apply_installs_and_removes_login_item() {
+        let home = tempfile::tempdir().unwrap();
+        let entry = item(LoginItemKind::XdgAutostart, home.path(), "/usr/bin/peeky");
+
+        apply(&entry, true).unwrap();
+        assert_eq!(fs::read_to_string(&entry.path).unwrap(), entry.contents);
+        assert_eq!(detect_drift(&entry, true), None);
+
+        apply(&entry, false).unwrap();
+        assert!(!entry.is_installed());
+        // Removing twice is fine.
+        apply(&entry, false).unwrap();
+    }
+
+    #[test]
+    fn detects_drift_in_both_directions() {
+        let home = tempfile::tempdir().unwrap();
+        let agent = item(
+            LoginItemKind::LaunchAgent,
+            home.path(),
+            "/Applications/Peeky.app",
+        );
+
+        assert_eq!(detect_drift(&agent, true), Some(Drift::RemovedExternally));
+        assert_eq!(detect_drift(&agent, false), None);
+
+        apply(&agent, true).unwrap();
+        assert_eq!(detect_drift(&agent, false), Some(Drift::AddedExternally));
+
+        let moved = item(
+            LoginItemKind::LaunchAgent,
+            home.path(),
+            "/Users/me/Peeky.app",
+        );
+        assert_eq!(detect_drift(&moved, true), Some(Drift::Outdated));
+    }
+
+    #[test]
+    fn startup_reconcile_reports_external_changes_and_fixes_outdated() {
+        let home = tempfile::tempdir().unwrap();
+        let old = item(LoginItemKind::XdgAutostart, home.path(), "/old/peeky");
+        let new = item(LoginItemKind::XdgAutostart, home.path(), "/new/peeky");
+
+        // User deleted the entry: reported, not recreated.
+        assert_eq!(
+            reconcile_on_startup(&new, true).unwrap(),
+            Some(Drift::RemovedExternally)
+        );
+        assert!(!new.is_installed());
+
+        // App moved: entry rewritten silently.
+        apply(&old, true).unwrap();
+        assert_eq!(reconcile_on_startup(&new, true).unwrap(), None);
+        assert_eq!(fs::read_to_string(&new.path).unwrap(), new.contents);
+    }
+}
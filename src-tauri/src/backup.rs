@@ -0,0 +1,485 @@
+//! Rotating backups of the database.
+//!
+//! Backups are taken with `VACUUM INTO`, which writes a consistent snapshot
+//! while the app keeps using the database, into `backups/` next to
+//! `peeky.db`. The file name records when and why a backup was made
+//! (`peeky-20261018-093000-automatic.db`), so listing needs no index.
+//!
+//! Restoring validates the backup, snapshots the current database, then
+//! closes every pool on the file, moves the backup into place and opens a
+//! new pool, bringing its schema up to date with [`db::migrator`].
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+use sqlx::SqlitePool;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_sql::{DbInstances, DbPool};
+
+use crate::commands::backup::{BackupInfo, BackupKind};
+use crate::commands::settings::{get_settings_by_pool, reapply_settings};
+use crate::db::{self, Db};
+use crate::events::{self, DatabaseRestored};
+use crate::http_api;
+use crate::i18n;
+use crate::library;
+
+const PREFIX: &str = "peeky-";
+const EXTENSION: &str = ".db";
+/// `YYYYMMDD-HHMMSS`
+const STAMP_LEN: usize = 15;
+
+/// How often the scheduler checks whether a backup is due.
+const CHECK_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+pub fn backups_dir(db_path: &Path) -> PathBuf {
+    db_path.with_file_name("backups")
+}
+
+struct BackupName<'a> {
+    stamp: &'a str,
+    kind: BackupKind,
+    /// Disambiguates backups of the same kind taken within one second.
+    seq: u32,
+}
+
+fn file_name(stamp: &str, kind: BackupKind, seq: u32) -> String {
+    match seq {
+        0 => format!("{PREFIX}{stamp}-{}{EXTENSION}", kind.as_str()),
+        _ => format!("{PREFIX}{stamp}-{}-{seq}{EXTENSION}", kind.as_str()),
+    }
+}
+
+fn parse_name(name: &str) -> Option<BackupName<'_>> {
+    let rest = name.strip_prefix(PREFIX)?.strip_suffix(EXTENSION)?;
+    let stamp = rest.get(..STAMP_LEN)?;
+    let valid_stamp = stamp.bytes().enumerate().all(|(i, b)| {
+        if i == 8 {
+            b == b'-'
+        } else {
+            b.is_ascii_digit()
+        }
+    });
+    if !valid_stamp {
+        return None;
+    }
+    let rest = rest[STAMP_LEN..].strip_prefix('-')?;
+    let (kind, seq) = match rest.split_once('-') {
+        Some((kind, seq)) if !seq.starts_with('0') => (kind, seq.parse().ok()?),
+        Some(_) => return None,
+        None => (rest, 0),
+    };
+    Some(BackupName {
+        stamp,
+        kind: BackupKind::parse(kind)?,
+        seq,
+    })
+}
+
+/// `20261018-093000` as `2026-10-18 09:30:00`.
+fn format_stamp(stamp: &str) -> String {
+    format!(
+        "{}-{}-{} {}:{}:{}",
+        &stamp[0..4],
+        &stamp[4..6],
+        &stamp[6..8],
+        &stamp[9..11],
+        &stamp[11..13],
+        &stamp[13..15]
+    )
+}
+
+fn info(dir: &Path, name: &str) -> Result<BackupInfo, String> {
+    let parsed = parse_name(name).ok_or_else(|| not_found(name))?;
+    let metadata = fs::metadata(dir.join(name)).map_err(|_| not_found(name))?;
+    Ok(BackupInfo {
+        name: name.to_string(),
+        kind: parsed.kind,
+        created_at: format_stamp(parsed.stamp),
+        size_bytes: metadata.len(),
+    })
+}
+
+fn not_found(name: &str) -> String {
+    i18n::tf("error.backup_not_found", &[("name", &name)])
+}
+
+/// Backups in `dir`, newest first. Files that do not look like backups are
+/// ignored.
+pub fn list(dir: &Path) -> Result<Vec<BackupInfo>, String> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err.to_string()),
+    };
+
+    let mut names: Vec<String> = entries
+        .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+        .filter(|name| parse_name(name).is_some())
+        .collect();
+    names.sort_by_cached_key(|name| {
+        let parsed = parse_name(name).expect("filtered above");
+        std::cmp::Reverse((parsed.stamp.to_string(), parsed.seq))
+    });
+    names.iter().map(|name| info(dir, name)).collect()
+}
+
+/// Snapshot the database behind `pool` into `dir`.
+pub async fn create(pool: &SqlitePool, dir: &Path, kind: BackupKind) -> Result<BackupInfo, String> {
+    fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    let (stamp,): (String,) = sqlx::query_as("SELECT strftime('%Y%m%d-%H%M%S', 'now')")
+        .fetch_one(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let mut seq = 0;
+    let name = loop {
+        let name = file_name(&stamp, kind, seq);
+        if !dir.join(&name).exists() {
+            break name;
+        }
+        seq += 1;
+    };
+    sqlx::query("VACUUM INTO ?")
+        .bind(dir.join(&name).to_string_lossy().to_string())
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    info(dir, &name)
+}
+
+/// Delete automatic and pre-restore backups beyond the newest `keep` of
+/// each kind. Returns the names removed.
+pub fn prune(dir: &Path, keep: usize) -> Result<Vec<String>, String> {
+    let backups = list(dir)?;
+    let mut removed = Vec::new();
+    for kind in [BackupKind::Automatic, BackupKind::PreRestore] {
+        for backup in backups
+            .iter()
+            .filter(|backup| backup.kind == kind)
+            .skip(keep)
+        {
+            fs::remove_file(dir.join(&backup.name)).map_err(|e| e.to_string())?;
+            removed.push(backup.name.clone());
+        }
+    }
+    Ok(removed)
+}
+
+/// Whether an automatic backup is due, given when the last one was written.
+pub fn is_due(last: Option<SystemTime>, now: SystemTime, interval: Duration) -> bool {
+    match last {
+        None => true,
+        // A backup from the future means the clock moved back; wait for it.
+        Some(last) => now.duration_since(last).is_ok_and(|age| age >= interval),
+    }
+}
+
+/// Check that `path` is an intact Peeky database this build can open.
+/// Returns its schema version.
+pub async fn validate(path: &Path) -> Result<i64, String> {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let invalid = |error: &dyn std::fmt::Display| {
+        i18n::tf("error.backup_invalid", &[("name", &name), ("error", error)])
+    };
+
+    let options = SqliteConnectOptions::new().filename(path).read_only(true);
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect_with(options)
+        .await
+        .map_err(|e| invalid(&e))?;
+    let checked = async {
+        let (integrity,): (String,) = sqlx::query_as("PRAGMA integrity_check")
+            .fetch_one(&pool)
+            .await?;
+        let (version,): (Option<i64>,) =
+            sqlx::query_as("SELECT MAX(version) FROM _sqlx_migrations WHERE success = 1")
+                .fetch_one(&pool)
+                .await?;
+        Ok::<_, sqlx::Error>((integrity, version))
+    }
+    .await;
+    pool.close().await;
+
+    match checked {
+        Err(err) => Err(invalid(&err)),
+        Ok((integrity, _)) if integrity != "ok" => Err(invalid(&integrity)),
+        Ok((_, None)) => Err(invalid(&"no schema version")),
+        Ok((_, Some(version))) if version > db::latest_version() => {
+            Err(i18n::tf("error.backup_too_new", &[("name", &name)]))
+        }
+        Ok((_, Some(version))) => Ok(version),
+    }
+}
+
+/// Move `staged` over `target`, dropping journal files that belong to the
+/// database being replaced.
+fn swap_file(staged: &Path, target: &Path) -> io::Result<()> {
+    for suffix in ["-journal", "-wal", "-shm"] {
+        let mut sidecar = target.as_os_str().to_owned();
+        sidecar.push(suffix);
+        match fs::remove_file(PathBuf::from(sidecar)) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => return Err(err),
+            _ => {}
+        }
+    }
+    fs::rename(staged, target)
+}
+
+/// Replace the database managed by `db` with the backup `name` from `dir`.
+/// The current database is saved as a pre-restore backup first, which is
+/// returned. Other pools on the file must be closed by the caller.
+pub async fn restore(db: &Db, dir: &Path, name: &str) -> Result<BackupInfo, String> {
+    if parse_name(name).is_none() || !dir.join(name).is_file() {
+        return Err(not_found(name));
+    }
+    let source = dir.join(name);
+    validate(&source).await?;
+
+    let safety = create(&db.pool(), dir, BackupKind::PreRestore).await?;
+    let mut staged = db.path().as_os_str().to_owned();
+    staged.push(".restoring");
+    let staged = PathBuf::from(staged);
+    fs::copy(&source, &staged).map_err(|e| e.to_string())?;
+
+    // Let queries in flight finish, then swap the file. A new pool is opened
+    // whatever happens so the app keeps working on one database or the
+    // other.
+    db.pool().close().await;
+    let swapped = swap_file(&staged, db.path());
+    let pool = db::create_pool(&db.path().to_string_lossy())
+        .await
+        .map_err(|e| e.to_string())?;
+    db.set_pool(pool.clone());
+    if let Err(err) = swapped {
+        let _ = fs::remove_file(&staged);
+        return Err(err.to_string());
+    }
+
+    db::migrator().run(&pool).await.map_err(|e| e.to_string())?;
+    Ok(safety)
+}
+
+/// Close the SQL plugin's preloaded pool so no connection keeps the old
+/// file open. The plugin reconnects if the frontend loads it again.
+async fn close_plugin_pool(app: &AppHandle) {
+    let Some(instances) = app.try_state::<DbInstances>() else {
+        return;
+    };
+    let pool = instances.inner().0.write().await.remove(db::DB_URL);
+    if let Some(pool) = pool {
+        match pool {
+            DbPool::Sqlite(pool) => pool.close().await,
+        }
+    }
+}
+
+/// Restore a backup and bring the running app in line with it.
+pub async fn restore_and_reload(app: &AppHandle, name: &str) -> Result<BackupInfo, String> {
+    let db = app.state::<Db>();
+    let dir = backups_dir(db.path());
+    let before = get_settings_by_pool(&db.pool()).await?;
+
+    close_plugin_pool(app).await;
+    let safety = restore(db.inner(), &dir, name).await?;
+
+    if let Err(err) = reapply_settings(app, before).await {
+        eprintln!("failed to apply restored settings: {err}");
+    }
+    // The token is not a setting, so a changed one is not noticed above.
+    if let Err(err) = http_api::restart(app).await {
+        eprintln!("failed to restart HTTP API: {err}");
+    }
+    library::refresh(app).await;
+    events::emit(
+        app,
+        DatabaseRestored {
+            backup: name.to_string(),
+            safety_backup: safety.name.clone(),
+        },
+    );
+    Ok(safety)
+}
+
+async fn run_scheduled(app: &AppHandle) -> Result<(), String> {
+    let db = app.state::<Db>();
+    let dir = backups_dir(db.path());
+    let settings = get_settings_by_pool(&db.pool()).await?;
+    let interval = Duration::from_secs(settings.backup_interval_hours.max(1) as u64 * 3600);
+
+    let last = list(&dir)?
+        .into_iter()
+        .find(|backup| backup.kind == BackupKind::Automatic)
+        .and_then(|backup| fs::metadata(dir.join(backup.name)).ok()?.modified().ok());
+    if !is_due(last, SystemTime::now(), interval) {
+        return Ok(());
+    }
+    create(&db.pool(), &dir, BackupKind::Automatic).await?;
+    prune(&dir, settings.backup_retention.max(1) as usize)?;
+    Ok(())
+}
+
+/// Take automatic backups in the background.
+pub fn start(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            if let Err(err) = run_scheduled(&app).await {
+                eprintln!("scheduled backup failed: {err}");
+            }
+            tokio::time::sleep(CHECK_INTERVAL).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn setup_db(dir: &Path) -> Db {
+        let path = dir.join("peeky.db");
+        let pool = db::create_pool(&path.to_string_lossy())
+            .await
+            .expect("create pool");
+        db::migrator().run(&pool).await.expect("run migrations");
+        Db::new(path, pool)
+    }
+
+    async fn add_category(pool: &SqlitePool, name: &str) {
+        sqlx::query("INSERT INTO categories (name) VALUES (?)")
+            .bind(name)
+            .execute(pool)
+            .await
+            .expect("insert category");
+    }
+
+    async fn category_names(pool: &SqlitePool) -> Vec<String> {
+        sqlx::query_as::<_, (String,)>("SELECT name FROM categories ORDER BY id")
+            .fetch_all(pool)
+            .await
+            .expect("select categories")
+            .into_iter()
+            .map(|(name,)| name)
+            .collect()
+    }
+
+    #[test]
+    fn names_round_trip_and_reject_strangers() {
+        let name = file_name("20261018-093000", BackupKind::PreRestore, 2);
+        assert_eq!(name, "peeky-20261018-093000-pre_restore-2.db");
+        let parsed = parse_name(&name).unwrap();
+        assert_eq!(parsed.kind, BackupKind::PreRestore);
+        assert_eq!(parsed.seq, 2);
+        assert_eq!(format_stamp(parsed.stamp), "2026-10-18 09:30:00");
+
+        for name in [
+            "peeky.db",
+            "peeky-20261018-093000-weekly.db",
+            "peeky-2026101-0930000-manual.db",
+            "peeky-20261018-093000-manual-01.db",
+            "../peeky-20261018-093000-manual.db",
+            "peeky-20261018-093000-manual.db.restoring",
+        ] {
+            assert!(parse_name(name).is_none(), "{name}");
+        }
+    }
+
+    #[test]
+    fn backups_are_due_once_the_interval_has_passed() {
+        let now = SystemTime::now();
+        let day = Duration::from_secs(24 * 3600);
+
+        assert!(is_due(None, now, day));
+        assert!(!is_due(Some(now - day / 2), now, day));
+        assert!(is_due(Some(now - day), now, day));
+        assert!(!is_due(Some(now + day), now, day));
+    }
+
+    #[tokio::test]
+    async fn prune_keeps_newest_automatic_and_every_manual() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = setup_db(dir.path()).await;
+        let backups = backups_dir(db.path());
+
+        for _ in 0..3 {
+            create(&db.pool(), &backups, BackupKind::Automatic)
+                .await
+                .expect("automatic backup");
+        }
+        create(&db.pool(), &backups, BackupKind::Manual)
+            .await
+            .expect("manual backup");
+        fs::write(backups.join("notes.txt"), "not a backup").unwrap();
+
+        let listed = list(&backups).unwrap();
+        assert_eq!(listed.len(), 4);
+        assert!(listed.iter().all(|backup| backup.size_bytes > 0));
+        let newest_automatic = listed
+            .iter()
+            .find(|backup| backup.kind == BackupKind::Automatic)
+            .unwrap()
+            .name
+            .clone();
+
+        let removed = prune(&backups, 1).unwrap();
+        assert_eq!(removed.len(), 2);
+        let mut kept: Vec<(BackupKind, String)> = list(&backups)
+            .unwrap()
+            .into_iter()
+            .map(|backup| (backup.kind, backup.name))
+            .collect();
+        kept.sort_by_key(|(kind, _)| kind.as_str());
+        assert_eq!(kept[0], (BackupKind::Automatic, newest_automatic));
+        assert_eq!(kept[1].0, BackupKind::Manual);
+    }
+
+    #[tokio::test]
+    async fn restore_swaps_in_the_backup_and_keeps_a_safety_copy() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = setup_db(dir.path()).await;
+        let backups = backups_dir(db.path());
+
+        add_category(&db.pool(), "Before").await;
+        let backup = create(&db.pool(), &backups, BackupKind::Manual)
+            .await
+            .unwrap();
+        add_category(&db.pool(), "After").await;
+
+        let safety = restore(&db, &backups, &backup.name).await.expect("restore");
+
+        assert_eq!(category_names(&db.pool()).await, vec!["Before"]);
+        assert_eq!(safety.kind, BackupKind::PreRestore);
+        // The safety copy can itself be restored.
+        restore(&db, &backups, &safety.name).await.unwrap();
+        assert_eq!(category_names(&db.pool()).await, vec!["Before", "After"]);
+    }
+
+    #[tokio::test]
+    async fn restore_rejects_missing_and_corrupt_backups() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = setup_db(dir.path()).await;
+        let backups = backups_dir(db.path());
+        add_category(&db.pool(), "Kept").await;
+
+        assert!(restore(&db, &backups, "peeky-20261018-093000-manual.db")
+            .await
+            .is_err());
+        assert!(restore(&db, &backups, "../peeky.db").await.is_err());
+
+        fs::create_dir_all(&backups).unwrap();
+        let corrupt = "peeky-20261018-093000-manual.db";
+        fs::write(backups.join(corrupt), b"definitely not sqlite").unwrap();
+        assert!(restore(&db, &backups, corrupt).await.is_err());
+
+        // Nothing was replaced and no safety copy was taken.
+        assert_eq!(category_names(&db.pool()).await, vec!["Kept"]);
+        assert_eq!(list(&backups).unwrap().len(), 1);
+    }
+}
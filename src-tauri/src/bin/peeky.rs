@@ -0,0 +1,5 @@
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    tauri_mac_starter_lib::cli::main()
+}
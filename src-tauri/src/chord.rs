@@ -0,0 +1,202 @@
+//! Peeky's shortcut notation, e.g. `Ctrl+Option+G` or `Shift+Cmd+F12`.
+//!
+//! Modifiers are written in macOS menu order (Ctrl, Option, Shift, Cmd)
+//! followed by a single key. Parsing is lenient about case, modifier
+//! aliases (`alt`, `command`, `super`, ...) and order; [`Chord`]'s `Display`
+//! always produces the canonical spelling, so two chords are the same
+//! shortcut exactly when their canonical strings are equal.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::i18n;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Chord {
+    pub ctrl: bool,
+    pub option: bool,
+    pub shift: bool,
+    pub cmd: bool,
+    /// Canonical key name, e.g. `G`, `1`, `F5`, `Space`, `/`.
+    pub key: String,
+}
+
+const NAMED_KEYS: &[(&str, &[&str])] = &[
+    ("Space", &["space"]),
+    ("Enter", &["enter", "return"]),
+    ("Tab", &["tab"]),
+    ("Esc", &["esc", "escape"]),
+    ("Backspace", &["backspace"]),
+    ("Delete", &["delete", "del"]),
+    ("Insert", &["insert", "ins"]),
+    ("Home", &["home"]),
+    ("End", &["end"]),
+    ("PageUp", &["pageup", "pgup"]),
+    ("PageDown", &["pagedown", "pgdn"]),
+    ("Up", &["up", "arrowup"]),
+    ("Down", &["down", "arrowdown"]),
+    ("Left", &["left", "arrowleft"]),
+    ("Right", &["right", "arrowright"]),
+];
+
+const PUNCTUATION_KEYS: &[char] = &['-', '=', '[', ']', '\\', ';', '\'', ',', '.', '/', '`'];
+
+fn normalize_key(token: &str) -> Option<String> {
+    let lower = token.to_ascii_lowercase();
+
+    let mut chars = token.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        if c.is_ascii_alphanumeric() {
+            return Some(c.to_ascii_uppercase().to_string());
+        }
+        if PUNCTUATION_KEYS.contains(&c) {
+            return Some(c.to_string());
+        }
+        return None;
+    }
+
+    if let Some(n) = lower.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+        if (1..=24).contains(&n) {
+            return Some(format!("F{n}"));
+        }
+    }
+    if let Some(c) = lower.strip_prefix("key").filter(|rest| rest.len() == 1) {
+        return normalize_key(c);
+    }
+    if let Some(c) = lower.strip_prefix("digit").filter(|rest| rest.len() == 1) {
+        return normalize_key(c);
+    }
+
+    NAMED_KEYS
+        .iter()
+        .find(|(_, aliases)| aliases.contains(&lower.as_str()))
+        .map(|(name, _)| name.to_string())
+}
+
+impl FromStr for Chord {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chord = Chord {
+            ctrl: false,
+            option: false,
+            shift: false,
+            cmd: false,
+            key: String::new(),
+        };
+
+        let tokens: Vec<&str> = s.split('+').map(str::trim).collect();
+        let Some((key, modifiers)) = tokens.split_last() else {
+            return Err(i18n::tf("error.shortcut_invalid", &[("shortcut", &s)]));
+        };
+
+        for token in modifiers {
+            let flag = match token.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" | "ctl" | "⌃" => &mut chord.ctrl,
+                "option" | "opt" | "alt" | "⌥" => &mut chord.option,
+                "shift" | "⇧" => &mut chord.shift,
+                "cmd" | "command" | "super" | "meta" | "win" | "⌘" => &mut chord.cmd,
+                _ => {
+                    return Err(i18n::tf(
+                        "error.shortcut_unknown_modifier",
+                        &[("token", token), ("shortcut", &s)],
+                    ))
+                }
+            };
+            if *flag {
+                return Err(i18n::tf(
+                    "error.shortcut_duplicate_modifier",
+                    &[("token", token), ("shortcut", &s)],
+                ));
+            }
+            *flag = true;
+        }
+
+        chord.key = normalize_key(key).ok_or_else(|| {
+            i18n::tf(
+                "error.shortcut_unknown_key",
+                &[("key", key), ("shortcut", &s)],
+            )
+        })?;
+        Ok(chord)
+    }
+}
+
+impl fmt::Display for Chord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (on, name) in [
+            (self.ctrl, "Ctrl"),
+            (self.option, "Option"),
+            (self.shift, "Shift"),
+            (self.cmd, "Cmd"),
+        ] {
+            if on {
+                write!(f, "{name}+")?;
+            }
+        }
+        f.write_str(&self.key)
+    }
+}
+
+impl Chord {
+    pub fn has_modifier(&self) -> bool {
+        self.ctrl || self.option || self.shift || self.cmd
+    }
+
+    /// Spelling understood by the global-shortcut plugin's parser.
+    pub fn to_accelerator(&self) -> String {
+        let mut parts = Vec::new();
+        if self.ctrl {
+            parts.push("Control");
+        }
+        if self.option {
+            parts.push("Alt");
+        }
+        if self.shift {
+            parts.push("Shift");
+        }
+        if self.cmd {
+            parts.push("Super");
+        }
+        parts.push(&self.key);
+        parts.join("+")
+    }
+}
+
+/// Parse and re-print a shortcut in canonical notation.
+pub fn normalize(shortcut: &str) -> Result<String, String> {
+    shortcut.parse::<Chord>().map(|chord| chord.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_aliases_case_and_order() {
+        assert_eq!(normalize("ctrl+alt+g").unwrap(), "Ctrl+Option+G");
+        assert_eq!(normalize("Option + Control + G").unwrap(), "Ctrl+Option+G");
+        assert_eq!(normalize("command+shift+f12").unwrap(), "Shift+Cmd+F12");
+        assert_eq!(normalize("Super+KeyK").unwrap(), "Cmd+K");
+        assert_eq!(normalize("ctrl+Digit1").unwrap(), "Ctrl+1");
+        assert_eq!(normalize("cmd+escape").unwrap(), "Cmd+Esc");
+        assert_eq!(normalize("cmd+/").unwrap(), "Cmd+/");
+    }
+
+    #[test]
+    fn rejects_unknown_and_duplicate_tokens() {
+        assert!(normalize("hyper+G").is_err());
+        assert!(normalize("ctrl+ctrl+G").is_err());
+        assert!(normalize("ctrl+").is_err());
+        assert!(normalize("ctrl+Banana").is_err());
+        assert!(normalize("").is_err());
+    }
+
+    #[test]
+    fn accelerator_uses_plugin_names() {
+        let chord: Chord = "Ctrl+Option+Shift+Cmd+G".parse().unwrap();
+        assert_eq!(chord.to_accelerator(), "Control+Alt+Shift+Super+G");
+        assert!(chord.has_modifier());
+        assert!(!"F5".parse::<Chord>().unwrap().has_modifier());
+    }
+}
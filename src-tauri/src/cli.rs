@@ -0,0 +1,507 @@
+//! The `peeky` command-line tool (`src/bin/peeky.rs`).
+//!
+//! Works on the app's own `peeky.db` through the same `*_by_pool` functions
+//! as the Tauri commands, so both can be used side by side. Output is meant
+//! for people by default and for scripts with `--json`.
+//!
+//! `show`, `hide`, `toggle`, `open` and `reload` go to the running app over
+//! its control socket instead of the database.
+
+use std::io::{self, IsTerminal, Read};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use clap::{Parser, Subcommand, ValueEnum};
+use serde::Serialize;
+use sqlx::SqlitePool;
+
+use crate::clock::SystemClock;
+use crate::commands::categories::{
+    create_category_by_pool, find_category_by_name_by_pool, get_categories_by_pool,
+    CreateCategoryInput,
+};
+use crate::commands::items::{
+    create_item_by_pool, get_all_items_by_pool, get_items_by_pool, search_items_by_pool,
+    CreateItemInput, ItemKind,
+};
+use crate::commands::library::{CheatsheetFormat, ImportMode, KeymapFormat, SnippetFormat};
+use crate::commands::settings::get_settings_by_pool;
+use crate::control::{self, ControlRequest};
+use crate::db;
+use crate::i18n;
+use crate::importers;
+use crate::library_file::{self, LibraryFile};
+
+/// Must match `identifier` in `tauri.conf.json`; the app keeps its data in
+/// a directory with this name.
+const APP_IDENTIFIER: &str = "com.peeky.app";
+
+#[derive(Debug, Parser)]
+#[command(name = "peeky", version, about = "Look up and add Peeky memos")]
+pub struct Cli {
+    /// Database to use instead of the app's own.
+    #[arg(long, global = true, env = "PEEKY_DB")]
+    db: Option<PathBuf>,
+    /// Print JSON instead of text.
+    #[arg(long, global = true)]
+    json: bool,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// List categories, or the items of one category.
+    List { category: Option<String> },
+    /// Print the value of the item matching QUERY (label first, then any
+    /// text).
+    Get { query: String },
+    /// Add an item, creating its category if needed. A VALUE of `-` is
+    /// read from piped stdin.
+    Add {
+        category: String,
+        label: String,
+        value: Option<String>,
+    },
+    /// Import a file, by extension: Markdown, JSON (ours or a Raycast
+    /// export), `.alfredsnippets`, an Espanso `.yml`, VS Code's
+    /// `keybindings.json`, a JetBrains keymap `.xml` or browser bookmarks
+    /// `.html`. A directory is read as tldr pages if it has `.md` files,
+    /// else as cheat sheets.
+    Import {
+        file: PathBuf,
+        /// Replace the value of items whose label is already in the
+        /// category instead of adding them again.
+        #[arg(long)]
+        update: bool,
+    },
+    /// Write the whole library to stdout.
+    Export {
+        #[arg(long, value_enum, default_value_t = Format::Markdown)]
+        format: Format,
+    },
+    #[command(flatten)]
+    Control(ControlCommand),
+}
+
+#[derive(Debug, Subcommand)]
+enum ControlCommand {
+    /// Show the overlay in the running app, optionally with one category.
+    Show { category: Option<String> },
+    /// Hide the overlay.
+    Hide,
+    /// Show the overlay if hidden, hide it otherwise.
+    Toggle,
+    /// Open the main window.
+    Open,
+    /// Make the running app re-read the database.
+    Reload,
+}
+
+impl From<ControlCommand> for ControlRequest {
+    fn from(command: ControlCommand) -> Self {
+        match command {
+            ControlCommand::Show { category } => ControlRequest::ShowOverlay { category },
+            ControlCommand::Hide => ControlRequest::HideOverlay,
+            ControlCommand::Toggle => ControlRequest::ToggleOverlay,
+            ControlCommand::Open => ControlRequest::OpenMain,
+            ControlCommand::Reload => ControlRequest::Reload,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Format {
+    Markdown,
+    Json,
+}
+
+/// Where the app keeps `peeky.db`: Tauri's app data directory.
+fn default_db_path() -> Option<PathBuf> {
+    let home = std::env::var_os("HOME").map(PathBuf::from);
+    let data_dir = if cfg!(target_os = "macos") {
+        home?.join("Library/Application Support")
+    } else if cfg!(windows) {
+        PathBuf::from(std::env::var_os("APPDATA")?)
+    } else {
+        std::env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .or_else(|| Some(home?.join(".local/share")))?
+    };
+    Some(data_dir.join(APP_IDENTIFIER).join("peeky.db"))
+}
+
+fn to_json<T: Serialize>(value: &T) -> Result<String, String> {
+    serde_json::to_string_pretty(value).map_err(|e| e.to_string())
+}
+
+fn first_line(value: &str) -> &str {
+    value.lines().next().unwrap_or("")
+}
+
+/// Run one command and return what to print.
+pub async fn execute(db: &SqlitePool, cli: Cli) -> Result<String, String> {
+    match cli.command {
+        Command::List { category: None } => {
+            let categories = get_categories_by_pool(db).await?;
+            if cli.json {
+                return to_json(&categories);
+            }
+            let mut lines = Vec::new();
+            for category in categories {
+                let count = get_items_by_pool(db, category.id).await?.len();
+                lines.push(format!("{} ({count})", category.name));
+            }
+            Ok(lines.join("\n"))
+        }
+        Command::List {
+            category: Some(name),
+        } => {
+            let category = find_category_by_name_by_pool(db, &name)
+                .await?
+                .ok_or_else(|| i18n::tf("error.category_name_not_found", &[("name", &name)]))?;
+            let items = get_items_by_pool(db, category.id).await?;
+            if cli.json {
+                return to_json(&items);
+            }
+            Ok(items
+                .iter()
+                .map(|item| format!("{}\t{}", item.label, first_line(&item.value)))
+                .collect::<Vec<_>>()
+                .join("\n"))
+        }
+        Command::Get { query } => {
+            let found = search_items_by_pool(db, &query, &SystemClock).await?;
+            let exact: Vec<_> = found
+                .iter()
+                .filter(|item| item.label.eq_ignore_ascii_case(&query))
+                .collect();
+            let matches: Vec<_> = if exact.is_empty() {
+                found.iter().collect()
+            } else {
+                exact
+            };
+            match matches.as_slice() {
+                [] => Err(i18n::tf("error.cli_no_match", &[("query", &query)])),
+                [item] if cli.json => to_json(item),
+                [item] => Ok(item.value.clone()),
+                _ => {
+                    let candidates: Vec<String> = matches
+                        .iter()
+                        .map(|item| format!("  {} / {}", item.category_name, item.label))
+                        .collect();
+                    Err(format!(
+                        "{}\n{}",
+                        i18n::tf(
+                            "error.cli_ambiguous",
+                            &[("query", &query), ("count", &matches.len())],
+                        ),
+                        candidates.join("\n")
+                    ))
+                }
+            }
+        }
+        Command::Add {
+            category,
+            label,
+            value,
+        } => {
+            let category = match find_category_by_name_by_pool(db, &category).await? {
+                Some(existing) => existing,
+                None => create_category_by_pool(db, CreateCategoryInput { name: category }).await?,
+            };
+            let item = create_item_by_pool(
+                db,
+                CreateItemInput {
+                    category_id: category.id,
+                    label,
+                    value,
+                    kind: ItemKind::Text,
+                },
+            )
+            .await?;
+            if cli.json {
+                return to_json(&item);
+            }
+            Ok(i18n::tf(
+                "cli.added",
+                &[("label", &item.label), ("category", &category.name)],
+            ))
+        }
+        Command::Import { file, update } => {
+            let extension = file
+                .extension()
+                .map(|ext| ext.to_string_lossy().to_ascii_lowercase())
+                .unwrap_or_default();
+            // Bookmarks whose URL is already saved.
+            let mut duplicates = 0;
+            let library = match extension.as_str() {
+                _ if file.is_dir() => {
+                    let tldr = importers::cheatsheets::read_dir(CheatsheetFormat::Tldr, &file);
+                    tldr.or_else(|_| {
+                        importers::cheatsheets::read_dir(CheatsheetFormat::Cheat, &file)
+                    })?
+                }
+                "alfredsnippets" => importers::read(SnippetFormat::Alfred, &file)?,
+                "html" | "htm" => {
+                    let mut library = importers::bookmarks::read(&file)?;
+                    let existing = get_all_items_by_pool(db, &SystemClock).await?;
+                    duplicates = importers::bookmarks::remove_duplicates(
+                        &mut library,
+                        existing.iter().map(|item| item.value.as_str()),
+                    );
+                    library
+                }
+                "yml" | "yaml" => importers::read(SnippetFormat::Espanso, &file)?,
+                "xml" => importers::read_keymap(KeymapFormat::Jetbrains, &file)?,
+                _ if file.ends_with("keybindings.json") => {
+                    importers::read_keymap(KeymapFormat::Vscode, &file)?
+                }
+                "json" => {
+                    let text = std::fs::read_to_string(&file).map_err(|e| e.to_string())?;
+                    match serde_json::from_str::<LibraryFile>(&text) {
+                        Ok(library) => library,
+                        // Raycast exports are a bare array of snippets.
+                        Err(err) if text.trim_start().starts_with('[') => {
+                            importers::read(SnippetFormat::Raycast, &file)
+                                .map_err(|_| err.to_string())?
+                        }
+                        Err(err) => return Err(err.to_string()),
+                    }
+                }
+                _ => {
+                    let text = std::fs::read_to_string(&file).map_err(|e| e.to_string())?;
+                    library_file::parse_markdown(&text)
+                }
+            };
+            let mode = if update {
+                ImportMode::Update
+            } else {
+                ImportMode::Merge
+            };
+            let mut summary = library_file::import_by_pool(db, &library, mode).await?;
+            summary.items_skipped += duplicates;
+            if cli.json {
+                return to_json(&summary);
+            }
+            Ok(i18n::tf(
+                "cli.imported",
+                &[
+                    ("items", &summary.items_created),
+                    ("categories", &summary.categories_created),
+                    ("updated", &summary.items_updated),
+                    ("skipped", &summary.items_skipped),
+                ],
+            ))
+        }
+        Command::Export { format } => {
+            let library = library_file::export_by_pool(db).await?;
+            match format {
+                Format::Json => to_json(&library),
+                Format::Markdown => Ok(library_file::to_markdown(&library)),
+            }
+        }
+        Command::Control(_) => unreachable!("control commands do not open the database"),
+    }
+}
+
+async fn open(path: &Path) -> Result<SqlitePool, String> {
+    if !path.is_file() {
+        return Err(i18n::tf(
+            "error.cli_database_missing",
+            &[("path", &path.display())],
+        ));
+    }
+    let pool = db::create_pool(&path.to_string_lossy())
+        .await
+        .map_err(|e| e.to_string())?;
+    db::migrator().run(&pool).await.map_err(|e| e.to_string())?;
+    Ok(pool)
+}
+
+/// The value for `add <category> <label> -`. From a terminal that would
+/// wait for input the user likely does not know to type, so it is refused.
+fn read_value(is_terminal: bool, mut input: impl Read) -> Result<String, String> {
+    if is_terminal {
+        return Err(i18n::t("error.cli_stdin_terminal"));
+    }
+    let mut value = String::new();
+    input
+        .read_to_string(&mut value)
+        .map_err(|e| e.to_string())?;
+    Ok(value.trim_end_matches('\n').to_string())
+}
+
+async fn run(mut cli: Cli) -> Result<String, String> {
+    let path = cli
+        .db
+        .clone()
+        .or_else(default_db_path)
+        .ok_or_else(|| i18n::t("error.cli_database_unknown"))?;
+    let endpoint = control::endpoint(&path);
+    if let Command::Control(command) = cli.command {
+        let response = control::send(&endpoint, &command.into()).await?;
+        return match response.error {
+            Some(err) => Err(err),
+            None => Ok(String::new()),
+        };
+    }
+
+    let pool = open(&path).await?;
+    if let Ok(settings) = get_settings_by_pool(&pool).await {
+        i18n::set_locale(&settings.locale);
+    }
+
+    if let Command::Add { value, .. } = &mut cli.command {
+        if value.as_deref() == Some("-") {
+            let stdin = io::stdin();
+            *value = Some(read_value(stdin.is_terminal(), stdin)?);
+        }
+    }
+    let changes_library = matches!(cli.command, Command::Add { .. } | Command::Import { .. });
+    let output = execute(&pool, cli).await;
+    pool.close().await;
+    if changes_library && output.is_ok() {
+        // Best effort: the app picks the changes up on its next reload anyway.
+        let _ = control::send(&endpoint, &ControlRequest::Reload).await;
+    }
+    output
+}
+
+/// Entry point of the `peeky` binary.
+pub fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let runtime = match tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+    {
+        Ok(runtime) => runtime,
+        Err(err) => {
+            eprintln!("peeky: {err}");
+            return ExitCode::FAILURE;
+        }
+    };
+    match runtime.block_on(run(cli)) {
+        Ok(output) => {
+            if !output.is_empty() {
+                println!("{}", output.trim_end_matches('\n'));
+            }
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("peeky: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn setup_db() -> SqlitePool {
+        let db = SqlitePool::connect("sqlite::memory:")
+            .await
+            .expect("connect sqlite memory");
+        sqlx::query(include_str!("../migrations/002_peeky_domain.sql"))
+            .execute(&db)
+            .await
+            .expect("run migration 002");
+        sqlx::query(include_str!("../migrations/009_item_kind.sql"))
+            .execute(&db)
+            .await
+            .expect("run migration 009");
+        sqlx::query(include_str!("../migrations/010_category_links.sql"))
+            .execute(&db)
+            .await
+            .expect("run migration 010");
+        sqlx::query(include_str!("../migrations/012_item_expiry.sql"))
+            .execute(&db)
+            .await
+            .expect("run migration 012");
+        db
+    }
+
+    async fn peeky(db: &SqlitePool, args: &[&str]) -> Result<String, String> {
+        let cli = Cli::try_parse_from(std::iter::once("peeky").chain(args.iter().copied()))
+            .map_err(|e| e.to_string())?;
+        execute(db, cli).await
+    }
+
+    #[tokio::test]
+    async fn add_creates_the_category_and_get_prints_the_value() {
+        let db = setup_db().await;
+        peeky(&db, &["add", "Git", "Undo last commit", "git reset HEAD~1"])
+            .await
+            .unwrap();
+        peeky(&db, &["add", "git", "Undo add", "git restore --staged ."])
+            .await
+            .unwrap();
+
+        assert_eq!(peeky(&db, &["list"]).await.unwrap(), "Git (2)");
+        assert_eq!(
+            peeky(&db, &["get", "undo last commit"]).await.unwrap(),
+            "git reset HEAD~1"
+        );
+        // Two items contain "undo" and neither is labelled exactly that.
+        let ambiguous = peeky(&db, &["get", "undo"]).await.unwrap_err();
+        assert!(ambiguous.contains("Git / Undo add"));
+        assert!(peeky(&db, &["get", "rebase"]).await.is_err());
+    }
+
+    #[test]
+    fn dash_reads_piped_stdin_but_not_a_terminal() {
+        assert_eq!(
+            read_value(false, "line one\nline two\n".as_bytes()).unwrap(),
+            "line one\nline two"
+        );
+        assert!(read_value(true, "ignored".as_bytes()).is_err());
+    }
+
+    #[tokio::test]
+    async fn json_output_parses() {
+        let db = setup_db().await;
+        peeky(&db, &["add", "VPN", "Connect", "wg-quick up office"])
+            .await
+            .unwrap();
+
+        let items: serde_json::Value =
+            serde_json::from_str(&peeky(&db, &["--json", "list", "vpn"]).await.unwrap()).unwrap();
+        assert_eq!(items[0]["value"], "wg-quick up office");
+        let exported: LibraryFile =
+            serde_json::from_str(&peeky(&db, &["export", "--format", "json"]).await.unwrap())
+                .unwrap();
+        assert_eq!(exported.categories[0].name, "VPN");
+    }
+
+    #[tokio::test]
+    async fn import_reads_markdown_and_json_files() {
+        let db = setup_db().await;
+        let dir = tempfile::tempdir().unwrap();
+        let markdown = dir.path().join("memos.md");
+        std::fs::write(&markdown, "# Docker\n- Running: `docker ps`\n").unwrap();
+        let json = dir.path().join("memos.json");
+        std::fs::write(
+            &json,
+            r#"{"categories":[{"name":"Docker","items":[{"label":"Logs","value":"docker logs -f"}]}]}"#,
+        )
+        .unwrap();
+
+        peeky(&db, &["import", &markdown.to_string_lossy()])
+            .await
+            .unwrap();
+        peeky(&db, &["import", &json.to_string_lossy()])
+            .await
+            .unwrap();
+        let raycast = dir.path().join("Docker.json");
+        std::fs::write(&raycast, r#"[{"name":"Images","text":"docker images"}]"#).unwrap();
+        peeky(&db, &["import", &raycast.to_string_lossy()])
+            .await
+            .unwrap();
+
+        assert_eq!(
+            peeky(&db, &["export"]).await.unwrap(),
+            "# Docker\n\n- Running: `docker ps`\n- Logs: `docker logs -f`\n- Images: `docker images`\n"
+        );
+    }
+}
@@ -0,0 +1,86 @@
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+/// Source of monotonic time for schedulers and state machines, and of wall
+/// time for things due at a date, like item expiry.
+///
+/// Production code uses [`SystemClock`]; tests drive time by hand with
+/// [`ManualClock`] so timing behaviour is deterministic.
+pub trait Clock: Send + Sync {
+    fn now(&self) -> Instant;
+
+    fn system_now(&self) -> SystemTime;
+
+    /// [`Clock::system_now`] in whole seconds since the Unix epoch, which
+    /// SQLite's `datetime(?, 'unixepoch')` turns into a stored timestamp.
+    fn unix_secs(&self) -> i64 {
+        self.system_now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since| since.as_secs() as i64)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn system_now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
+
+#[cfg(test)]
+pub use manual::ManualClock;
+
+#[cfg(test)]
+mod manual {
+    use std::sync::Mutex;
+    use std::time::{Duration, Instant, SystemTime};
+
+    use super::Clock;
+
+    /// Clock that only moves when told to.
+    #[derive(Debug)]
+    pub struct ManualClock {
+        base: Instant,
+        system_base: SystemTime,
+        offset: Mutex<Duration>,
+    }
+
+    impl ManualClock {
+        pub fn new() -> Self {
+            Self {
+                base: Instant::now(),
+                system_base: SystemTime::now(),
+                offset: Mutex::new(Duration::ZERO),
+            }
+        }
+
+        pub fn advance(&self, by: Duration) {
+            *self.offset.lock().unwrap() += by;
+        }
+
+        pub fn advance_ms(&self, ms: u64) {
+            self.advance(Duration::from_millis(ms));
+        }
+    }
+
+    impl Default for ManualClock {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    impl Clock for ManualClock {
+        fn now(&self) -> Instant {
+            self.base + *self.offset.lock().unwrap()
+        }
+
+        fn system_now(&self) -> SystemTime {
+            self.system_base + *self.offset.lock().unwrap()
+        }
+    }
+}
@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::appearance::Appearance;
+
+/// The `theme` setting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Theme {
+    System,
+    Light,
+    Dark,
+}
+
+/// The theme windows actually render with once `system` is resolved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EffectiveTheme {
+    Light,
+    Dark,
+}
+
+#[tauri::command]
+pub async fn get_effective_theme(
+    appearance: State<'_, Appearance>,
+) -> Result<EffectiveTheme, String> {
+    Ok(appearance.effective())
+}
@@ -0,0 +1,142 @@
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::autostart::{self, Drift, LoginItem};
+use crate::commands::settings::get_settings_by_pool;
+use crate::db::Db;
+use crate::i18n;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AutostartDrift {
+    RemovedExternally,
+    AddedExternally,
+    Outdated,
+}
+
+impl From<Drift> for AutostartDrift {
+    fn from(drift: Drift) -> Self {
+        match drift {
+            Drift::RemovedExternally => AutostartDrift::RemovedExternally,
+            Drift::AddedExternally => AutostartDrift::AddedExternally,
+            Drift::Outdated => AutostartDrift::Outdated,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AutostartStatus {
+    /// False on platforms without a login-item implementation.
+    pub supported: bool,
+    /// The `launch_on_login` setting.
+    pub enabled: bool,
+    /// Whether the login item currently exists on disk.
+    pub installed: bool,
+    pub drift: Option<AutostartDrift>,
+    pub path: Option<String>,
+}
+
+/// Managed state holding the login item for this install.
+pub struct Autostart {
+    item: Option<LoginItem>,
+}
+
+impl Autostart {
+    pub fn new(item: Option<LoginItem>) -> Self {
+        Self { item }
+    }
+
+    /// Bring the login item in line with the setting at launch, reporting
+    /// (but not undoing) changes the user made outside the app.
+    pub fn reconcile_on_startup(&self, enabled: bool) {
+        let Some(item) = &self.item else {
+            return;
+        };
+        match autostart::reconcile_on_startup(item, enabled) {
+            Ok(Some(drift)) => eprintln!(
+                "login item {} out of sync with launch_on_login: {drift:?}",
+                item.path.display()
+            ),
+            Ok(None) => {}
+            Err(err) => eprintln!("failed to update login item: {err}"),
+        }
+    }
+
+    /// Install or remove the login item.
+    pub fn apply(&self, enabled: bool) -> Result<(), String> {
+        match &self.item {
+            Some(item) => autostart::apply(item, enabled).map_err(|e| e.to_string()),
+            None if enabled => Err(i18n::t("error.autostart_unsupported")),
+            None => Ok(()),
+        }
+    }
+
+    pub fn status(&self, enabled: bool) -> AutostartStatus {
+        match &self.item {
+            Some(item) => AutostartStatus {
+                supported: true,
+                enabled,
+                installed: item.is_installed(),
+                drift: autostart::detect_drift(item, enabled).map(AutostartDrift::from),
+                path: Some(item.path.to_string_lossy().to_string()),
+            },
+            None => AutostartStatus {
+                supported: false,
+                enabled,
+                installed: false,
+                drift: None,
+                path: None,
+            },
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn get_autostart_status(
+    db: State<'_, Db>,
+    autostart: State<'_, Autostart>,
+) -> Result<AutostartStatus, String> {
+    let settings = get_settings_by_pool(&db.pool()).await?;
+    Ok(autostart.status(settings.launch_on_login))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::autostart::LoginItemKind;
+
+    #[test]
+    fn status_reports_drift_until_reapplied() {
+        let home = tempfile::tempdir().unwrap();
+        let autostart = Autostart::new(Some(LoginItem::new(
+            LoginItemKind::XdgAutostart,
+            "com.peeky.app",
+            home.path(),
+            None,
+            Path::new("/usr/bin/peeky"),
+        )));
+
+        let status = autostart.status(true);
+        assert!(status.supported);
+        assert!(!status.installed);
+        assert_eq!(status.drift, Some(AutostartDrift::RemovedExternally));
+
+        autostart.apply(true).unwrap();
+        let status = autostart.status(true);
+        assert!(status.installed);
+        assert_eq!(status.drift, None);
+    }
+
+    #[test]
+    fn unsupported_platform_only_accepts_disabling() {
+        let autostart = Autostart::new(None);
+
+        assert!(autostart.apply(true).is_err());
+        assert!(autostart.apply(false).is_ok());
+        let status = autostart.status(false);
+        assert!(!status.supported);
+        assert_eq!(status.path, None);
+    }
+}
@@ -0,0 +1,68 @@
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, State};
+
+use crate::backup;
+use crate::db::Db;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BackupKind {
+    /// Written by the scheduler; pruned to `backup_retention`.
+    Automatic,
+    /// Requested by the user; never pruned.
+    Manual,
+    /// Taken just before a restore; pruned like automatic backups.
+    PreRestore,
+}
+
+impl BackupKind {
+    pub const ALL: [BackupKind; 3] = [
+        BackupKind::Automatic,
+        BackupKind::Manual,
+        BackupKind::PreRestore,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            BackupKind::Automatic => "automatic",
+            BackupKind::Manual => "manual",
+            BackupKind::PreRestore => "pre_restore",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        BackupKind::ALL.into_iter().find(|kind| kind.as_str() == s)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupInfo {
+    /// File name inside the backups directory; pass it to `restore_backup`.
+    pub name: String,
+    pub kind: BackupKind,
+    /// UTC, formatted like the other timestamps (`2026-10-18 09:30:00`).
+    pub created_at: String,
+    pub size_bytes: u64,
+}
+
+#[tauri::command]
+pub async fn list_backups(db: State<'_, Db>) -> Result<Vec<BackupInfo>, String> {
+    backup::list(&backup::backups_dir(db.path()))
+}
+
+#[tauri::command]
+pub async fn create_backup_now(db: State<'_, Db>) -> Result<BackupInfo, String> {
+    backup::create(
+        &db.pool(),
+        &backup::backups_dir(db.path()),
+        BackupKind::Manual,
+    )
+    .await
+}
+
+/// Replace the database with a backup. Returns the safety backup taken of
+/// the database it replaced.
+#[tauri::command]
+pub async fn restore_backup(app: AppHandle, name: String) -> Result<BackupInfo, String> {
+    backup::restore_and_reload(&app, &name).await
+}
@@ -1,6 +1,10 @@
 use serde::{Deserialize, Serialize};
-use sqlx::SqlitePool;
-use tauri::State;
+use sqlx::{SqliteConnection, SqlitePool};
+use tauri::{AppHandle, State};
+
+use crate::db::Db;
+use crate::events::{self, ChangeKind, LibraryChanged};
+use crate::i18n;
 
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct Category {
@@ -25,22 +29,39 @@ pub struct UpdateCategoryInput {
 
 // --- Pool-based functions (testable) ---
 
-pub async fn get_categories_by_pool(db: &SqlitePool) -> Result<Vec<Category>, String> {
+pub async fn get_categories_by_conn(db: &mut SqliteConnection) -> Result<Vec<Category>, String> {
     sqlx::query_as::<_, Category>(
         "SELECT id, name, sort_order, created_at, updated_at FROM categories ORDER BY sort_order, id",
     )
-    .fetch_all(db)
+    .fetch_all(&mut *db)
     .await
     .map_err(|e| e.to_string())
 }
 
-pub async fn create_category_by_pool(
+pub async fn get_categories_by_pool(db: &SqlitePool) -> Result<Vec<Category>, String> {
+    let mut conn = db.acquire().await.map_err(|e| e.to_string())?;
+    get_categories_by_conn(&mut conn).await
+}
+
+/// The category called `name`, ignoring ASCII case, for callers that only
+/// know names (the CLI, the control socket, deep links).
+pub async fn find_category_by_name_by_pool(
     db: &SqlitePool,
+    name: &str,
+) -> Result<Option<Category>, String> {
+    Ok(get_categories_by_pool(db)
+        .await?
+        .into_iter()
+        .find(|category| category.name.eq_ignore_ascii_case(name)))
+}
+
+pub async fn create_category_by_conn(
+    db: &mut SqliteConnection,
     input: CreateCategoryInput,
 ) -> Result<Category, String> {
     let max_order: Option<(i64,)> =
         sqlx::query_as("SELECT COALESCE(MAX(sort_order), -1) FROM categories")
-            .fetch_optional(db)
+            .fetch_optional(&mut *db)
             .await
             .map_err(|e| e.to_string())?;
     let next_order = max_order.map(|r| r.0 + 1).unwrap_or(0);
@@ -48,7 +69,7 @@ pub async fn create_category_by_pool(
     let id = sqlx::query("INSERT INTO categories (name, sort_order) VALUES (?, ?)")
         .bind(&input.name)
         .bind(next_order)
-        .execute(db)
+        .execute(&mut *db)
         .await
         .map_err(|e| e.to_string())?
         .last_insert_rowid();
@@ -57,11 +78,19 @@ pub async fn create_category_by_pool(
         "SELECT id, name, sort_order, created_at, updated_at FROM categories WHERE id = ?",
     )
     .bind(id)
-    .fetch_one(db)
+    .fetch_one(&mut *db)
     .await
     .map_err(|e| e.to_string())
 }
 
+pub async fn create_category_by_pool(
+    db: &SqlitePool,
+    input: CreateCategoryInput,
+) -> Result<Category, String> {
+    let mut conn = db.acquire().await.map_err(|e| e.to_string())?;
+    create_category_by_conn(&mut conn, input).await
+}
+
 pub async fn update_category_by_pool(
     db: &SqlitePool,
     input: UpdateCategoryInput,
@@ -73,7 +102,7 @@ pub async fn update_category_by_pool(
     .fetch_optional(db)
     .await
     .map_err(|e| e.to_string())?
-    .ok_or_else(|| format!("Category {} not found", input.id))?;
+    .ok_or_else(|| i18n::tf("error.category_not_found", &[("id", &input.id)]))?;
 
     let name = input.name.unwrap_or(current.name);
     let sort_order = input.sort_order.unwrap_or(current.sort_order);
@@ -105,7 +134,7 @@ pub async fn delete_category_by_pool(db: &SqlitePool, id: i64) -> Result<(), Str
         .map_err(|e| e.to_string())?;
 
     if result.rows_affected() == 0 {
-        return Err(format!("Category {} not found", id));
+        return Err(i18n::tf("error.category_not_found", &[("id", &id)]));
     }
     Ok(())
 }
@@ -127,34 +156,57 @@ pub async fn reorder_categories_by_pool(db: &SqlitePool, ids: Vec<i64>) -> Resul
 // --- Tauri commands (thin wrappers) ---
 
 #[tauri::command]
-pub async fn get_categories(db: State<'_, SqlitePool>) -> Result<Vec<Category>, String> {
-    get_categories_by_pool(db.inner()).await
+pub async fn get_categories(db: State<'_, Db>) -> Result<Vec<Category>, String> {
+    get_categories_by_pool(&db.pool()).await
 }
 
 #[tauri::command]
 pub async fn create_category(
-    db: State<'_, SqlitePool>,
+    app: AppHandle,
+    db: State<'_, Db>,
     input: CreateCategoryInput,
 ) -> Result<Category, String> {
-    create_category_by_pool(db.inner(), input).await
+    let category = create_category_by_pool(&db.pool(), input).await?;
+    events::emit(
+        &app,
+        LibraryChanged::categories(ChangeKind::Created, vec![category.id]),
+    );
+    Ok(category)
 }
 
 #[tauri::command]
 pub async fn update_category(
-    db: State<'_, SqlitePool>,
+    app: AppHandle,
+    db: State<'_, Db>,
     input: UpdateCategoryInput,
 ) -> Result<Category, String> {
-    update_category_by_pool(db.inner(), input).await
+    let category = update_category_by_pool(&db.pool(), input).await?;
+    events::emit(
+        &app,
+        LibraryChanged::categories(ChangeKind::Updated, vec![category.id]),
+    );
+    Ok(category)
 }
 
 #[tauri::command]
-pub async fn delete_category(db: State<'_, SqlitePool>, id: i64) -> Result<(), String> {
-    delete_category_by_pool(db.inner(), id).await
+pub async fn delete_category(app: AppHandle, db: State<'_, Db>, id: i64) -> Result<(), String> {
+    delete_category_by_pool(&db.pool(), id).await?;
+    events::emit(
+        &app,
+        LibraryChanged::categories(ChangeKind::Deleted, vec![id]),
+    );
+    Ok(())
 }
 
 #[tauri::command]
-pub async fn reorder_categories(db: State<'_, SqlitePool>, ids: Vec<i64>) -> Result<(), String> {
-    reorder_categories_by_pool(db.inner(), ids).await
+pub async fn reorder_categories(
+    app: AppHandle,
+    db: State<'_, Db>,
+    ids: Vec<i64>,
+) -> Result<(), String> {
+    reorder_categories_by_pool(&db.pool(), ids.clone()).await?;
+    events::emit(&app, LibraryChanged::categories(ChangeKind::Reordered, ids));
+    Ok(())
 }
 
 #[cfg(test)]
@@ -0,0 +1,259 @@
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use tauri::State;
+
+use crate::commands::categories::Category;
+use crate::commands::items::Item;
+use crate::db::Db;
+use crate::events::LibraryEntity;
+
+/// Changes returned per call when the caller does not say.
+const DEFAULT_LIMIT: u32 = 500;
+
+/// An entity that was deleted after the cursor.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Tombstone {
+    pub entity: LibraryEntity,
+    pub id: i64,
+}
+
+/// What changed after a cursor. Each entity appears once, with its current
+/// state: in `categories`/`items` if it exists, in `deleted` if not.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChangeSet {
+    /// Pass to the next call to continue from here.
+    pub cursor: i64,
+    pub categories: Vec<Category>,
+    pub items: Vec<Item>,
+    pub deleted: Vec<Tombstone>,
+    /// More changes are waiting; call again with `cursor`.
+    pub has_more: bool,
+    /// The cursor was ahead of the feed (e.g. after restoring a backup), so
+    /// this starts over from the beginning. Drop anything cached.
+    pub reset: bool,
+}
+
+// --- Pool-based functions (testable) ---
+
+/// Changes after `cursor` (0 for everything), oldest first, at most `limit`
+/// entities.
+pub async fn get_changes_since_by_pool(
+    db: &SqlitePool,
+    cursor: i64,
+    limit: u32,
+) -> Result<ChangeSet, String> {
+    let (latest,): (i64,) = sqlx::query_as("SELECT COALESCE(MAX(seq), 0) FROM changes")
+        .fetch_one(db)
+        .await
+        .map_err(|e| e.to_string())?;
+    let limit = limit.max(1);
+    let reset = cursor > latest;
+    let cursor = if reset { 0 } else { cursor.max(0) };
+
+    // Only the newest change of each entity matters.
+    let mut rows: Vec<(i64, String, i64, String)> = sqlx::query_as(
+        "SELECT c.seq, c.entity, c.entity_id, c.op FROM changes c
+         WHERE c.seq > ? AND c.seq = (
+           SELECT MAX(seq) FROM changes WHERE entity = c.entity AND entity_id = c.entity_id
+         )
+         ORDER BY c.seq LIMIT ?",
+    )
+    .bind(cursor)
+    .bind(i64::from(limit) + 1)
+    .fetch_all(db)
+    .await
+    .map_err(|e| e.to_string())?;
+    let has_more = rows.len() > limit as usize;
+    rows.truncate(limit as usize);
+
+    let mut set = ChangeSet {
+        cursor: rows.last().map_or(cursor, |row| row.0),
+        categories: Vec::new(),
+        items: Vec::new(),
+        deleted: Vec::new(),
+        has_more,
+        reset,
+    };
+    let mut category_ids = Vec::new();
+    let mut item_ids = Vec::new();
+    for (_, entity, id, op) in rows {
+        let entity = match entity.as_str() {
+            "category" => LibraryEntity::Category,
+            _ => LibraryEntity::Item,
+        };
+        match (op.as_str(), entity) {
+            ("delete", _) => set.deleted.push(Tombstone { entity, id }),
+            (_, LibraryEntity::Category) => category_ids.push(id),
+            (_, LibraryEntity::Item) => item_ids.push(id),
+        }
+    }
+
+    let ids_json = |ids: &[i64]| serde_json::to_string(ids).map_err(|e| e.to_string());
+    set.categories = sqlx::query_as::<_, Category>(
+        "SELECT id, name, sort_order, created_at, updated_at FROM categories
+         WHERE id IN (SELECT value FROM json_each(?)) ORDER BY sort_order, id",
+    )
+    .bind(ids_json(&category_ids)?)
+    .fetch_all(db)
+    .await
+    .map_err(|e| e.to_string())?;
+    set.items = sqlx::query_as::<_, Item>(
+        "SELECT id, category_id, label, value, sort_order, created_at, updated_at, expires_at, kind
+         FROM items
+         WHERE id IN (SELECT value FROM json_each(?)) ORDER BY category_id, sort_order, id",
+    )
+    .bind(ids_json(&item_ids)?)
+    .fetch_all(db)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(set)
+}
+
+// --- Tauri commands ---
+
+#[tauri::command]
+pub async fn get_changes_since(
+    db: State<'_, Db>,
+    cursor: i64,
+    limit: Option<u32>,
+) -> Result<ChangeSet, String> {
+    get_changes_since_by_pool(&db.pool(), cursor, limit.unwrap_or(DEFAULT_LIMIT)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::categories::{
+        create_category_by_pool, delete_category_by_pool, CreateCategoryInput,
+    };
+    use crate::commands::items::{
+        create_item_by_pool, delete_item_by_pool, update_item_by_pool, CreateItemInput, ItemKind,
+        UpdateItemInput,
+    };
+
+    async fn setup_db() -> SqlitePool {
+        let db = SqlitePool::connect("sqlite::memory:")
+            .await
+            .expect("connect sqlite memory");
+        for (name, sql) in [
+            ("002", include_str!("../../migrations/002_peeky_domain.sql")),
+            ("008", include_str!("../../migrations/008_change_feed.sql")),
+            ("009", include_str!("../../migrations/009_item_kind.sql")),
+            (
+                "010",
+                include_str!("../../migrations/010_category_links.sql"),
+            ),
+            ("012", include_str!("../../migrations/012_item_expiry.sql")),
+        ] {
+            sqlx::query(sql)
+                .execute(&db)
+                .await
+                .unwrap_or_else(|e| panic!("run migration {name}: {e}"));
+        }
+        db
+    }
+
+    async fn create_category(db: &SqlitePool, name: &str) -> i64 {
+        create_category_by_pool(
+            db,
+            CreateCategoryInput {
+                name: name.to_string(),
+            },
+        )
+        .await
+        .expect("create category")
+        .id
+    }
+
+    async fn create_item(db: &SqlitePool, category_id: i64, label: &str) -> i64 {
+        create_item_by_pool(
+            db,
+            CreateItemInput {
+                category_id,
+                label: label.to_string(),
+                value: None,
+                kind: ItemKind::Text,
+            },
+        )
+        .await
+        .expect("create item")
+        .id
+    }
+
+    #[tokio::test]
+    async fn reports_each_entity_once_with_its_current_state() {
+        let db = setup_db().await;
+        let category = create_category(&db, "Git").await;
+        let item = create_item(&db, category, "status").await;
+        let gone = create_item(&db, category, "stash").await;
+        update_item_by_pool(
+            &db,
+            UpdateItemInput {
+                id: item,
+                label: Some("status -sb".to_string()),
+                value: None,
+                sort_order: None,
+            },
+        )
+        .await
+        .unwrap();
+        delete_item_by_pool(&db, gone).await.unwrap();
+
+        let set = get_changes_since_by_pool(&db, 0, 100).await.unwrap();
+        assert_eq!(set.categories.len(), 1);
+        assert_eq!(set.items.len(), 1);
+        assert_eq!(set.items[0].label, "status -sb");
+        assert_eq!(
+            set.deleted,
+            vec![Tombstone {
+                entity: LibraryEntity::Item,
+                id: gone
+            }]
+        );
+        assert!(!set.has_more && !set.reset);
+
+        let next = get_changes_since_by_pool(&db, set.cursor, 100)
+            .await
+            .unwrap();
+        assert_eq!(next.cursor, set.cursor);
+        assert!(next.categories.is_empty() && next.items.is_empty() && next.deleted.is_empty());
+    }
+
+    #[tokio::test]
+    async fn pages_through_changes_and_sees_cascading_deletes() {
+        let db = setup_db().await;
+        let category = create_category(&db, "Docker").await;
+        let first = create_item(&db, category, "ps").await;
+        let second = create_item(&db, category, "logs").await;
+
+        let page = get_changes_since_by_pool(&db, 0, 2).await.unwrap();
+        assert!(page.has_more);
+        assert_eq!(page.categories.len() + page.items.len(), 2);
+        let rest = get_changes_since_by_pool(&db, page.cursor, 2)
+            .await
+            .unwrap();
+        assert!(!rest.has_more);
+        assert_eq!(rest.items.len(), 1);
+
+        delete_category_by_pool(&db, category).await.unwrap();
+        let set = get_changes_since_by_pool(&db, rest.cursor, 10)
+            .await
+            .unwrap();
+        let deleted: Vec<(LibraryEntity, i64)> =
+            set.deleted.iter().map(|t| (t.entity, t.id)).collect();
+        assert_eq!(deleted.len(), 3);
+        assert!(deleted.contains(&(LibraryEntity::Category, category)));
+        assert!(deleted.contains(&(LibraryEntity::Item, first)));
+        assert!(deleted.contains(&(LibraryEntity::Item, second)));
+    }
+
+    #[tokio::test]
+    async fn a_cursor_past_the_feed_starts_over() {
+        let db = setup_db().await;
+        create_category(&db, "Git").await;
+
+        let set = get_changes_since_by_pool(&db, 1_000, 10).await.unwrap();
+        assert!(set.reset);
+        assert_eq!(set.categories.len(), 1);
+    }
+}
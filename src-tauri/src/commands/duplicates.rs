@@ -0,0 +1,405 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use tauri::{AppHandle, State};
+
+use crate::clock::SystemClock;
+use crate::commands::items::{get_all_items_by_pool, get_item_by_pool, Item, ItemWithCategory};
+use crate::commands::links::{ensure_writable_by_conn, Writer};
+use crate::db::Db;
+use crate::events::{self, ChangeKind, LibraryChanged};
+use crate::i18n;
+
+/// Fuzzy matches must be at least this similar when no threshold is given.
+pub const DEFAULT_THRESHOLD: f64 = 0.85;
+
+/// How alike the items of a [`DuplicateGroup`] are, from the weakest pair
+/// that put them together.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DuplicateMatch {
+    /// Same label and value.
+    Exact,
+    /// Same label and value ignoring case and whitespace.
+    Normalized,
+    /// Label and value together at least as similar as the threshold.
+    Fuzzy,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuplicateGroup {
+    pub kind: DuplicateMatch,
+    /// Normalized Levenshtein similarity of the least similar pair that
+    /// joined the group, from 0 to 1; 1 unless `kind` is fuzzy.
+    pub similarity: f64,
+    /// In library order.
+    pub items: Vec<ItemWithCategory>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MergeItemsInput {
+    /// The item that stays.
+    pub keep_id: i64,
+    /// Items deleted into it.
+    pub merge_ids: Vec<i64>,
+    /// Append the values of the merged items that the kept one lacks, one
+    /// per line, instead of dropping them.
+    #[serde(default)]
+    pub combine_values: bool,
+}
+
+/// Lowercase with runs of whitespace collapsed to one space.
+fn normalize(text: &str) -> String {
+    text.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// Disjoint sets of keys, remembering the weakest link of each set.
+struct Groups {
+    parent: Vec<usize>,
+    weakest: Vec<f64>,
+}
+
+impl Groups {
+    fn new(len: usize) -> Self {
+        Self {
+            parent: (0..len).collect(),
+            weakest: vec![1.0; len],
+        }
+    }
+
+    fn root(&mut self, mut key: usize) -> usize {
+        while self.parent[key] != key {
+            self.parent[key] = self.parent[self.parent[key]];
+            key = self.parent[key];
+        }
+        key
+    }
+
+    fn join(&mut self, a: usize, b: usize, similarity: f64) {
+        let (a, b) = (self.root(a), self.root(b));
+        let weakest = self.weakest[a].min(self.weakest[b]).min(similarity);
+        self.parent[b] = a;
+        self.weakest[a] = weakest;
+    }
+}
+
+/// Items that look like copies of each other, across categories.
+///
+/// Items are first grouped by normalized label and value, then those groups
+/// are joined when label and value together are at least `threshold`
+/// similar. A threshold of 1 finds exact and normalized duplicates only.
+pub async fn find_duplicate_items_by_pool(
+    db: &SqlitePool,
+    threshold: f64,
+) -> Result<Vec<DuplicateGroup>, String> {
+    if !(0.5..=1.0).contains(&threshold) {
+        return Err(i18n::t("error.duplicate_threshold"));
+    }
+    let items = get_all_items_by_pool(db, &SystemClock).await?;
+
+    // Distinct normalized items and the library items behind each.
+    let mut keys: Vec<(String, String)> = Vec::new();
+    let mut members: Vec<Vec<usize>> = Vec::new();
+    let mut positions: HashMap<(String, String), usize> = HashMap::new();
+    for (index, item) in items.iter().enumerate() {
+        let key = (normalize(&item.label), normalize(&item.value));
+        match positions.get(&key) {
+            Some(existing) => members[*existing].push(index),
+            None => {
+                positions.insert(key.clone(), keys.len());
+                keys.push(key);
+                members.push(vec![index]);
+            }
+        }
+    }
+
+    let mut groups = Groups::new(keys.len());
+    if threshold < 1.0 {
+        let texts: Vec<String> = keys.iter().map(|(l, v)| format!("{l} {v}")).collect();
+        let lengths: Vec<usize> = texts.iter().map(|t| t.chars().count()).collect();
+        for a in 0..texts.len() {
+            for b in a + 1..texts.len() {
+                // Strings this different in length cannot be similar enough.
+                let (short, long) = (lengths[a].min(lengths[b]), lengths[a].max(lengths[b]));
+                if (short as f64) < long as f64 * threshold {
+                    continue;
+                }
+                let similarity = strsim::normalized_levenshtein(&texts[a], &texts[b]);
+                if similarity >= threshold {
+                    groups.join(a, b, similarity);
+                }
+            }
+        }
+    }
+
+    // Keys of each group, in the order the groups first appear.
+    let mut by_root: Vec<(usize, Vec<usize>)> = Vec::new();
+    let mut root_positions: HashMap<usize, usize> = HashMap::new();
+    for key in 0..keys.len() {
+        let root = groups.root(key);
+        match root_positions.get(&root) {
+            Some(position) => by_root[*position].1.push(key),
+            None => {
+                root_positions.insert(root, by_root.len());
+                by_root.push((root, vec![key]));
+            }
+        }
+    }
+
+    let mut duplicates = Vec::new();
+    for (root, group_keys) in by_root {
+        let mut indices: Vec<usize> = group_keys
+            .iter()
+            .flat_map(|key| members[*key].iter().copied())
+            .collect();
+        if indices.len() < 2 {
+            continue;
+        }
+        indices.sort_unstable();
+        let group_items: Vec<ItemWithCategory> =
+            indices.iter().map(|i| items[*i].clone()).collect();
+        let first = &group_items[0];
+        let kind = if group_keys.len() > 1 {
+            DuplicateMatch::Fuzzy
+        } else if group_items
+            .iter()
+            .all(|item| item.label == first.label && item.value == first.value)
+        {
+            DuplicateMatch::Exact
+        } else {
+            DuplicateMatch::Normalized
+        };
+        duplicates.push(DuplicateGroup {
+            kind,
+            similarity: groups.weakest[root],
+            items: group_items,
+        });
+    }
+    Ok(duplicates)
+}
+
+/// Keep one item and delete the others, all or nothing.
+pub async fn merge_items_by_pool(db: &SqlitePool, input: MergeItemsInput) -> Result<Item, String> {
+    if input.merge_ids.is_empty() || input.merge_ids.contains(&input.keep_id) {
+        return Err(i18n::t("error.merge_items_invalid"));
+    }
+    let keep = get_item_by_pool(db, input.keep_id).await?;
+    let mut merged = Vec::new();
+    for id in &input.merge_ids {
+        merged.push(get_item_by_pool(db, *id).await?);
+    }
+
+    let mut tx = db.begin().await.map_err(|e| e.to_string())?;
+    for item in merged.iter().chain([&keep]) {
+        ensure_writable_by_conn(&mut tx, item.category_id, Writer::User).await?;
+    }
+    if input.combine_values {
+        let mut values = vec![keep.value.clone()];
+        for item in &merged {
+            if !values
+                .iter()
+                .any(|v| normalize(v) == normalize(&item.value))
+            {
+                values.push(item.value.clone());
+            }
+        }
+        values.retain(|value| !value.is_empty());
+        sqlx::query("UPDATE items SET value = ?, updated_at = datetime('now') WHERE id = ?")
+            .bind(values.join("\n"))
+            .bind(keep.id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    for item in &merged {
+        sqlx::query("DELETE FROM items WHERE id = ?")
+            .bind(item.id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    tx.commit().await.map_err(|e| e.to_string())?;
+    get_item_by_pool(db, keep.id).await
+}
+
+// --- Tauri commands (thin wrappers) ---
+
+#[tauri::command]
+pub async fn find_duplicate_items(
+    db: State<'_, Db>,
+    threshold: Option<f64>,
+) -> Result<Vec<DuplicateGroup>, String> {
+    find_duplicate_items_by_pool(&db.pool(), threshold.unwrap_or(DEFAULT_THRESHOLD)).await
+}
+
+#[tauri::command]
+pub async fn merge_items(
+    app: AppHandle,
+    db: State<'_, Db>,
+    input: MergeItemsInput,
+) -> Result<Item, String> {
+    let pool = db.pool();
+    let mut merged = Vec::new();
+    for id in &input.merge_ids {
+        merged.push(get_item_by_pool(&pool, *id).await?);
+    }
+    let item = merge_items_by_pool(&pool, input).await?;
+    events::emit(
+        &app,
+        LibraryChanged::items(ChangeKind::Updated, vec![item.id], item.category_id),
+    );
+    for gone in &merged {
+        events::emit(
+            &app,
+            LibraryChanged::items(ChangeKind::Deleted, vec![gone.id], gone.category_id),
+        );
+    }
+    Ok(item)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::categories::{create_category_by_pool, CreateCategoryInput};
+    use crate::commands::items::{create_item_by_pool, CreateItemInput, ItemKind};
+
+    async fn setup_db() -> SqlitePool {
+        let db = SqlitePool::connect("sqlite::memory:")
+            .await
+            .expect("connect sqlite memory");
+        sqlx::query(include_str!("../../migrations/002_peeky_domain.sql"))
+            .execute(&db)
+            .await
+            .expect("run migration 002");
+        sqlx::query(include_str!("../../migrations/009_item_kind.sql"))
+            .execute(&db)
+            .await
+            .expect("run migration 009");
+        sqlx::query(include_str!("../../migrations/010_category_links.sql"))
+            .execute(&db)
+            .await
+            .expect("run migration 010");
+        sqlx::query(include_str!("../../migrations/012_item_expiry.sql"))
+            .execute(&db)
+            .await
+            .expect("run migration 012");
+        db
+    }
+
+    async fn category(db: &SqlitePool, name: &str) -> i64 {
+        create_category_by_pool(
+            db,
+            CreateCategoryInput {
+                name: name.to_string(),
+            },
+        )
+        .await
+        .expect("create category")
+        .id
+    }
+
+    async fn item(db: &SqlitePool, category_id: i64, label: &str, value: &str) -> i64 {
+        create_item_by_pool(
+            db,
+            CreateItemInput {
+                category_id,
+                label: label.to_string(),
+                value: Some(value.to_string()),
+                kind: ItemKind::Text,
+            },
+        )
+        .await
+        .expect("create item")
+        .id
+    }
+
+    fn ids(group: &DuplicateGroup) -> Vec<i64> {
+        group.items.iter().map(|item| item.id).collect()
+    }
+
+    #[tokio::test]
+    async fn groups_exact_normalized_and_fuzzy_duplicates() {
+        let db = setup_db().await;
+        let git = category(&db, "Git").await;
+        let imported = category(&db, "Imported").await;
+        let a = item(&db, git, "Undo commit", "git reset HEAD~1").await;
+        let b = item(&db, imported, "Undo commit", "git reset HEAD~1").await;
+        let c = item(&db, git, "Status", "git status").await;
+        let d = item(&db, imported, "  status ", "GIT   status").await;
+        let e = item(&db, git, "Amend commit", "git commit --amend --no-edit").await;
+        let f = item(
+            &db,
+            imported,
+            "Amend commits",
+            "git commit --amend --no-edit",
+        )
+        .await;
+        item(&db, git, "Push", "git push").await;
+
+        let strict = find_duplicate_items_by_pool(&db, 1.0).await.unwrap();
+        assert_eq!(strict.len(), 2);
+        assert_eq!(strict[0].kind, DuplicateMatch::Exact);
+        assert_eq!(ids(&strict[0]), vec![a, b]);
+        assert_eq!(strict[1].kind, DuplicateMatch::Normalized);
+        assert_eq!(ids(&strict[1]), vec![c, d]);
+
+        let fuzzy = find_duplicate_items_by_pool(&db, 0.9).await.unwrap();
+        assert_eq!(fuzzy.len(), 3);
+        assert_eq!(fuzzy[2].kind, DuplicateMatch::Fuzzy);
+        assert_eq!(ids(&fuzzy[2]), vec![e, f]);
+        assert!(fuzzy[2].similarity >= 0.9 && fuzzy[2].similarity < 1.0);
+
+        assert!(find_duplicate_items_by_pool(&db, 1.5).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn merge_keeps_one_and_combines_values() {
+        let db = setup_db().await;
+        let git = category(&db, "Git").await;
+        let keep = item(&db, git, "Log", "git log --oneline").await;
+        let same = item(&db, git, "log", "GIT LOG --oneline").await;
+        let other = item(&db, git, "Log graph", "git log --graph").await;
+
+        let merged = merge_items_by_pool(
+            &db,
+            MergeItemsInput {
+                keep_id: keep,
+                merge_ids: vec![same, other],
+                combine_values: true,
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(merged.value, "git log --oneline\ngit log --graph");
+        assert!(get_item_by_pool(&db, same).await.is_err());
+        assert!(get_item_by_pool(&db, other).await.is_err());
+
+        // A missing item fails the whole merge before anything is deleted.
+        let spare = item(&db, git, "Log", "git log").await;
+        let result = merge_items_by_pool(
+            &db,
+            MergeItemsInput {
+                keep_id: keep,
+                merge_ids: vec![spare, 999],
+                combine_values: false,
+            },
+        )
+        .await;
+        assert!(result.is_err());
+        assert!(get_item_by_pool(&db, spare).await.is_ok());
+        assert!(merge_items_by_pool(
+            &db,
+            MergeItemsInput {
+                keep_id: keep,
+                merge_ids: vec![keep],
+                combine_values: false,
+            },
+        )
+        .await
+        .is_err());
+    }
+}
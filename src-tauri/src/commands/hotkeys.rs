@@ -0,0 +1,395 @@
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use tauri::{AppHandle, State};
+
+use crate::chord::{self, Chord};
+use crate::db::Db;
+use crate::hotkeys;
+use crate::i18n;
+
+pub const TOGGLE_OVERLAY_SHORTCUT: &str = "Ctrl+Option+O";
+pub const TOGGLE_MAIN_SHORTCUT: &str = "Ctrl+Option+L";
+
+/// Shortcuts the app registers itself; user bindings may not reuse them.
+/// Each entry pairs the shortcut with the catalog key describing it.
+pub const BUILTIN_SHORTCUTS: [(&str, &str); 2] = [
+    (TOGGLE_OVERLAY_SHORTCUT, "shortcut.toggle_overlay"),
+    (TOGGLE_MAIN_SHORTCUT, "shortcut.toggle_main"),
+];
+
+pub const ACTION_SHOW_CATEGORY: &str = "show_category";
+pub const ACTION_COPY_ITEM: &str = "copy_item";
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct HotkeyBinding {
+    pub id: i64,
+    pub shortcut: String,
+    pub action: String,
+    pub category_id: Option<i64>,
+    pub item_id: Option<i64>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateHotkeyBindingInput {
+    pub shortcut: String,
+    /// `show_category` (needs `category_id`) or `copy_item` (needs `item_id`).
+    pub action: String,
+    pub category_id: Option<i64>,
+    pub item_id: Option<i64>,
+}
+
+// --- Pool-based functions (testable) ---
+
+pub async fn get_hotkey_bindings_by_pool(db: &SqlitePool) -> Result<Vec<HotkeyBinding>, String> {
+    sqlx::query_as::<_, HotkeyBinding>(
+        "SELECT id, shortcut, action, category_id, item_id, created_at
+         FROM hotkey_bindings ORDER BY id",
+    )
+    .fetch_all(db)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+async fn exists(db: &SqlitePool, table: &str, id: i64) -> Result<bool, String> {
+    let row: Option<(i64,)> = sqlx::query_as(&format!("SELECT id FROM {table} WHERE id = ?"))
+        .bind(id)
+        .fetch_optional(db)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(row.is_some())
+}
+
+/// Check `shortcut` against the built-in shortcuts and existing bindings and
+/// return its canonical spelling.
+pub async fn check_hotkey_conflict_by_pool(
+    db: &SqlitePool,
+    shortcut: &str,
+) -> Result<String, String> {
+    let chord: Chord = shortcut.parse()?;
+    if !chord.has_modifier() {
+        return Err(i18n::tf(
+            "error.shortcut_needs_modifier",
+            &[("shortcut", &chord)],
+        ));
+    }
+    let canonical = chord.to_string();
+
+    for (builtin, description) in BUILTIN_SHORTCUTS {
+        if chord::normalize(builtin).as_deref() == Ok(canonical.as_str()) {
+            return Err(i18n::tf(
+                "error.shortcut_reserved",
+                &[("shortcut", &canonical), ("action", &i18n::t(description))],
+            ));
+        }
+    }
+
+    let taken: Option<(i64,)> = sqlx::query_as("SELECT id FROM hotkey_bindings WHERE shortcut = ?")
+        .bind(&canonical)
+        .fetch_optional(db)
+        .await
+        .map_err(|e| e.to_string())?;
+    if taken.is_some() {
+        return Err(i18n::tf(
+            "error.shortcut_taken",
+            &[("shortcut", &canonical)],
+        ));
+    }
+
+    Ok(canonical)
+}
+
+pub async fn create_hotkey_binding_by_pool(
+    db: &SqlitePool,
+    input: CreateHotkeyBindingInput,
+) -> Result<HotkeyBinding, String> {
+    let shortcut = check_hotkey_conflict_by_pool(db, &input.shortcut).await?;
+
+    let (category_id, item_id) = match input.action.as_str() {
+        ACTION_SHOW_CATEGORY => {
+            let id = input
+                .category_id
+                .ok_or_else(|| i18n::t("error.hotkey_needs_category"))?;
+            if !exists(db, "categories", id).await? {
+                return Err(i18n::tf("error.category_not_found", &[("id", &id)]));
+            }
+            (Some(id), None)
+        }
+        ACTION_COPY_ITEM => {
+            let id = input
+                .item_id
+                .ok_or_else(|| i18n::t("error.hotkey_needs_item"))?;
+            if !exists(db, "items", id).await? {
+                return Err(i18n::tf("error.item_not_found", &[("id", &id)]));
+            }
+            (None, Some(id))
+        }
+        other => {
+            return Err(i18n::tf(
+                "error.hotkey_unknown_action",
+                &[("action", &other)],
+            ))
+        }
+    };
+
+    let id = sqlx::query(
+        "INSERT INTO hotkey_bindings (shortcut, action, category_id, item_id) VALUES (?, ?, ?, ?)",
+    )
+    .bind(&shortcut)
+    .bind(&input.action)
+    .bind(category_id)
+    .bind(item_id)
+    .execute(db)
+    .await
+    .map_err(|e| e.to_string())?
+    .last_insert_rowid();
+
+    sqlx::query_as::<_, HotkeyBinding>(
+        "SELECT id, shortcut, action, category_id, item_id, created_at
+         FROM hotkey_bindings WHERE id = ?",
+    )
+    .bind(id)
+    .fetch_one(db)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+pub async fn delete_hotkey_binding_by_pool(db: &SqlitePool, id: i64) -> Result<(), String> {
+    let result = sqlx::query("DELETE FROM hotkey_bindings WHERE id = ?")
+        .bind(id)
+        .execute(db)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if result.rows_affected() == 0 {
+        return Err(i18n::tf("error.hotkey_binding_not_found", &[("id", &id)]));
+    }
+    Ok(())
+}
+
+// --- Tauri commands (thin wrappers) ---
+
+#[tauri::command]
+pub async fn get_hotkey_bindings(db: State<'_, Db>) -> Result<Vec<HotkeyBinding>, String> {
+    get_hotkey_bindings_by_pool(&db.pool()).await
+}
+
+/// Save a binding and register it right away. If the OS refuses the
+/// shortcut (e.g. another app owns it) the binding is rolled back.
+#[tauri::command]
+pub async fn create_hotkey_binding(
+    app: AppHandle,
+    db: State<'_, Db>,
+    input: CreateHotkeyBindingInput,
+) -> Result<HotkeyBinding, String> {
+    let binding = create_hotkey_binding_by_pool(&db.pool(), input).await?;
+    let failures = hotkeys::reload(&app).await?;
+    if let Some(err) = failures.get(&binding.id) {
+        delete_hotkey_binding_by_pool(&db.pool(), binding.id).await?;
+        hotkeys::reload(&app).await?;
+        return Err(i18n::tf(
+            "error.shortcut_register_failed",
+            &[("shortcut", &binding.shortcut), ("error", err)],
+        ));
+    }
+    Ok(binding)
+}
+
+#[tauri::command]
+pub async fn delete_hotkey_binding(
+    app: AppHandle,
+    db: State<'_, Db>,
+    id: i64,
+) -> Result<(), String> {
+    delete_hotkey_binding_by_pool(&db.pool(), id).await?;
+    hotkeys::reload(&app).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlx::SqlitePool;
+
+    use super::*;
+    use crate::commands::categories::{create_category_by_pool, CreateCategoryInput};
+    use crate::commands::items::{
+        create_item_by_pool, delete_item_by_pool, CreateItemInput, ItemKind,
+    };
+
+    async fn setup_db() -> SqlitePool {
+        let db = SqlitePool::connect("sqlite::memory:")
+            .await
+            .expect("connect sqlite memory");
+        sqlx::query(include_str!("../../migrations/002_peeky_domain.sql"))
+            .execute(&db)
+            .await
+            .expect("run migration 002");
+        sqlx::query(include_str!("../../migrations/009_item_kind.sql"))
+            .execute(&db)
+            .await
+            .expect("run migration 009");
+        sqlx::query(include_str!("../../migrations/010_category_links.sql"))
+            .execute(&db)
+            .await
+            .expect("run migration 010");
+        sqlx::query(include_str!("../../migrations/012_item_expiry.sql"))
+            .execute(&db)
+            .await
+            .expect("run migration 012");
+        sqlx::query(include_str!("../../migrations/004_hotkey_bindings.sql"))
+            .execute(&db)
+            .await
+            .expect("run migration 004");
+        sqlx::query("PRAGMA foreign_keys = ON")
+            .execute(&db)
+            .await
+            .expect("enable foreign keys");
+        db
+    }
+
+    async fn seed(db: &SqlitePool) -> (i64, i64) {
+        let cat = create_category_by_pool(
+            db,
+            CreateCategoryInput {
+                name: "Git".to_string(),
+            },
+        )
+        .await
+        .unwrap();
+        let item = create_item_by_pool(
+            db,
+            CreateItemInput {
+                category_id: cat.id,
+                label: "VPN password".to_string(),
+                value: Some("hunter2".to_string()),
+                kind: ItemKind::Text,
+            },
+        )
+        .await
+        .unwrap();
+        (cat.id, item.id)
+    }
+
+    fn input(
+        shortcut: &str,
+        action: &str,
+        category_id: Option<i64>,
+        item_id: Option<i64>,
+    ) -> CreateHotkeyBindingInput {
+        CreateHotkeyBindingInput {
+            shortcut: shortcut.to_string(),
+            action: action.to_string(),
+            category_id,
+            item_id,
+        }
+    }
+
+    #[tokio::test]
+    async fn create_binding_stores_canonical_shortcut() {
+        let db = setup_db().await;
+        let (cat_id, item_id) = seed(&db).await;
+
+        let show = create_hotkey_binding_by_pool(
+            &db,
+            input("alt+ctrl+g", ACTION_SHOW_CATEGORY, Some(cat_id), None),
+        )
+        .await
+        .unwrap();
+        assert_eq!(show.shortcut, "Ctrl+Option+G");
+        assert_eq!(show.category_id, Some(cat_id));
+        assert_eq!(show.item_id, None);
+
+        let copy = create_hotkey_binding_by_pool(
+            &db,
+            input("Ctrl+Option+1", ACTION_COPY_ITEM, None, Some(item_id)),
+        )
+        .await
+        .unwrap();
+        assert_eq!(copy.item_id, Some(item_id));
+
+        let all = get_hotkey_bindings_by_pool(&db).await.unwrap();
+        assert_eq!(all.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn create_binding_rejects_conflicts() {
+        let db = setup_db().await;
+        let (cat_id, _) = seed(&db).await;
+
+        let builtin = create_hotkey_binding_by_pool(
+            &db,
+            input("control+alt+o", ACTION_SHOW_CATEGORY, Some(cat_id), None),
+        )
+        .await;
+        assert!(builtin.unwrap_err().contains("reserved"));
+
+        create_hotkey_binding_by_pool(
+            &db,
+            input("Ctrl+Option+G", ACTION_SHOW_CATEGORY, Some(cat_id), None),
+        )
+        .await
+        .unwrap();
+        let duplicate = create_hotkey_binding_by_pool(
+            &db,
+            input("Option+Ctrl+g", ACTION_SHOW_CATEGORY, Some(cat_id), None),
+        )
+        .await;
+        assert!(duplicate.unwrap_err().contains("already bound"));
+
+        let bare = create_hotkey_binding_by_pool(
+            &db,
+            input("G", ACTION_SHOW_CATEGORY, Some(cat_id), None),
+        )
+        .await;
+        assert!(bare.is_err());
+    }
+
+    #[tokio::test]
+    async fn create_binding_validates_target() {
+        let db = setup_db().await;
+        let (cat_id, _) = seed(&db).await;
+
+        let missing_item = create_hotkey_binding_by_pool(
+            &db,
+            input("Ctrl+Option+1", ACTION_COPY_ITEM, None, Some(999)),
+        )
+        .await;
+        assert!(missing_item.is_err());
+
+        let wrong_target = create_hotkey_binding_by_pool(
+            &db,
+            input("Ctrl+Option+2", ACTION_COPY_ITEM, Some(cat_id), None),
+        )
+        .await;
+        assert!(wrong_target.is_err());
+
+        let unknown = create_hotkey_binding_by_pool(
+            &db,
+            input("Ctrl+Option+3", "launch_rocket", Some(cat_id), None),
+        )
+        .await;
+        assert!(unknown.is_err());
+    }
+
+    #[tokio::test]
+    async fn deleting_target_removes_binding() {
+        let db = setup_db().await;
+        let (_, item_id) = seed(&db).await;
+        create_hotkey_binding_by_pool(
+            &db,
+            input("Ctrl+Option+1", ACTION_COPY_ITEM, None, Some(item_id)),
+        )
+        .await
+        .unwrap();
+
+        delete_item_by_pool(&db, item_id).await.unwrap();
+
+        assert!(get_hotkey_bindings_by_pool(&db).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn delete_binding_not_found() {
+        let db = setup_db().await;
+        assert!(delete_hotkey_binding_by_pool(&db, 42).await.is_err());
+    }
+}
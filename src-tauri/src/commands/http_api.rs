@@ -0,0 +1,109 @@
+use sqlx::SqlitePool;
+use tauri::{AppHandle, State};
+
+use crate::db::Db;
+use crate::http_api;
+
+// --- Pool-based functions (testable) ---
+
+/// The token every HTTP API request must carry, or empty while there is
+/// none and the server stays off. A stored token that fails
+/// [`http_api::validate_token`] counts as none.
+pub async fn get_http_api_token_by_pool(db: &SqlitePool) -> Result<String, String> {
+    let token: Option<String> = sqlx::query_scalar("SELECT token FROM http_api_token WHERE id = 1")
+        .fetch_optional(db)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(token
+        .filter(|token| http_api::validate_token(token).is_ok())
+        .unwrap_or_default())
+}
+
+/// Replace the token with a new random one and return it.
+pub async fn regenerate_http_api_token_by_pool(db: &SqlitePool) -> Result<String, String> {
+    let token = http_api::generate_token(db).await?;
+    sqlx::query(
+        "INSERT INTO http_api_token (id, token) VALUES (1, ?)
+         ON CONFLICT(id) DO UPDATE SET token = excluded.token",
+    )
+    .bind(&token)
+    .execute(db)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(token)
+}
+
+// --- Tauri commands (thin wrappers) ---
+
+/// The token is left out of the settings, so the UI only sees it when it
+/// asks for it.
+#[tauri::command]
+pub async fn get_http_api_token(db: State<'_, Db>) -> Result<String, String> {
+    get_http_api_token_by_pool(&db.pool()).await
+}
+
+/// Replace the token with a new random one, which also restarts the HTTP
+/// API if it is enabled.
+#[tauri::command]
+pub async fn regenerate_http_api_token(
+    app: AppHandle,
+    db: State<'_, Db>,
+) -> Result<String, String> {
+    let token = regenerate_http_api_token_by_pool(&db.pool()).await?;
+    if let Err(err) = http_api::restart(&app).await {
+        eprintln!("failed to restart HTTP API: {err}");
+    }
+    Ok(token)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db;
+
+    async fn setup_db() -> (tempfile::TempDir, SqlitePool) {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let pool = db::create_pool(&dir.path().join("peeky.db").to_string_lossy())
+            .await
+            .expect("create pool");
+        db::migrator().run(&pool).await.expect("run migrations");
+        (dir, pool)
+    }
+
+    #[tokio::test]
+    async fn token_is_empty_until_regenerated() {
+        let (_dir, db) = setup_db().await;
+        assert_eq!(get_http_api_token_by_pool(&db).await.unwrap(), "");
+
+        let token = regenerate_http_api_token_by_pool(&db).await.unwrap();
+        assert_eq!(get_http_api_token_by_pool(&db).await.unwrap(), token);
+        let next = regenerate_http_api_token_by_pool(&db).await.unwrap();
+        assert_ne!(next, token);
+        assert_eq!(get_http_api_token_by_pool(&db).await.unwrap(), next);
+    }
+
+    #[tokio::test]
+    async fn migration_moves_the_token_out_of_the_settings() {
+        let db = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        for sql in [
+            include_str!("../../migrations/001_init.sql"),
+            include_str!("../../migrations/003_hold_to_peek.sql"),
+            include_str!("../../migrations/005_settings_store.sql"),
+            "INSERT INTO settings (key, value) VALUES ('http_api_token', '\"0123456789abcdef\"')",
+            include_str!("../../migrations/013_http_api_token.sql"),
+        ] {
+            sqlx::query(sql).execute(&db).await.unwrap();
+        }
+
+        assert_eq!(
+            get_http_api_token_by_pool(&db).await.unwrap(),
+            "0123456789abcdef"
+        );
+        let left: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM settings WHERE key = 'http_api_token'")
+                .fetch_one(&db)
+                .await
+                .unwrap();
+        assert_eq!(left, 0);
+    }
+}
@@ -1,6 +1,12 @@
 use serde::{Deserialize, Serialize};
-use sqlx::SqlitePool;
-use tauri::State;
+use sqlx::{SqliteConnection, SqlitePool};
+use tauri::{AppHandle, State};
+
+use crate::clock::{Clock, SystemClock};
+use crate::commands::links::{ensure_not_linked_by_pool, ensure_writable_by_conn, Writer};
+use crate::db::Db;
+use crate::events::{self, ChangeKind, LibraryChanged};
+use crate::i18n;
 
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct Item {
@@ -11,6 +17,9 @@ pub struct Item {
     pub sort_order: i64,
     pub created_at: String,
     pub updated_at: String,
+    /// When the item is cleaned up, as `YYYY-MM-DD HH:MM:SS` UTC.
+    pub expires_at: Option<String>,
+    pub kind: ItemKind,
 }
 
 /// Flat struct returned by get_all_items (JOIN with categories).
@@ -23,6 +32,33 @@ pub struct ItemWithCategory {
     pub sort_order: i64,
     pub category_name: String,
     pub category_sort_order: i64,
+    pub kind: ItemKind,
+}
+
+/// What an item's value is, so windows know whether to copy or open it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case")]
+pub enum ItemKind {
+    #[default]
+    Text,
+    /// The value is a URL.
+    Link,
+}
+
+impl ItemKind {
+    pub fn is_text(&self) -> bool {
+        *self == ItemKind::Text
+    }
+}
+
+/// What the cleanup task does with items past their expiry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExpiredItemAction {
+    Delete,
+    /// Move them to the "Expired" category, without an expiry.
+    Move,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -30,6 +66,8 @@ pub struct CreateItemInput {
     pub category_id: i64,
     pub label: String,
     pub value: Option<String>,
+    #[serde(default)]
+    pub kind: ItemKind,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -41,36 +79,101 @@ pub struct UpdateItemInput {
 }
 
 // --- Pool-based functions (testable) ---
-
-pub async fn get_items_by_pool(db: &SqlitePool, category_id: i64) -> Result<Vec<Item>, String> {
+//
+// The `_by_conn` variants run on any connection, such as a transaction that
+// lets an importer write a whole file or nothing. Writes take the [`Writer`]
+// so that linked categories refuse everyone but their file's sync; the
+// `_by_pool` ones write for the user.
+
+pub async fn get_items_by_conn(
+    db: &mut SqliteConnection,
+    category_id: i64,
+) -> Result<Vec<Item>, String> {
     sqlx::query_as::<_, Item>(
-        "SELECT id, category_id, label, value, sort_order, created_at, updated_at
+        "SELECT id, category_id, label, value, sort_order, created_at, updated_at, expires_at, kind
          FROM items WHERE category_id = ? ORDER BY sort_order, id",
     )
     .bind(category_id)
+    .fetch_all(&mut *db)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+pub async fn get_items_by_pool(db: &SqlitePool, category_id: i64) -> Result<Vec<Item>, String> {
+    let mut conn = db.acquire().await.map_err(|e| e.to_string())?;
+    get_items_by_conn(&mut conn, category_id).await
+}
+
+pub async fn get_item_by_pool(db: &SqlitePool, id: i64) -> Result<Item, String> {
+    sqlx::query_as::<_, Item>(
+        "SELECT id, category_id, label, value, sort_order, created_at, updated_at, expires_at, kind
+         FROM items WHERE id = ?",
+    )
+    .bind(id)
+    .fetch_optional(db)
+    .await
+    .map_err(|e| e.to_string())?
+    .ok_or_else(|| i18n::tf("error.item_not_found", &[("id", &id)]))
+}
+
+/// Every item in library order, except those past their expiry by `clock`
+/// that the cleanup task has not removed yet.
+pub async fn get_all_items_by_pool(
+    db: &SqlitePool,
+    clock: &dyn Clock,
+) -> Result<Vec<ItemWithCategory>, String> {
+    sqlx::query_as::<_, ItemWithCategory>(
+        "SELECT i.id, i.category_id, i.label, i.value, i.sort_order,
+                c.name AS category_name, c.sort_order AS category_sort_order, i.kind
+         FROM items i
+         JOIN categories c ON c.id = i.category_id
+         WHERE i.expires_at IS NULL OR i.expires_at > datetime(?, 'unixepoch')
+         ORDER BY c.sort_order, c.id, i.sort_order, i.id",
+    )
+    .bind(clock.unix_secs())
     .fetch_all(db)
     .await
     .map_err(|e| e.to_string())
 }
 
-pub async fn get_all_items_by_pool(db: &SqlitePool) -> Result<Vec<ItemWithCategory>, String> {
+/// Items whose label or value contains `query`, ignoring ASCII case, in
+/// library order. An empty query matches everything. Like
+/// [`get_all_items_by_pool`], leaves out expired items.
+pub async fn search_items_by_pool(
+    db: &SqlitePool,
+    query: &str,
+    clock: &dyn Clock,
+) -> Result<Vec<ItemWithCategory>, String> {
+    let escaped = query
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
     sqlx::query_as::<_, ItemWithCategory>(
         "SELECT i.id, i.category_id, i.label, i.value, i.sort_order,
-                c.name AS category_name, c.sort_order AS category_sort_order
+                c.name AS category_name, c.sort_order AS category_sort_order, i.kind
          FROM items i
          JOIN categories c ON c.id = i.category_id
+         WHERE (i.label LIKE ?1 ESCAPE '\\' OR i.value LIKE ?1 ESCAPE '\\')
+           AND (i.expires_at IS NULL OR i.expires_at > datetime(?2, 'unixepoch'))
          ORDER BY c.sort_order, c.id, i.sort_order, i.id",
     )
+    .bind(format!("%{escaped}%"))
+    .bind(clock.unix_secs())
     .fetch_all(db)
     .await
     .map_err(|e| e.to_string())
 }
 
-pub async fn create_item_by_pool(db: &SqlitePool, input: CreateItemInput) -> Result<Item, String> {
+pub async fn create_item_by_conn(
+    db: &mut SqliteConnection,
+    input: CreateItemInput,
+    writer: Writer,
+) -> Result<Item, String> {
+    ensure_writable_by_conn(db, input.category_id, writer).await?;
     let max_order: Option<(i64,)> =
         sqlx::query_as("SELECT COALESCE(MAX(sort_order), -1) FROM items WHERE category_id = ?")
             .bind(input.category_id)
-            .fetch_optional(db)
+            .fetch_optional(&mut *db)
             .await
             .map_err(|e| e.to_string())?;
     let next_order = max_order.map(|r| r.0 + 1).unwrap_or(0);
@@ -78,37 +181,48 @@ pub async fn create_item_by_pool(db: &SqlitePool, input: CreateItemInput) -> Res
     let value = input.value.unwrap_or_default();
 
     let id = sqlx::query(
-        "INSERT INTO items (category_id, label, value, sort_order) VALUES (?, ?, ?, ?)",
+        "INSERT INTO items (category_id, label, value, sort_order, kind) VALUES (?, ?, ?, ?, ?)",
     )
     .bind(input.category_id)
     .bind(&input.label)
     .bind(&value)
     .bind(next_order)
-    .execute(db)
+    .bind(input.kind)
+    .execute(&mut *db)
     .await
     .map_err(|e| e.to_string())?
     .last_insert_rowid();
 
     sqlx::query_as::<_, Item>(
-        "SELECT id, category_id, label, value, sort_order, created_at, updated_at
+        "SELECT id, category_id, label, value, sort_order, created_at, updated_at, expires_at, kind
          FROM items WHERE id = ?",
     )
     .bind(id)
-    .fetch_one(db)
+    .fetch_one(&mut *db)
     .await
     .map_err(|e| e.to_string())
 }
 
-pub async fn update_item_by_pool(db: &SqlitePool, input: UpdateItemInput) -> Result<Item, String> {
+pub async fn create_item_by_pool(db: &SqlitePool, input: CreateItemInput) -> Result<Item, String> {
+    let mut conn = db.acquire().await.map_err(|e| e.to_string())?;
+    create_item_by_conn(&mut conn, input, Writer::User).await
+}
+
+pub async fn update_item_by_conn(
+    db: &mut SqliteConnection,
+    input: UpdateItemInput,
+    writer: Writer,
+) -> Result<Item, String> {
     let current = sqlx::query_as::<_, Item>(
-        "SELECT id, category_id, label, value, sort_order, created_at, updated_at
+        "SELECT id, category_id, label, value, sort_order, created_at, updated_at, expires_at, kind
          FROM items WHERE id = ?",
     )
     .bind(input.id)
-    .fetch_optional(db)
+    .fetch_optional(&mut *db)
     .await
     .map_err(|e| e.to_string())?
-    .ok_or_else(|| format!("Item {} not found", input.id))?;
+    .ok_or_else(|| i18n::tf("error.item_not_found", &[("id", &input.id)]))?;
+    ensure_writable_by_conn(db, current.category_id, writer).await?;
 
     let label = input.label.unwrap_or(current.label);
     let value = input.value.unwrap_or(current.value);
@@ -121,64 +235,145 @@ pub async fn update_item_by_pool(db: &SqlitePool, input: UpdateItemInput) -> Res
     .bind(&value)
     .bind(sort_order)
     .bind(input.id)
-    .execute(db)
+    .execute(&mut *db)
     .await
     .map_err(|e| e.to_string())?;
 
     sqlx::query_as::<_, Item>(
-        "SELECT id, category_id, label, value, sort_order, created_at, updated_at
+        "SELECT id, category_id, label, value, sort_order, created_at, updated_at, expires_at, kind
          FROM items WHERE id = ?",
     )
     .bind(input.id)
-    .fetch_one(db)
+    .fetch_one(&mut *db)
     .await
     .map_err(|e| e.to_string())
 }
 
-pub async fn delete_item_by_pool(db: &SqlitePool, id: i64) -> Result<(), String> {
-    let result = sqlx::query("DELETE FROM items WHERE id = ?")
+pub async fn update_item_by_pool(db: &SqlitePool, input: UpdateItemInput) -> Result<Item, String> {
+    let mut conn = db.acquire().await.map_err(|e| e.to_string())?;
+    update_item_by_conn(&mut conn, input, Writer::User).await
+}
+
+/// Make the item expire at `expires_at`, any date and time SQLite
+/// understands (`2026-05-01 18:00`, ISO 8601 with an offset), or never for
+/// `None`. Stored as UTC.
+pub async fn set_item_expiry_by_pool(
+    db: &SqlitePool,
+    id: i64,
+    expires_at: Option<&str>,
+) -> Result<Item, String> {
+    let expires_at: Option<String> = match expires_at {
+        Some(text) => {
+            let normalized: Option<String> = sqlx::query_scalar("SELECT datetime(?)")
+                .bind(text)
+                .fetch_one(db)
+                .await
+                .map_err(|e| e.to_string())?;
+            Some(normalized.ok_or_else(|| i18n::tf("error.expiry_invalid", &[("value", &text)]))?)
+        }
+        None => None,
+    };
+    let current = get_item_by_pool(db, id).await?;
+    ensure_not_linked_by_pool(db, current.category_id).await?;
+    sqlx::query("UPDATE items SET expires_at = ?, updated_at = datetime('now') WHERE id = ?")
+        .bind(&expires_at)
         .bind(id)
         .execute(db)
         .await
         .map_err(|e| e.to_string())?;
+    get_item_by_pool(db, id).await
+}
 
-    if result.rows_affected() == 0 {
-        return Err(format!("Item {} not found", id));
-    }
+pub async fn delete_item_by_conn(
+    db: &mut SqliteConnection,
+    id: i64,
+    writer: Writer,
+) -> Result<(), String> {
+    let category_id: i64 = sqlx::query_scalar("SELECT category_id FROM items WHERE id = ?")
+        .bind(id)
+        .fetch_optional(&mut *db)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| i18n::tf("error.item_not_found", &[("id", &id)]))?;
+    ensure_writable_by_conn(db, category_id, writer).await?;
+
+    sqlx::query("DELETE FROM items WHERE id = ?")
+        .bind(id)
+        .execute(&mut *db)
+        .await
+        .map_err(|e| e.to_string())?;
     Ok(())
 }
 
+pub async fn delete_item_by_pool(db: &SqlitePool, id: i64) -> Result<(), String> {
+    let mut conn = db.acquire().await.map_err(|e| e.to_string())?;
+    delete_item_by_conn(&mut conn, id, Writer::User).await
+}
+
 // --- Tauri commands (thin wrappers) ---
 
 #[tauri::command]
-pub async fn get_items(db: State<'_, SqlitePool>, category_id: i64) -> Result<Vec<Item>, String> {
-    get_items_by_pool(db.inner(), category_id).await
+pub async fn get_items(db: State<'_, Db>, category_id: i64) -> Result<Vec<Item>, String> {
+    get_items_by_pool(&db.pool(), category_id).await
 }
 
 #[tauri::command]
-pub async fn get_all_items(db: State<'_, SqlitePool>) -> Result<Vec<ItemWithCategory>, String> {
-    get_all_items_by_pool(db.inner()).await
+pub async fn get_all_items(db: State<'_, Db>) -> Result<Vec<ItemWithCategory>, String> {
+    get_all_items_by_pool(&db.pool(), &SystemClock).await
 }
 
 #[tauri::command]
 pub async fn create_item(
-    db: State<'_, SqlitePool>,
+    app: AppHandle,
+    db: State<'_, Db>,
     input: CreateItemInput,
 ) -> Result<Item, String> {
-    create_item_by_pool(db.inner(), input).await
+    let item = create_item_by_pool(&db.pool(), input).await?;
+    events::emit(
+        &app,
+        LibraryChanged::items(ChangeKind::Created, vec![item.id], item.category_id),
+    );
+    Ok(item)
 }
 
 #[tauri::command]
 pub async fn update_item(
-    db: State<'_, SqlitePool>,
+    app: AppHandle,
+    db: State<'_, Db>,
     input: UpdateItemInput,
 ) -> Result<Item, String> {
-    update_item_by_pool(db.inner(), input).await
+    let item = update_item_by_pool(&db.pool(), input).await?;
+    events::emit(
+        &app,
+        LibraryChanged::items(ChangeKind::Updated, vec![item.id], item.category_id),
+    );
+    Ok(item)
 }
 
 #[tauri::command]
-pub async fn delete_item(db: State<'_, SqlitePool>, id: i64) -> Result<(), String> {
-    delete_item_by_pool(db.inner(), id).await
+pub async fn delete_item(app: AppHandle, db: State<'_, Db>, id: i64) -> Result<(), String> {
+    let item = get_item_by_pool(&db.pool(), id).await?;
+    delete_item_by_pool(&db.pool(), id).await?;
+    events::emit(
+        &app,
+        LibraryChanged::items(ChangeKind::Deleted, vec![id], item.category_id),
+    );
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn set_item_expiry(
+    app: AppHandle,
+    db: State<'_, Db>,
+    id: i64,
+    expires_at: Option<String>,
+) -> Result<Item, String> {
+    let item = set_item_expiry_by_pool(&db.pool(), id, expires_at.as_deref()).await?;
+    events::emit(
+        &app,
+        LibraryChanged::items(ChangeKind::Updated, vec![item.id], item.category_id),
+    );
+    Ok(item)
 }
 
 #[cfg(test)]
@@ -216,12 +411,18 @@ mod tests {
               value TEXT NOT NULL DEFAULT '',
               sort_order INTEGER NOT NULL DEFAULT 0,
               created_at TEXT NOT NULL DEFAULT (datetime('now')),
-              updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+              updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+              expires_at TEXT,
+              kind TEXT NOT NULL DEFAULT 'text'
             )",
         )
         .execute(&db)
         .await
         .expect("create items");
+        sqlx::query(include_str!("../../migrations/010_category_links.sql"))
+            .execute(&db)
+            .await
+            .expect("run migration 010");
         sqlx::query("PRAGMA foreign_keys = ON")
             .execute(&db)
             .await
@@ -261,6 +462,7 @@ mod tests {
                 category_id: cat.id,
                 label: "Copy".to_string(),
                 value: Some("Cmd+C".to_string()),
+                kind: ItemKind::Text,
             },
         )
         .await
@@ -280,6 +482,7 @@ mod tests {
                 category_id: cat.id,
                 label: "A".to_string(),
                 value: None,
+                kind: ItemKind::Text,
             },
         )
         .await
@@ -290,6 +493,7 @@ mod tests {
                 category_id: cat.id,
                 label: "B".to_string(),
                 value: None,
+                kind: ItemKind::Text,
             },
         )
         .await
@@ -298,6 +502,44 @@ mod tests {
         assert_eq!(i2.sort_order, 1);
     }
 
+    #[tokio::test]
+    async fn search_items_matches_label_or_value_literally() {
+        let db = setup_db().await;
+        let cat = create_test_category(&db, "Shell").await;
+        for (label, value) in [("Disk", "df -h"), ("Progress", "100% done"), ("Grep", "rg")] {
+            create_item_by_pool(
+                &db,
+                CreateItemInput {
+                    category_id: cat.id,
+                    label: label.to_string(),
+                    value: Some(value.to_string()),
+                    kind: ItemKind::Text,
+                },
+            )
+            .await
+            .unwrap();
+        }
+
+        let labels = |items: Vec<ItemWithCategory>| -> Vec<String> {
+            items.into_iter().map(|item| item.label).collect()
+        };
+        assert_eq!(
+            labels(search_items_by_pool(&db, "DF", &SystemClock).await.unwrap()),
+            ["Disk"]
+        );
+        assert_eq!(
+            labels(search_items_by_pool(&db, "%", &SystemClock).await.unwrap()),
+            ["Progress"]
+        );
+        assert_eq!(
+            search_items_by_pool(&db, "", &SystemClock)
+                .await
+                .unwrap()
+                .len(),
+            3
+        );
+    }
+
     #[tokio::test]
     async fn update_item_partial_fields() {
         let db = setup_db().await;
@@ -308,6 +550,7 @@ mod tests {
                 category_id: cat.id,
                 label: "Old".to_string(),
                 value: Some("val".to_string()),
+                kind: ItemKind::Text,
             },
         )
         .await
@@ -339,6 +582,7 @@ mod tests {
                 category_id: cat.id,
                 label: "Del".to_string(),
                 value: None,
+                kind: ItemKind::Text,
             },
         )
         .await
@@ -367,12 +611,13 @@ mod tests {
                 category_id: cat.id,
                 label: "Copy".to_string(),
                 value: Some("Cmd+C".to_string()),
+                kind: ItemKind::Text,
             },
         )
         .await
         .unwrap();
 
-        let all = get_all_items_by_pool(&db).await.unwrap();
+        let all = get_all_items_by_pool(&db, &SystemClock).await.unwrap();
         assert_eq!(all.len(), 1);
         assert_eq!(all[0].category_name, "Shortcuts");
         assert_eq!(all[0].label, "Copy");
@@ -0,0 +1,181 @@
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, State};
+
+use crate::control;
+use crate::db::Db;
+use crate::i18n;
+use crate::importers;
+use crate::library_file;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImportSummary {
+    pub categories_created: u32,
+    pub items_created: u32,
+    /// Items given a new value in [`ImportMode::Update`].
+    pub items_updated: u32,
+    /// Items already in their category with the same label and value.
+    pub items_skipped: u32,
+    /// Items deleted in [`ImportMode::Replace`].
+    pub items_deleted: u32,
+}
+
+/// What to do with an incoming item whose label is already in its category
+/// with a different value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportMode {
+    /// Add it as another item.
+    Merge,
+    /// Give the existing item the new value, so that importing a newer
+    /// version of a file does not duplicate what changed.
+    Update,
+    /// Like update, and also delete the items of the imported categories
+    /// that the file no longer has, for categories that mirror a file.
+    Replace,
+}
+
+/// Snippet files of other tools that the library can be imported from and
+/// exported to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SnippetFormat {
+    /// An Alfred `.alfredsnippets` collection.
+    Alfred,
+    /// A Raycast snippets JSON export.
+    Raycast,
+    /// An Espanso match file.
+    Espanso,
+}
+
+/// Editor keymaps whose shortcuts can be imported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeymapFormat {
+    /// VS Code's `keybindings.json`.
+    Vscode,
+    /// A JetBrains IDE keymap XML file.
+    Jetbrains,
+}
+
+/// Cheat sheet collections whose pages can be imported from a directory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheatsheetFormat {
+    /// tldr-pages Markdown.
+    Tldr,
+    /// `cheat/cheatsheets` plain text.
+    Cheat,
+}
+
+/// A folder of a bookmarks file, for picking which ones to import.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BookmarkFolder {
+    /// Folder path, which is also the category name.
+    pub name: String,
+    pub bookmarks: u32,
+}
+
+// --- Tauri commands ---
+
+/// Import the snippet file at `path`; each collection becomes a category.
+#[tauri::command]
+pub async fn import_snippets(
+    app: AppHandle,
+    db: State<'_, Db>,
+    format: SnippetFormat,
+    path: String,
+) -> Result<ImportSummary, String> {
+    let library = importers::read(format, Path::new(&path))?;
+    let summary = library_file::import_by_pool(&db.pool(), &library, ImportMode::Merge).await?;
+    control::reload_library(&app);
+    Ok(summary)
+}
+
+/// Import the shortcuts of the keymap at `path` into one category, with
+/// commands as labels and key chords as values.
+#[tauri::command]
+pub async fn import_keymap(
+    app: AppHandle,
+    db: State<'_, Db>,
+    format: KeymapFormat,
+    path: String,
+) -> Result<ImportSummary, String> {
+    let library = importers::read_keymap(format, Path::new(&path))?;
+    let summary = library_file::import_by_pool(&db.pool(), &library, ImportMode::Merge).await?;
+    control::reload_library(&app);
+    Ok(summary)
+}
+
+/// Import every cheat sheet page under `dir`, one category per page.
+#[tauri::command]
+pub async fn import_cheatsheets(
+    app: AppHandle,
+    db: State<'_, Db>,
+    format: CheatsheetFormat,
+    dir: String,
+    mode: ImportMode,
+) -> Result<ImportSummary, String> {
+    let library = importers::cheatsheets::read_dir(format, Path::new(&dir))?;
+    let summary = library_file::import_by_pool(&db.pool(), &library, mode).await?;
+    control::reload_library(&app);
+    Ok(summary)
+}
+
+/// Import the hosts of the SSH config at `path`, `~/.ssh/config` by
+/// default, into the "SSH Hosts" category.
+#[tauri::command]
+pub async fn import_ssh_hosts(
+    app: AppHandle,
+    db: State<'_, Db>,
+    path: Option<String>,
+    mode: ImportMode,
+) -> Result<ImportSummary, String> {
+    let path = match path {
+        Some(path) => PathBuf::from(path),
+        None => importers::ssh_config::default_path()
+            .ok_or_else(|| i18n::t("error.ssh_config_no_home"))?,
+    };
+    let summary = importers::ssh_config::import_by_pool(&db.pool(), &path, mode).await?;
+    control::reload_library(&app);
+    Ok(summary)
+}
+
+/// The folders with bookmarks in the bookmarks HTML file at `path`.
+#[tauri::command]
+pub async fn list_bookmark_folders(path: String) -> Result<Vec<BookmarkFolder>, String> {
+    let library = importers::bookmarks::read(Path::new(&path))?;
+    Ok(importers::bookmarks::folders(&library))
+}
+
+/// Import the bookmarks HTML file at `path`, limited to `folders` and their
+/// subfolders if given. Bookmarks whose URL is already saved are skipped.
+#[tauri::command]
+pub async fn import_bookmarks(
+    app: AppHandle,
+    db: State<'_, Db>,
+    path: String,
+    folders: Option<Vec<String>>,
+) -> Result<ImportSummary, String> {
+    let summary =
+        importers::bookmarks::import_by_pool(&db.pool(), Path::new(&path), folders.as_deref())
+            .await?;
+    control::reload_library(&app);
+    Ok(summary)
+}
+
+/// Write one snippet file per category into `dir` and return their paths.
+#[tauri::command]
+pub async fn export_snippets(
+    db: State<'_, Db>,
+    format: SnippetFormat,
+    dir: String,
+) -> Result<Vec<String>, String> {
+    let library = library_file::export_by_pool(&db.pool()).await?;
+    let paths = importers::write(format, &library, Path::new(&dir))?;
+    Ok(paths
+        .iter()
+        .map(|path| path.to_string_lossy().into_owned())
+        .collect())
+}
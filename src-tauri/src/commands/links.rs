@@ -0,0 +1,188 @@
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use sqlx::{SqliteConnection, SqlitePool};
+use tauri::{AppHandle, State};
+
+use crate::db::Db;
+use crate::i18n;
+use crate::linked_files;
+
+/// A category kept in step with a Markdown file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, sqlx::FromRow)]
+pub struct CategoryLink {
+    pub category_id: i64,
+    pub path: String,
+    /// Why the file could not be used at the last attempt; the items are
+    /// left as they were until it can.
+    pub error: Option<String>,
+    /// When the items last matched the file.
+    pub synced_at: Option<String>,
+}
+
+// --- Pool-based functions (testable) ---
+
+pub async fn get_category_links_by_pool(db: &SqlitePool) -> Result<Vec<CategoryLink>, String> {
+    sqlx::query_as::<_, CategoryLink>(
+        "SELECT category_id, path, error, synced_at FROM category_links ORDER BY category_id",
+    )
+    .fetch_all(db)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+pub async fn get_category_link_by_pool(
+    db: &SqlitePool,
+    category_id: i64,
+) -> Result<Option<CategoryLink>, String> {
+    sqlx::query_as::<_, CategoryLink>(
+        "SELECT category_id, path, error, synced_at FROM category_links WHERE category_id = ?",
+    )
+    .bind(category_id)
+    .fetch_optional(db)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Bind the category to the file at `path`, replacing any earlier link.
+/// Its items are only rewritten by the next sync.
+pub async fn link_category_by_pool(
+    db: &SqlitePool,
+    category_id: i64,
+    path: &str,
+) -> Result<CategoryLink, String> {
+    if !Path::new(path).is_absolute() {
+        return Err(i18n::t("error.link_path_relative"));
+    }
+    let exists: Option<i64> = sqlx::query_scalar("SELECT id FROM categories WHERE id = ?")
+        .bind(category_id)
+        .fetch_optional(db)
+        .await
+        .map_err(|e| e.to_string())?;
+    if exists.is_none() {
+        return Err(i18n::tf(
+            "error.category_not_found",
+            &[("id", &category_id)],
+        ));
+    }
+    sqlx::query(
+        "INSERT INTO category_links (category_id, path) VALUES (?, ?)
+         ON CONFLICT (category_id) DO UPDATE SET path = excluded.path, error = NULL, synced_at = NULL",
+    )
+    .bind(category_id)
+    .bind(path)
+    .execute(db)
+    .await
+    .map_err(|e| e.to_string())?;
+    get_category_link_by_pool(db, category_id)
+        .await?
+        .ok_or_else(|| i18n::tf("error.category_not_linked", &[("id", &category_id)]))
+}
+
+pub async fn unlink_category_by_pool(db: &SqlitePool, category_id: i64) -> Result<(), String> {
+    sqlx::query("DELETE FROM category_links WHERE category_id = ?")
+        .bind(category_id)
+        .execute(db)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Record the outcome of a sync: `error`, or success as of now.
+pub async fn set_link_status_by_pool(
+    db: &SqlitePool,
+    category_id: i64,
+    error: Option<&str>,
+) -> Result<(), String> {
+    let query = match error {
+        Some(_) => "UPDATE category_links SET error = ? WHERE category_id = ?",
+        None => {
+            "UPDATE category_links SET error = ?, synced_at = datetime('now') WHERE category_id = ?"
+        }
+    };
+    sqlx::query(query)
+        .bind(error)
+        .bind(category_id)
+        .execute(db)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Who is changing a category's items.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Writer {
+    /// The main window, the CLI, the HTTP API and everything else acting
+    /// for the user.
+    User,
+    /// The sync of a linked file, the one writer a linked category takes.
+    LinkedFile,
+}
+
+/// Fails if the category's items belong to a file and `writer` is not its
+/// sync. Every item write goes through this.
+pub async fn ensure_writable_by_conn(
+    db: &mut SqliteConnection,
+    category_id: i64,
+    writer: Writer,
+) -> Result<(), String> {
+    if writer == Writer::LinkedFile {
+        return Ok(());
+    }
+    let path: Option<String> =
+        sqlx::query_scalar("SELECT path FROM category_links WHERE category_id = ?")
+            .bind(category_id)
+            .fetch_optional(&mut *db)
+            .await
+            .map_err(|e| e.to_string())?;
+    match path {
+        Some(path) => Err(i18n::tf("error.category_linked", &[("path", &path)])),
+        None => Ok(()),
+    }
+}
+
+/// [`ensure_writable_by_conn`] for the user.
+pub async fn ensure_not_linked_by_pool(db: &SqlitePool, category_id: i64) -> Result<(), String> {
+    let mut conn = db.acquire().await.map_err(|e| e.to_string())?;
+    ensure_writable_by_conn(&mut conn, category_id, Writer::User).await
+}
+
+// --- Tauri commands (thin wrappers) ---
+
+#[tauri::command]
+pub async fn get_category_links(db: State<'_, Db>) -> Result<Vec<CategoryLink>, String> {
+    get_category_links_by_pool(&db.pool()).await
+}
+
+/// Link the category to the Markdown file at `path` and replace its items
+/// with the file's. A file that cannot be read still links; the returned
+/// link then carries the error.
+#[tauri::command]
+pub async fn link_category(
+    app: AppHandle,
+    db: State<'_, Db>,
+    category_id: i64,
+    path: String,
+) -> Result<CategoryLink, String> {
+    link_category_by_pool(&db.pool(), category_id, &path).await?;
+    let link = linked_files::sync(&app, category_id).await?;
+    linked_files::restart(&app).await?;
+    Ok(link)
+}
+
+/// Stop mirroring the file; the category keeps its current items.
+#[tauri::command]
+pub async fn unlink_category(
+    app: AppHandle,
+    db: State<'_, Db>,
+    category_id: i64,
+) -> Result<(), String> {
+    unlink_category_by_pool(&db.pool(), category_id).await?;
+    linked_files::restart(&app).await
+}
+
+/// Re-read the file now, e.g. after fixing an error the watcher missed.
+#[tauri::command]
+pub async fn sync_category_link(app: AppHandle, category_id: i64) -> Result<CategoryLink, String> {
+    linked_files::sync(&app, category_id).await
+}
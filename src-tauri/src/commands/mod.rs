@@ -1,4 +1,16 @@
 pub mod app;
+pub mod appearance;
+pub mod autostart;
+pub mod backup;
 pub mod categories;
+pub mod changes;
+pub mod duplicates;
+pub mod hotkeys;
+pub mod http_api;
 pub mod items;
+pub mod library;
+pub mod links;
+pub mod revisions;
 pub mod settings;
+pub mod sync;
+pub mod ticker;
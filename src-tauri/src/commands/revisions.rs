@@ -0,0 +1,317 @@
+use serde::{Deserialize, Serialize};
+use similar::{ChangeTag, TextDiff};
+use sqlx::SqlitePool;
+use tauri::{AppHandle, State};
+
+use crate::commands::items::{get_item_by_pool, update_item_by_pool, Item, UpdateItemInput};
+use crate::db::Db;
+use crate::events::{self, ChangeKind, LibraryChanged};
+use crate::i18n;
+
+/// An earlier version of an item. Revisions are written by a trigger
+/// whenever an item's label or value changes (migration 010).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, sqlx::FromRow)]
+pub struct ItemRevision {
+    pub id: i64,
+    pub item_id: i64,
+    pub label: String,
+    pub value: String,
+    /// When this version was replaced.
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiffTag {
+    Equal,
+    Delete,
+    Insert,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiffLine {
+    pub tag: DiffTag,
+    /// The line without its line break.
+    pub text: String,
+}
+
+/// What changed between two versions of an item.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RevisionDiff {
+    pub old_label: String,
+    pub new_label: String,
+    /// The values line by line.
+    pub lines: Vec<DiffLine>,
+}
+
+fn diff_lines(old: &str, new: &str) -> Vec<DiffLine> {
+    TextDiff::from_lines(old, new)
+        .iter_all_changes()
+        .map(|change| DiffLine {
+            tag: match change.tag() {
+                ChangeTag::Equal => DiffTag::Equal,
+                ChangeTag::Delete => DiffTag::Delete,
+                ChangeTag::Insert => DiffTag::Insert,
+            },
+            text: change.value().trim_end_matches(['\n', '\r']).to_string(),
+        })
+        .collect()
+}
+
+// --- Pool-based functions (testable) ---
+
+/// The item's earlier versions, newest first.
+pub async fn get_item_history_by_pool(
+    db: &SqlitePool,
+    item_id: i64,
+) -> Result<Vec<ItemRevision>, String> {
+    get_item_by_pool(db, item_id).await?;
+    sqlx::query_as::<_, ItemRevision>(
+        "SELECT id, item_id, label, value, created_at FROM item_revisions
+         WHERE item_id = ? ORDER BY id DESC",
+    )
+    .bind(item_id)
+    .fetch_all(db)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+pub async fn get_item_revision_by_pool(
+    db: &SqlitePool,
+    revision_id: i64,
+) -> Result<ItemRevision, String> {
+    sqlx::query_as::<_, ItemRevision>(
+        "SELECT id, item_id, label, value, created_at FROM item_revisions WHERE id = ?",
+    )
+    .bind(revision_id)
+    .fetch_optional(db)
+    .await
+    .map_err(|e| e.to_string())?
+    .ok_or_else(|| i18n::tf("error.revision_not_found", &[("id", &revision_id)]))
+}
+
+/// `(label, value)` of a revision of the item, or of the item as it is now
+/// for `None`.
+async fn version_by_pool(
+    db: &SqlitePool,
+    item_id: i64,
+    revision_id: Option<i64>,
+) -> Result<(String, String), String> {
+    match revision_id {
+        Some(id) => {
+            let revision = get_item_revision_by_pool(db, id).await?;
+            if revision.item_id != item_id {
+                return Err(i18n::tf("error.revision_not_found", &[("id", &id)]));
+            }
+            Ok((revision.label, revision.value))
+        }
+        None => {
+            let item = get_item_by_pool(db, item_id).await?;
+            Ok((item.label, item.value))
+        }
+    }
+}
+
+/// Line diff from revision `from` to revision `to` of the item, where
+/// `None` stands for the current version.
+pub async fn diff_item_revisions_by_pool(
+    db: &SqlitePool,
+    item_id: i64,
+    from: Option<i64>,
+    to: Option<i64>,
+) -> Result<RevisionDiff, String> {
+    let (old_label, old_value) = version_by_pool(db, item_id, from).await?;
+    let (new_label, new_value) = version_by_pool(db, item_id, to).await?;
+    Ok(RevisionDiff {
+        lines: diff_lines(&old_value, &new_value),
+        old_label,
+        new_label,
+    })
+}
+
+/// Put the revision's label and value back. The version it replaces becomes
+/// a revision itself, so a restore can be undone the same way.
+pub async fn restore_item_revision_by_pool(
+    db: &SqlitePool,
+    revision_id: i64,
+) -> Result<Item, String> {
+    let revision = get_item_revision_by_pool(db, revision_id).await?;
+    update_item_by_pool(
+        db,
+        UpdateItemInput {
+            id: revision.item_id,
+            label: Some(revision.label),
+            value: Some(revision.value),
+            sort_order: None,
+        },
+    )
+    .await
+}
+
+// --- Tauri commands (thin wrappers) ---
+
+#[tauri::command]
+pub async fn get_item_history(
+    db: State<'_, Db>,
+    item_id: i64,
+) -> Result<Vec<ItemRevision>, String> {
+    get_item_history_by_pool(&db.pool(), item_id).await
+}
+
+#[tauri::command]
+pub async fn diff_item_revisions(
+    db: State<'_, Db>,
+    item_id: i64,
+    from: Option<i64>,
+    to: Option<i64>,
+) -> Result<RevisionDiff, String> {
+    diff_item_revisions_by_pool(&db.pool(), item_id, from, to).await
+}
+
+#[tauri::command]
+pub async fn restore_item_revision(
+    app: AppHandle,
+    db: State<'_, Db>,
+    revision_id: i64,
+) -> Result<Item, String> {
+    let item = restore_item_revision_by_pool(&db.pool(), revision_id).await?;
+    events::emit(
+        &app,
+        LibraryChanged::items(ChangeKind::Updated, vec![item.id], item.category_id),
+    );
+    Ok(item)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use serde_json::Value;
+
+    use super::*;
+    use crate::commands::categories::{create_category_by_pool, CreateCategoryInput};
+    use crate::commands::items::{create_item_by_pool, CreateItemInput, ItemKind};
+    use crate::commands::settings::set_settings_by_pool;
+    use crate::db;
+
+    async fn setup_db(dir: &std::path::Path) -> SqlitePool {
+        let pool = db::create_pool(&dir.join("peeky.db").to_string_lossy())
+            .await
+            .expect("create pool");
+        db::migrator().run(&pool).await.expect("run migrations");
+        pool
+    }
+
+    async fn create_item(db: &SqlitePool, value: &str) -> Item {
+        let category = create_category_by_pool(
+            db,
+            CreateCategoryInput {
+                name: "Deploy".to_string(),
+            },
+        )
+        .await
+        .expect("create category");
+        create_item_by_pool(
+            db,
+            CreateItemInput {
+                category_id: category.id,
+                label: "Release".to_string(),
+                value: Some(value.to_string()),
+                kind: ItemKind::Text,
+            },
+        )
+        .await
+        .expect("create item")
+    }
+
+    async fn set_value(db: &SqlitePool, id: i64, value: &str) {
+        update_item_by_pool(
+            db,
+            UpdateItemInput {
+                id,
+                label: None,
+                value: Some(value.to_string()),
+                sort_order: None,
+            },
+        )
+        .await
+        .expect("update item");
+    }
+
+    #[tokio::test]
+    async fn updates_keep_history_that_can_be_diffed_and_restored() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let db = setup_db(dir.path()).await;
+        let item = create_item(&db, "make build\nmake deploy").await;
+
+        // Moving an item is not a new version.
+        update_item_by_pool(
+            &db,
+            UpdateItemInput {
+                id: item.id,
+                label: None,
+                value: None,
+                sort_order: Some(5),
+            },
+        )
+        .await
+        .expect("move item");
+        assert!(get_item_history_by_pool(&db, item.id)
+            .await
+            .unwrap()
+            .is_empty());
+
+        set_value(&db, item.id, "make build\nmake test\nmake deploy").await;
+        let history = get_item_history_by_pool(&db, item.id).await.unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].value, "make build\nmake deploy");
+
+        let diff = diff_item_revisions_by_pool(&db, item.id, Some(history[0].id), None)
+            .await
+            .unwrap();
+        let tags: Vec<(DiffTag, &str)> = diff
+            .lines
+            .iter()
+            .map(|line| (line.tag, line.text.as_str()))
+            .collect();
+        assert_eq!(
+            tags,
+            vec![
+                (DiffTag::Equal, "make build"),
+                (DiffTag::Insert, "make test"),
+                (DiffTag::Equal, "make deploy"),
+            ]
+        );
+
+        let restored = restore_item_revision_by_pool(&db, history[0].id)
+            .await
+            .unwrap();
+        assert_eq!(restored.value, "make build\nmake deploy");
+        let history = get_item_history_by_pool(&db, item.id).await.unwrap();
+        assert_eq!(history[0].value, "make build\nmake test\nmake deploy");
+        assert!(get_item_revision_by_pool(&db, 999).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn history_is_capped_by_the_setting() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let db = setup_db(dir.path()).await;
+        set_settings_by_pool(
+            &db,
+            HashMap::from([("item_revision_limit".to_string(), Value::from(2))]),
+        )
+        .await
+        .expect("set limit");
+        let item = create_item(&db, "v1").await;
+        for value in ["v2", "v3", "v4"] {
+            set_value(&db, item.id, value).await;
+        }
+        let values: Vec<String> = get_item_history_by_pool(&db, item.id)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|revision| revision.value)
+            .collect();
+        assert_eq!(values, vec!["v3", "v2"]);
+    }
+}
@@ -1,99 +1,452 @@
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 use sqlx::SqlitePool;
-use tauri::State;
+use tauri::{AppHandle, Manager, State};
+
+use crate::appearance;
+use crate::commands::appearance::Theme;
+use crate::commands::autostart::Autostart;
+use crate::commands::items::ExpiredItemAction;
+use crate::db::Db;
+use crate::events::{self, SettingsChanged};
+use crate::hold_trigger::{HoldConfig, HoldTriggerHandle};
+use crate::http_api;
+use crate::i18n;
+use crate::locale::SUPPORTED_LOCALES;
+use crate::settings_schema::{self, SettingDef, SettingType, SCHEMA};
+use crate::tray;
+use crate::tray_ticker;
 
-#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+/// Typed view of the settings store. Field names are the store keys; see
+/// [`settings_schema::SCHEMA`] for defaults and validation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppSettings {
     pub locale: String,
     pub launch_on_login: bool,
-    pub theme: String,
+    pub theme: Theme,
+    pub hold_modifier: String,
+    pub hold_duration_ms: i64,
+    pub hold_hide_on_release: bool,
+    pub ticker_interval_secs: i64,
+    pub ticker_max_chars: i64,
+    pub backup_interval_hours: i64,
+    /// Automatic backups to keep; manual backups are never pruned.
+    pub backup_retention: i64,
+    /// Folder the library is mirrored to; empty disables sync.
+    pub sync_folder: String,
+    pub sync_interval_minutes: i64,
+    /// Serve the local HTTP API on `127.0.0.1:http_api_port`.
+    pub http_api_enabled: bool,
+    pub http_api_port: i64,
+    /// Re-sync the "SSH Hosts" category from `~/.ssh/config` at startup.
+    pub ssh_hosts_sync: bool,
+    /// Earlier versions kept per item; older ones are dropped on the next
+    /// edit.
+    pub item_revision_limit: i64,
+    pub expired_items: ExpiredItemAction,
 }
 
 impl Default for AppSettings {
     fn default() -> Self {
+        serde_json::from_value(Value::Object(settings_schema::defaults()))
+            .expect("settings schema defaults match AppSettings")
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SettingKind {
+    Bool,
+    Integer,
+    Choice,
+    Text,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SupportedLocale {
+    /// BCP 47 tag, e.g. `zh-CN`.
+    pub tag: String,
+    /// Name of the language in that language.
+    pub name: String,
+}
+
+/// One entry of the settings schema, as sent to the frontend.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SettingSchema {
+    pub key: String,
+    pub kind: SettingKind,
+    pub default: Value,
+    pub min: Option<i64>,
+    pub max: Option<i64>,
+    pub choices: Option<Vec<String>>,
+}
+
+impl From<&SettingDef> for SettingSchema {
+    fn from(def: &SettingDef) -> Self {
+        let (kind, min, max, choices) = match def.ty {
+            SettingType::Bool { .. } => (SettingKind::Bool, None, None, None),
+            SettingType::Integer { min, max, .. } => {
+                (SettingKind::Integer, Some(min), Some(max), None)
+            }
+            SettingType::Choice { choices, .. } => (
+                SettingKind::Choice,
+                None,
+                None,
+                Some(choices.iter().map(|c| c.to_string()).collect()),
+            ),
+            SettingType::Text { .. } => (SettingKind::Text, None, None, None),
+        };
         Self {
-            locale: "en-US".to_string(),
-            launch_on_login: false,
-            theme: "system".to_string(),
+            key: def.key.to_string(),
+            kind,
+            default: def.default_value(),
+            min,
+            max,
+            choices,
         }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct SetSettingsInput {
-    pub locale: Option<String>,
-    pub launch_on_login: Option<bool>,
-    pub theme: Option<String>,
+/// Raw stored values, including keys this build does not know about.
+async fn load_stored(db: &SqlitePool) -> Result<Map<String, Value>, String> {
+    let rows: Vec<(String, String)> = sqlx::query_as("SELECT key, value FROM settings")
+        .fetch_all(db)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(key, value)| {
+            // Hand edits and older builds may have stored bare text; keep it
+            // as a string rather than losing it.
+            let value = serde_json::from_str(&value).unwrap_or(Value::String(value));
+            (key, value)
+        })
+        .collect())
 }
 
-pub async fn get_settings_by_pool(db: &SqlitePool) -> Result<AppSettings, String> {
-    let settings = sqlx::query_as::<_, AppSettings>(
-        "SELECT locale, launch_on_login, theme FROM app_settings WHERE id = 1",
-    )
-    .fetch_optional(db)
-    .await
-    .map_err(|e| e.to_string())?;
+pub fn get_settings_schema_entries() -> Vec<SettingSchema> {
+    SCHEMA.iter().map(SettingSchema::from).collect()
+}
 
-    Ok(settings.unwrap_or_default())
+/// Stored values that pass validation, on top of the schema defaults.
+pub async fn get_settings_by_pool(db: &SqlitePool) -> Result<AppSettings, String> {
+    let stored = load_stored(db).await?;
+    let mut values = settings_schema::defaults();
+    for def in SCHEMA {
+        if let Some(value) = stored.get(def.key) {
+            if def.validate(value).is_ok() {
+                values.insert(def.key.to_string(), value.clone());
+            }
+        }
+    }
+    serde_json::from_value(Value::Object(values)).map_err(|e| e.to_string())
 }
 
+/// Write the given keys. A `null` value resets that key to its default.
+/// Nothing is written unless every key is known and every value valid.
 pub async fn set_settings_by_pool(
     db: &SqlitePool,
-    input: SetSettingsInput,
+    input: HashMap<String, Value>,
 ) -> Result<AppSettings, String> {
-    let current = get_settings_by_pool(db).await?;
-    let merged = AppSettings {
-        locale: input.locale.unwrap_or(current.locale),
-        launch_on_login: input.launch_on_login.unwrap_or(current.launch_on_login),
-        theme: input.theme.unwrap_or(current.theme),
-    };
+    for (key, value) in &input {
+        let def = settings_schema::find(key)
+            .ok_or_else(|| i18n::tf("error.setting_unknown", &[("key", key)]))?;
+        if !value.is_null() {
+            def.validate(value)?;
+        }
+    }
+
+    let mut tx = db.begin().await.map_err(|e| e.to_string())?;
+    for (key, value) in &input {
+        if value.is_null() {
+            sqlx::query("DELETE FROM settings WHERE key = ?")
+                .bind(key)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| e.to_string())?;
+        } else {
+            sqlx::query(
+                "INSERT INTO settings (key, value) VALUES (?, ?)
+                 ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_at = datetime('now')",
+            )
+            .bind(key)
+            .bind(value.to_string())
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+        }
+    }
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    get_settings_by_pool(db).await
+}
+
+pub async fn reset_settings_by_pool(db: &SqlitePool) -> Result<AppSettings, String> {
+    sqlx::query("DELETE FROM settings")
+        .execute(db)
+        .await
+        .map_err(|e| e.to_string())?;
+    get_settings_by_pool(db).await
+}
+
+/// The write that puts `keys` back as they are stored now: their valid
+/// stored values, and `null` for those left at their default.
+pub async fn undo_input_by_pool<'a>(
+    db: &SqlitePool,
+    keys: impl IntoIterator<Item = &'a str>,
+) -> Result<HashMap<String, Value>, String> {
+    let stored = load_stored(db).await?;
+    Ok(keys
+        .into_iter()
+        .filter_map(settings_schema::find)
+        .map(|def| {
+            let value = stored
+                .get(def.key)
+                .filter(|value| def.validate(value).is_ok())
+                .cloned()
+                .unwrap_or(Value::Null);
+            (def.key.to_string(), value)
+        })
+        .collect())
+}
+
+pub async fn migrate_settings_by_pool(db: &SqlitePool) -> Result<i64, String> {
+    migrate_settings_with(db, settings_schema::MIGRATIONS).await
+}
+
+async fn migrate_settings_with(
+    db: &SqlitePool,
+    migrations: &[settings_schema::Migration],
+) -> Result<i64, String> {
+    let stored_version: Option<(i64,)> =
+        sqlx::query_as("SELECT version FROM settings_meta WHERE id = 1")
+            .fetch_optional(db)
+            .await
+            .map_err(|e| e.to_string())?;
+    let from = stored_version.map_or(1, |(version,)| version);
+
+    let mut stored = load_stored(db).await?;
+    let to = settings_schema::migrate(&mut stored, from, migrations);
+    if to == from && stored_version.is_some() {
+        return Ok(to);
+    }
 
+    let mut tx = db.begin().await.map_err(|e| e.to_string())?;
+    sqlx::query("DELETE FROM settings")
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    for (key, value) in &stored {
+        sqlx::query("INSERT INTO settings (key, value) VALUES (?, ?)")
+            .bind(key)
+            .bind(value.to_string())
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
     sqlx::query(
-        "INSERT INTO app_settings (id, locale, launch_on_login, theme) VALUES (1, ?, ?, ?)
-         ON CONFLICT(id) DO UPDATE SET locale = excluded.locale, launch_on_login = excluded.launch_on_login, theme = excluded.theme",
+        "INSERT INTO settings_meta (id, version) VALUES (1, ?)
+         ON CONFLICT(id) DO UPDATE SET version = excluded.version",
     )
-    .bind(&merged.locale)
-    .bind(merged.launch_on_login)
-    .bind(&merged.theme)
-    .execute(db)
+    .bind(to)
+    .execute(&mut *tx)
     .await
     .map_err(|e| e.to_string())?;
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(to)
+}
 
-    Ok(merged)
+/// Names of the fields that differ between two settings snapshots.
+pub fn changed_keys(before: &AppSettings, after: &AppSettings) -> Vec<String> {
+    let (Ok(serde_json::Value::Object(before)), Ok(serde_json::Value::Object(after))) =
+        (serde_json::to_value(before), serde_json::to_value(after))
+    else {
+        return Vec::new();
+    };
+    after
+        .iter()
+        .filter(|(key, value)| before.get(*key) != Some(*value))
+        .map(|(key, _)| key.clone())
+        .collect()
+}
+
+#[tauri::command]
+pub async fn get_settings(db: State<'_, Db>) -> Result<AppSettings, String> {
+    get_settings_by_pool(&db.pool()).await
 }
 
 #[tauri::command]
-pub async fn get_settings(db: State<'_, SqlitePool>) -> Result<AppSettings, String> {
-    get_settings_by_pool(db.inner()).await
+pub async fn get_settings_schema() -> Result<Vec<SettingSchema>, String> {
+    Ok(get_settings_schema_entries())
+}
+
+#[tauri::command]
+pub async fn get_supported_locales() -> Result<Vec<SupportedLocale>, String> {
+    Ok(SUPPORTED_LOCALES
+        .iter()
+        .map(|(tag, name)| SupportedLocale {
+            tag: tag.to_string(),
+            name: name.to_string(),
+        })
+        .collect())
+}
+
+/// Push a settings change out to the running app and the webviews. `undo`
+/// is the write that reverts the change, from [`undo_input_by_pool`], with
+/// one entry per key the change wrote. Touching `launch_on_login` always
+/// reconciles the login item, even when the value is unchanged, so the UI
+/// can repair drift; if that fails the write is rolled back.
+async fn apply_settings(
+    app: &AppHandle,
+    db: &SqlitePool,
+    hold_trigger: &HoldTriggerHandle,
+    autostart: &Autostart,
+    before: AppSettings,
+    settings: AppSettings,
+    undo: HashMap<String, Value>,
+) -> Result<AppSettings, String> {
+    let changed = changed_keys(&before, &settings);
+    if undo.contains_key("launch_on_login") || changed.iter().any(|key| key == "launch_on_login") {
+        if let Err(err) = autostart.apply(settings.launch_on_login) {
+            set_settings_by_pool(db, undo).await?;
+            return Err(i18n::tf("error.autostart_failed", &[("error", &err)]));
+        }
+    }
+
+    hold_trigger.set_config(HoldConfig::from_settings(&settings));
+    if changed.iter().any(|key| key == "locale") {
+        i18n::set_locale(&settings.locale);
+        tray::refresh_locale(app);
+    }
+    if changed.iter().any(|key| key == "theme") {
+        appearance::apply_theme(app, settings.theme);
+    }
+    if changed.iter().any(|key| key.starts_with("ticker_")) {
+        if let Err(err) = tray_ticker::reload(app).await {
+            eprintln!("failed to reload ticker: {err}");
+        }
+    }
+    if changed.iter().any(|key| key.starts_with("http_api_")) {
+        if let Err(err) = http_api::restart(app).await {
+            eprintln!("failed to restart HTTP API: {err}");
+        }
+    }
+    events::emit(
+        app,
+        SettingsChanged {
+            changed,
+            settings: settings.clone(),
+        },
+    );
+    Ok(settings)
+}
+
+/// Apply the settings of a freshly restored database to the running app.
+pub async fn reapply_settings(app: &AppHandle, before: AppSettings) -> Result<AppSettings, String> {
+    let db = app.state::<Db>().pool();
+    let settings = get_settings_by_pool(&db).await?;
+    apply_settings(
+        app,
+        &db,
+        &app.state::<HoldTriggerHandle>(),
+        &app.state::<Autostart>(),
+        before,
+        settings,
+        HashMap::new(),
+    )
+    .await
 }
 
 #[tauri::command]
 pub async fn set_settings(
-    db: State<'_, SqlitePool>,
-    input: SetSettingsInput,
+    app: AppHandle,
+    db: State<'_, Db>,
+    hold_trigger: State<'_, HoldTriggerHandle>,
+    autostart: State<'_, Autostart>,
+    input: HashMap<String, Value>,
 ) -> Result<AppSettings, String> {
-    set_settings_by_pool(db.inner(), input).await
+    let before = get_settings_by_pool(&db.pool()).await?;
+    let undo = undo_input_by_pool(&db.pool(), input.keys().map(String::as_str)).await?;
+    let settings = set_settings_by_pool(&db.pool(), input).await?;
+    apply_settings(
+        &app,
+        &db.pool(),
+        &hold_trigger,
+        &autostart,
+        before,
+        settings,
+        undo,
+    )
+    .await
+}
+
+#[tauri::command]
+pub async fn reset_settings(
+    app: AppHandle,
+    db: State<'_, Db>,
+    hold_trigger: State<'_, HoldTriggerHandle>,
+    autostart: State<'_, Autostart>,
+) -> Result<AppSettings, String> {
+    let before = get_settings_by_pool(&db.pool()).await?;
+    let undo = undo_input_by_pool(&db.pool(), SCHEMA.iter().map(|def| def.key)).await?;
+    let settings = reset_settings_by_pool(&db.pool()).await?;
+    apply_settings(
+        &app,
+        &db.pool(),
+        &hold_trigger,
+        &autostart,
+        before,
+        settings,
+        undo,
+    )
+    .await
 }
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashMap;
+
+    use serde_json::{json, Value};
     use sqlx::SqlitePool;
 
-    use super::{get_settings_by_pool, set_settings_by_pool, AppSettings, SetSettingsInput};
+    use super::{
+        changed_keys, get_settings_by_pool, get_settings_schema_entries, migrate_settings_with,
+        reset_settings_by_pool, set_settings_by_pool, undo_input_by_pool, AppSettings, SettingKind,
+    };
+    use crate::commands::appearance::Theme;
+    use crate::settings_schema;
 
     async fn setup_db() -> SqlitePool {
         let db = SqlitePool::connect("sqlite::memory:")
             .await
             .expect("connect sqlite memory");
-        sqlx::query(
-            "CREATE TABLE app_settings (id INTEGER PRIMARY KEY CHECK (id = 1), locale TEXT NOT NULL DEFAULT 'en-US', launch_on_login INTEGER NOT NULL DEFAULT 0, theme TEXT NOT NULL DEFAULT 'system')",
-        )
-        .execute(&db)
-        .await
-        .expect("create app_settings table");
+        for (name, sql) in [
+            ("001", include_str!("../../migrations/001_init.sql")),
+            ("003", include_str!("../../migrations/003_hold_to_peek.sql")),
+            (
+                "005",
+                include_str!("../../migrations/005_settings_store.sql"),
+            ),
+        ] {
+            sqlx::query(sql)
+                .execute(&db)
+                .await
+                .unwrap_or_else(|e| panic!("run migration {name}: {e}"));
+        }
         db
     }
 
+    fn input(pairs: &[(&str, Value)]) -> HashMap<String, Value> {
+        pairs
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.clone()))
+            .collect()
+    }
+
     #[tokio::test]
     async fn get_settings_returns_defaults_when_missing() {
         let db = setup_db().await;
@@ -102,7 +455,7 @@ mod tests {
 
         assert_eq!(settings.locale, "en-US");
         assert!(!settings.launch_on_login);
-        assert_eq!(settings.theme, "system");
+        assert_eq!(settings.theme, Theme::System);
     }
 
     #[tokio::test]
@@ -111,17 +464,275 @@ mod tests {
 
         let updated = set_settings_by_pool(
             &db,
-            SetSettingsInput {
-                locale: Some("zh-CN".to_string()),
-                launch_on_login: Some(true),
-                theme: None,
-            },
+            input(&[("locale", json!("zh-CN")), ("launch_on_login", json!(true))]),
         )
         .await
         .expect("set settings");
 
         assert_eq!(updated.locale, "zh-CN");
         assert!(updated.launch_on_login);
-        assert_eq!(updated.theme, "system");
+        assert_eq!(updated.theme, Theme::System);
+        assert_eq!(updated.hold_modifier, "command");
+        assert_eq!(updated.hold_duration_ms, 1000);
+    }
+
+    #[tokio::test]
+    async fn set_settings_rejects_invalid_hold_options() {
+        let db = setup_db().await;
+
+        let bad_modifier =
+            set_settings_by_pool(&db, input(&[("hold_modifier", json!("hyper"))])).await;
+        assert!(bad_modifier.is_err());
+
+        let bad_duration = set_settings_by_pool(
+            &db,
+            input(&[("locale", json!("zh-CN")), ("hold_duration_ms", json!(50))]),
+        )
+        .await;
+        assert!(bad_duration.is_err());
+        // Nothing from a rejected write is stored.
+        assert_eq!(get_settings_by_pool(&db).await.unwrap().locale, "en-US");
+
+        let disabled = set_settings_by_pool(
+            &db,
+            input(&[
+                ("hold_modifier", json!("none")),
+                ("hold_duration_ms", json!(600)),
+                ("hold_hide_on_release", json!(false)),
+            ]),
+        )
+        .await
+        .expect("set hold settings");
+        assert_eq!(disabled.hold_modifier, "none");
+        assert_eq!(disabled.hold_duration_ms, 600);
+        assert!(!disabled.hold_hide_on_release);
+
+        let reloaded = get_settings_by_pool(&db).await.expect("get settings");
+        assert_eq!(reloaded.hold_modifier, "none");
+    }
+
+    #[tokio::test]
+    async fn set_settings_rejects_unknown_keys_and_null_resets() {
+        let db = setup_db().await;
+
+        let unknown = set_settings_by_pool(&db, input(&[("font_size", json!(14))])).await;
+        assert!(unknown.unwrap_err().contains("font_size"));
+
+        set_settings_by_pool(&db, input(&[("theme", json!("dark"))]))
+            .await
+            .unwrap();
+        let reset = set_settings_by_pool(&db, input(&[("theme", Value::Null)]))
+            .await
+            .unwrap();
+        assert_eq!(reset.theme, Theme::System);
+    }
+
+    #[tokio::test]
+    async fn reset_settings_restores_defaults() {
+        let db = setup_db().await;
+        set_settings_by_pool(
+            &db,
+            input(&[
+                ("locale", json!("zh-CN")),
+                ("hold_duration_ms", json!(3000)),
+            ]),
+        )
+        .await
+        .unwrap();
+
+        let reset = reset_settings_by_pool(&db).await.unwrap();
+
+        assert_eq!(reset.locale, "en-US");
+        assert_eq!(reset.hold_duration_ms, 1000);
+    }
+
+    #[tokio::test]
+    async fn invalid_stored_values_fall_back_to_defaults() {
+        let db = setup_db().await;
+        sqlx::query(
+            "INSERT INTO settings (key, value) VALUES ('hold_duration_ms', '99999'), ('theme', 'not json')",
+        )
+        .execute(&db)
+        .await
+        .unwrap();
+
+        let settings = get_settings_by_pool(&db).await.unwrap();
+
+        assert_eq!(settings.hold_duration_ms, 1000);
+        assert_eq!(settings.theme, Theme::System);
+    }
+
+    #[tokio::test]
+    async fn store_migration_carries_over_legacy_row() {
+        let db = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::query(include_str!("../../migrations/001_init.sql"))
+            .execute(&db)
+            .await
+            .unwrap();
+        sqlx::query(include_str!("../../migrations/003_hold_to_peek.sql"))
+            .execute(&db)
+            .await
+            .unwrap();
+        sqlx::query(
+            "INSERT INTO app_settings (id, locale, launch_on_login, theme, hold_duration_ms)
+             VALUES (1, 'zh-CN', 1, 'dark', 1500)",
+        )
+        .execute(&db)
+        .await
+        .unwrap();
+        sqlx::query(include_str!("../../migrations/005_settings_store.sql"))
+            .execute(&db)
+            .await
+            .unwrap();
+
+        let settings = get_settings_by_pool(&db).await.unwrap();
+
+        assert_eq!(settings.locale, "zh-CN");
+        assert!(settings.launch_on_login);
+        assert_eq!(settings.theme, Theme::Dark);
+        assert_eq!(settings.hold_modifier, "command");
+        assert_eq!(settings.hold_duration_ms, 1500);
+        assert!(settings.hold_hide_on_release);
+    }
+
+    #[tokio::test]
+    async fn store_migration_keeps_legacy_values_that_are_not_numbers() {
+        let db = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        for sql in [
+            include_str!("../../migrations/001_init.sql"),
+            include_str!("../../migrations/003_hold_to_peek.sql"),
+            "INSERT INTO app_settings (id, hold_duration_ms) VALUES (1, 'long')",
+            include_str!("../../migrations/005_settings_store.sql"),
+        ] {
+            sqlx::query(sql).execute(&db).await.unwrap();
+        }
+
+        let stored: String =
+            sqlx::query_scalar("SELECT value FROM settings WHERE key = 'hold_duration_ms'")
+                .fetch_one(&db)
+                .await
+                .unwrap();
+        assert_eq!(stored, "\"long\"");
+        assert_eq!(
+            get_settings_by_pool(&db).await.unwrap().hold_duration_ms,
+            1000
+        );
+    }
+
+    #[tokio::test]
+    async fn bare_text_values_are_read_and_kept_as_strings() {
+        let db = setup_db().await;
+        sqlx::query("INSERT INTO settings (key, value) VALUES ('locale', 'zh-CN')")
+            .execute(&db)
+            .await
+            .unwrap();
+        assert_eq!(get_settings_by_pool(&db).await.unwrap().locale, "zh-CN");
+
+        // A settings migration rewrites the store without dropping it.
+        migrate_settings_with(&db, &[|_| {}]).await.unwrap();
+        assert_eq!(get_settings_by_pool(&db).await.unwrap().locale, "zh-CN");
+    }
+
+    #[tokio::test]
+    async fn undo_input_restores_only_the_given_keys() {
+        let db = setup_db().await;
+        set_settings_by_pool(&db, input(&[("theme", json!("dark"))]))
+            .await
+            .unwrap();
+
+        let undo = undo_input_by_pool(&db, ["theme", "locale"]).await.unwrap();
+        assert_eq!(
+            undo,
+            input(&[("theme", json!("dark")), ("locale", Value::Null)])
+        );
+
+        set_settings_by_pool(
+            &db,
+            input(&[("theme", json!("light")), ("locale", json!("zh-CN"))]),
+        )
+        .await
+        .unwrap();
+        // Written since, by someone else; the undo leaves it alone.
+        set_settings_by_pool(&db, input(&[("hold_duration_ms", json!(1500))]))
+            .await
+            .unwrap();
+        let settings = set_settings_by_pool(&db, undo).await.unwrap();
+        assert_eq!(settings.theme, Theme::Dark);
+        assert_eq!(settings.locale, "en-US");
+        assert_eq!(settings.hold_duration_ms, 1500);
+    }
+
+    #[tokio::test]
+    async fn settings_migrations_run_once_and_record_version() {
+        fn rename_locale(settings: &mut serde_json::Map<String, Value>) {
+            if let Some(value) = settings.remove("language") {
+                settings.insert("locale".to_string(), value);
+            }
+        }
+        let db = setup_db().await;
+        sqlx::query("INSERT INTO settings (key, value) VALUES ('language', '\"zh-CN\"')")
+            .execute(&db)
+            .await
+            .unwrap();
+
+        let version = migrate_settings_with(&db, &[rename_locale]).await.unwrap();
+        assert_eq!(version, 2);
+        assert_eq!(get_settings_by_pool(&db).await.unwrap().locale, "zh-CN");
+
+        // Already at version 2: the step does not run again.
+        sqlx::query("INSERT INTO settings (key, value) VALUES ('language', '\"fr-FR\"')")
+            .execute(&db)
+            .await
+            .unwrap();
+        assert_eq!(
+            migrate_settings_with(&db, &[rename_locale]).await.unwrap(),
+            2
+        );
+        assert_eq!(get_settings_by_pool(&db).await.unwrap().locale, "zh-CN");
+
+        assert_eq!(
+            migrate_settings_with(&db, settings_schema::MIGRATIONS)
+                .await
+                .unwrap(),
+            2
+        );
+    }
+
+    #[test]
+    fn schema_entries_describe_each_setting() {
+        let schema = get_settings_schema_entries();
+        let duration = schema
+            .iter()
+            .find(|entry| entry.key == "hold_duration_ms")
+            .unwrap();
+        assert_eq!(duration.kind, SettingKind::Integer);
+        assert_eq!((duration.min, duration.max), (Some(200), Some(5000)));
+        assert_eq!(duration.default, json!(1000));
+
+        let modifier = schema
+            .iter()
+            .find(|entry| entry.key == "hold_modifier")
+            .unwrap();
+        assert_eq!(modifier.kind, SettingKind::Choice);
+        assert!(modifier
+            .choices
+            .as_ref()
+            .unwrap()
+            .contains(&"none".to_string()));
+    }
+
+    #[test]
+    fn changed_keys_lists_only_differing_fields() {
+        let before = AppSettings::default();
+        let after = AppSettings {
+            locale: "zh-CN".to_string(),
+            hold_duration_ms: 1500,
+            ..AppSettings::default()
+        };
+
+        let mut changed = changed_keys(&before, &after);
+        changed.sort();
+        assert_eq!(changed, vec!["hold_duration_ms", "locale"]);
+        assert!(changed_keys(&after, &after).is_empty());
     }
 }
@@ -0,0 +1,144 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::SqlitePool;
+use tauri::{AppHandle, State};
+
+use crate::db::Db;
+use crate::i18n;
+use crate::sync;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncEntity {
+    Category,
+    Item,
+}
+
+impl SyncEntity {
+    /// In sync order: categories before the items that refer to them.
+    pub const ALL: [SyncEntity; 2] = [SyncEntity::Category, SyncEntity::Item];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SyncEntity::Category => "category",
+            SyncEntity::Item => "item",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        SyncEntity::ALL
+            .into_iter()
+            .find(|entity| entity.as_str() == s)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncSide {
+    /// This device's database.
+    Local,
+    /// The sync folder, i.e. another device.
+    Remote,
+}
+
+impl SyncSide {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SyncSide::Local => "local",
+            SyncSide::Remote => "remote",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        [SyncSide::Local, SyncSide::Remote]
+            .into_iter()
+            .find(|side| side.as_str() == s)
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SyncReport {
+    /// Entities changed in the database.
+    pub pulled: u32,
+    /// Entities written to the folder.
+    pub pushed: u32,
+    /// Entities edited on both sides in ways that could not be merged.
+    pub conflicts: u32,
+    /// Items from the folder left out because their category is not known
+    /// here; they are tried again on the next run.
+    pub skipped: u32,
+}
+
+/// Concurrent edits to the same field of an entity. The newer edit was
+/// applied; both versions are kept so the user can redo the other by hand.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncConflict {
+    pub id: i64,
+    pub entity: SyncEntity,
+    pub sync_id: String,
+    /// Both versions as synced records (`fields`, `updated_at`, ...).
+    pub local: Value,
+    pub remote: Value,
+    /// Which version is now in the library.
+    pub kept: SyncSide,
+    pub detected_at: String,
+}
+
+// --- Pool-based functions (testable) ---
+
+/// Newest first.
+pub async fn list_sync_conflicts_by_pool(db: &SqlitePool) -> Result<Vec<SyncConflict>, String> {
+    let rows: Vec<(i64, String, String, String, String, String, String)> = sqlx::query_as(
+        "SELECT id, entity, sync_id, local, remote, kept, detected_at
+         FROM sync_conflicts ORDER BY id DESC",
+    )
+    .fetch_all(db)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(rows
+        .into_iter()
+        .filter_map(|(id, entity, sync_id, local, remote, kept, detected_at)| {
+            Some(SyncConflict {
+                id,
+                entity: SyncEntity::parse(&entity)?,
+                sync_id,
+                local: serde_json::from_str(&local).ok()?,
+                remote: serde_json::from_str(&remote).ok()?,
+                kept: SyncSide::parse(&kept)?,
+                detected_at,
+            })
+        })
+        .collect())
+}
+
+pub async fn dismiss_sync_conflict_by_pool(db: &SqlitePool, id: i64) -> Result<(), String> {
+    let result = sqlx::query("DELETE FROM sync_conflicts WHERE id = ?")
+        .bind(id)
+        .execute(db)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if result.rows_affected() == 0 {
+        return Err(i18n::tf("error.sync_conflict_not_found", &[("id", &id)]));
+    }
+    Ok(())
+}
+
+// --- Tauri commands ---
+
+/// Sync with the folder from the `sync_folder` setting right away.
+#[tauri::command]
+pub async fn sync_now(app: AppHandle) -> Result<SyncReport, String> {
+    sync::sync_now(&app).await
+}
+
+#[tauri::command]
+pub async fn list_sync_conflicts(db: State<'_, Db>) -> Result<Vec<SyncConflict>, String> {
+    list_sync_conflicts_by_pool(&db.pool()).await
+}
+
+#[tauri::command]
+pub async fn dismiss_sync_conflict(db: State<'_, Db>, id: i64) -> Result<(), String> {
+    dismiss_sync_conflict_by_pool(&db.pool(), id).await
+}
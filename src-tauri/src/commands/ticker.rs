@@ -0,0 +1,246 @@
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use tauri::{AppHandle, State};
+
+use crate::clock::{Clock, SystemClock};
+use crate::commands::items::{get_item_by_pool, Item};
+use crate::db::Db;
+use crate::tray_ticker::{self, TrayTicker};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TickerStatus {
+    pub paused: bool,
+    /// Pinned items in rotation order.
+    pub item_ids: Vec<i64>,
+    /// Title currently shown by the ticker, if it is rotating.
+    pub current: Option<String>,
+}
+
+// --- Pool-based functions (testable) ---
+
+/// Pinned items in rotation order, except those past their expiry by
+/// `clock`; they stay pinned until the cleanup task removes them.
+pub async fn get_ticker_items_by_pool(
+    db: &SqlitePool,
+    clock: &dyn Clock,
+) -> Result<Vec<Item>, String> {
+    sqlx::query_as::<_, Item>(
+        "SELECT i.id, i.category_id, i.label, i.value, i.sort_order, i.created_at, i.updated_at,
+                i.expires_at, i.kind
+         FROM ticker_items t JOIN items i ON i.id = t.item_id
+         WHERE i.expires_at IS NULL OR i.expires_at > datetime(?, 'unixepoch')
+         ORDER BY t.sort_order, t.item_id",
+    )
+    .bind(clock.unix_secs())
+    .fetch_all(db)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Replace the pinned items. Duplicates keep their first position; unknown
+/// items fail the whole call.
+pub async fn set_ticker_items_by_pool(db: &SqlitePool, item_ids: &[i64]) -> Result<(), String> {
+    let mut unique: Vec<i64> = Vec::with_capacity(item_ids.len());
+    for id in item_ids {
+        if !unique.contains(id) {
+            get_item_by_pool(db, *id).await?;
+            unique.push(*id);
+        }
+    }
+
+    let mut tx = db.begin().await.map_err(|e| e.to_string())?;
+    sqlx::query("DELETE FROM ticker_items")
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    for (position, id) in unique.iter().enumerate() {
+        sqlx::query("INSERT INTO ticker_items (item_id, sort_order) VALUES (?, ?)")
+            .bind(id)
+            .bind(position as i64)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    tx.commit().await.map_err(|e| e.to_string())
+}
+
+async fn status(db: &SqlitePool, ticker: &TrayTicker) -> Result<TickerStatus, String> {
+    let items = get_ticker_items_by_pool(db, &SystemClock).await?;
+    Ok(ticker.status(items.iter().map(|item| item.id).collect()))
+}
+
+// --- Tauri commands ---
+
+#[tauri::command]
+pub async fn get_ticker_status(
+    db: State<'_, Db>,
+    ticker: State<'_, TrayTicker>,
+) -> Result<TickerStatus, String> {
+    status(&db.pool(), ticker.inner()).await
+}
+
+#[tauri::command]
+pub async fn set_ticker_items(
+    app: AppHandle,
+    db: State<'_, Db>,
+    ticker: State<'_, TrayTicker>,
+    item_ids: Vec<i64>,
+) -> Result<TickerStatus, String> {
+    set_ticker_items_by_pool(&db.pool(), &item_ids).await?;
+    tray_ticker::reload(&app).await?;
+    status(&db.pool(), ticker.inner()).await
+}
+
+#[tauri::command]
+pub async fn pause_ticker(
+    db: State<'_, Db>,
+    ticker: State<'_, TrayTicker>,
+) -> Result<TickerStatus, String> {
+    ticker.set_paused(true);
+    status(&db.pool(), ticker.inner()).await
+}
+
+#[tauri::command]
+pub async fn resume_ticker(
+    db: State<'_, Db>,
+    ticker: State<'_, TrayTicker>,
+) -> Result<TickerStatus, String> {
+    ticker.set_paused(false);
+    status(&db.pool(), ticker.inner()).await
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::clock::ManualClock;
+    use crate::commands::categories::{create_category_by_pool, CreateCategoryInput};
+    use crate::commands::items::{
+        create_item_by_pool, delete_item_by_pool, set_item_expiry_by_pool, CreateItemInput,
+        ItemKind,
+    };
+
+    async fn setup_db() -> SqlitePool {
+        let db = SqlitePool::connect("sqlite::memory:")
+            .await
+            .expect("connect sqlite memory");
+        for (name, sql) in [
+            ("002", include_str!("../../migrations/002_peeky_domain.sql")),
+            ("006", include_str!("../../migrations/006_tray_ticker.sql")),
+            ("009", include_str!("../../migrations/009_item_kind.sql")),
+            (
+                "010",
+                include_str!("../../migrations/010_category_links.sql"),
+            ),
+            ("012", include_str!("../../migrations/012_item_expiry.sql")),
+        ] {
+            sqlx::query(sql)
+                .execute(&db)
+                .await
+                .unwrap_or_else(|e| panic!("run migration {name}: {e}"));
+        }
+        db
+    }
+
+    async fn create_items(db: &SqlitePool, labels: &[&str]) -> Vec<i64> {
+        let category = create_category_by_pool(
+            db,
+            CreateCategoryInput {
+                name: "Pinned".to_string(),
+            },
+        )
+        .await
+        .expect("create category");
+        let mut ids = Vec::new();
+        for label in labels {
+            let item = create_item_by_pool(
+                db,
+                CreateItemInput {
+                    category_id: category.id,
+                    label: label.to_string(),
+                    value: Some(format!("{label} value")),
+                    kind: ItemKind::Text,
+                },
+            )
+            .await
+            .expect("create item");
+            ids.push(item.id);
+        }
+        ids
+    }
+
+    #[tokio::test]
+    async fn set_ticker_items_keeps_order_and_drops_duplicates() {
+        let db = setup_db().await;
+        let ids = create_items(&db, &["On-call", "Sprint", "Standup"]).await;
+
+        set_ticker_items_by_pool(&db, &[ids[2], ids[0], ids[2]])
+            .await
+            .expect("set ticker items");
+
+        let pinned: Vec<i64> = get_ticker_items_by_pool(&db, &SystemClock)
+            .await
+            .unwrap()
+            .iter()
+            .map(|item| item.id)
+            .collect();
+        assert_eq!(pinned, vec![ids[2], ids[0]]);
+    }
+
+    #[tokio::test]
+    async fn set_ticker_items_rejects_unknown_items() {
+        let db = setup_db().await;
+        let ids = create_items(&db, &["On-call"]).await;
+        set_ticker_items_by_pool(&db, &ids).await.unwrap();
+
+        assert!(set_ticker_items_by_pool(&db, &[ids[0], 999]).await.is_err());
+        // The previous selection is left untouched.
+        assert_eq!(
+            get_ticker_items_by_pool(&db, &SystemClock)
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn deleted_items_leave_the_rotation() {
+        let db = setup_db().await;
+        let ids = create_items(&db, &["On-call", "Sprint"]).await;
+        set_ticker_items_by_pool(&db, &ids).await.unwrap();
+
+        delete_item_by_pool(&db, ids[0]).await.expect("delete item");
+
+        let pinned = get_ticker_items_by_pool(&db, &SystemClock).await.unwrap();
+        assert_eq!(pinned.len(), 1);
+        assert_eq!(pinned[0].label, "Sprint");
+    }
+
+    #[tokio::test]
+    async fn expired_items_leave_the_rotation_by_the_clock() {
+        let db = setup_db().await;
+        let clock = ManualClock::new();
+        let ids = create_items(&db, &["Room code", "Sprint"]).await;
+        set_ticker_items_by_pool(&db, &ids).await.unwrap();
+        let at: String = sqlx::query_scalar("SELECT datetime(?, 'unixepoch')")
+            .bind(clock.unix_secs() + 60)
+            .fetch_one(&db)
+            .await
+            .unwrap();
+        set_item_expiry_by_pool(&db, ids[0], Some(&at))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            get_ticker_items_by_pool(&db, &clock).await.unwrap().len(),
+            2
+        );
+
+        clock.advance(Duration::from_secs(60));
+        let pinned = get_ticker_items_by_pool(&db, &clock).await.unwrap();
+        assert_eq!(pinned.len(), 1);
+        assert_eq!(pinned[0].label, "Sprint");
+    }
+}
@@ -0,0 +1,468 @@
+//! Control socket: lets window managers, scripts and the `peeky` CLI drive
+//! the running app without synthesized keystrokes.
+//!
+//! The app listens on a Unix domain socket in a private `control/` folder
+//! next to `peeky.db` (a per-user named pipe on Windows). Clients write one JSON request per line, such as
+//! `{"command":"show_overlay","category":"Git"}`, and get one JSON response
+//! line back, `{"ok":true}` or `{"ok":false,"error":"..."}`. Connections
+//! may send any number of requests.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use tauri::{AppHandle, Manager};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::task::JoinHandle;
+
+use crate::commands::categories::find_category_by_name_by_pool;
+use crate::db::Db;
+use crate::i18n;
+use crate::library;
+use crate::overlay;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum ControlRequest {
+    /// Show the overlay, limited to the category with this name if given.
+    ShowOverlay {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        category: Option<String>,
+    },
+    HideOverlay,
+    ToggleOverlay,
+    /// Show and focus the main window.
+    OpenMain,
+    /// Re-read the library after another program changed the database.
+    Reload,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ControlResponse {
+    pub ok: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl ControlResponse {
+    fn ok() -> Self {
+        Self {
+            ok: true,
+            error: None,
+        }
+    }
+
+    fn error(message: String) -> Self {
+        Self {
+            ok: false,
+            error: Some(message),
+        }
+    }
+}
+
+/// What the requests act on.
+pub trait Controller: Clone + Send + Sync + 'static {
+    fn pool(&self) -> SqlitePool;
+    fn show_overlay(&self, category_id: Option<i64>);
+    fn hide_overlay(&self);
+    fn toggle_overlay(&self);
+    fn open_main(&self);
+    fn reload(&self);
+}
+
+impl Controller for AppHandle {
+    fn pool(&self) -> SqlitePool {
+        self.state::<Db>().pool()
+    }
+
+    fn show_overlay(&self, category_id: Option<i64>) {
+        let app = self.clone();
+        let _ = self.run_on_main_thread(move || overlay::show_category(&app, category_id));
+    }
+
+    fn hide_overlay(&self) {
+        let app = self.clone();
+        let _ = self.run_on_main_thread(move || overlay::hide(&app));
+    }
+
+    fn toggle_overlay(&self) {
+        let app = self.clone();
+        let _ = self.run_on_main_thread(move || overlay::toggle(&app));
+    }
+
+    fn open_main(&self) {
+        let app = self.clone();
+        let _ = self.run_on_main_thread(move || {
+            if let Some(window) = app.get_webview_window("main") {
+                let _ = window.show();
+                let _ = window.set_focus();
+            }
+        });
+    }
+
+    fn reload(&self) {
+        reload_library(self);
+    }
+}
+
+/// [`library::refresh`] in the background, for callers that must not wait.
+pub fn reload_library(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move { library::refresh(&app).await });
+}
+
+/// Where the app listens when its database is at `db_path`.
+pub fn endpoint(db_path: &Path) -> PathBuf {
+    #[cfg(unix)]
+    {
+        db_path.with_file_name("control").join("peeky.sock")
+    }
+    #[cfg(windows)]
+    {
+        let _ = db_path;
+        let user = std::env::var("USERNAME").unwrap_or_default();
+        PathBuf::from(format!(r"\\.\pipe\peeky-{user}"))
+    }
+}
+
+async fn dispatch<C: Controller>(controller: &C, request: ControlRequest) -> ControlResponse {
+    match request {
+        ControlRequest::ShowOverlay { category: None } => controller.show_overlay(None),
+        ControlRequest::ShowOverlay {
+            category: Some(name),
+        } => match find_category_by_name_by_pool(&controller.pool(), &name).await {
+            Ok(Some(category)) => controller.show_overlay(Some(category.id)),
+            Ok(None) => {
+                return ControlResponse::error(i18n::tf(
+                    "error.category_name_not_found",
+                    &[("name", &name)],
+                ))
+            }
+            Err(err) => return ControlResponse::error(err),
+        },
+        ControlRequest::HideOverlay => controller.hide_overlay(),
+        ControlRequest::ToggleOverlay => controller.toggle_overlay(),
+        ControlRequest::OpenMain => controller.open_main(),
+        ControlRequest::Reload => controller.reload(),
+    }
+    ControlResponse::ok()
+}
+
+async fn serve_connection<S, C>(stream: S, controller: C)
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+    C: Controller,
+{
+    let (reader, mut writer) = tokio::io::split(stream);
+    let mut lines = BufReader::new(reader).lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let response = match serde_json::from_str::<ControlRequest>(&line) {
+            Ok(request) => dispatch(&controller, request).await,
+            Err(err) => ControlResponse::error(err.to_string()),
+        };
+        let Ok(mut json) = serde_json::to_string(&response) else {
+            break;
+        };
+        json.push('\n');
+        if writer.write_all(json.as_bytes()).await.is_err() {
+            break;
+        }
+    }
+}
+
+/// Accept connections on `endpoint` until the returned task is aborted.
+/// Fails if another process is already listening there.
+#[cfg(unix)]
+pub async fn listen<C: Controller>(
+    endpoint: &Path,
+    controller: C,
+) -> Result<JoinHandle<()>, String> {
+    use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+    use tokio::net::{UnixListener, UnixStream};
+
+    // The socket is only made private after `bind`; until then, the folder
+    // keeps other users from connecting.
+    if let Some(dir) = endpoint.parent() {
+        std::fs::DirBuilder::new()
+            .recursive(true)
+            .mode(0o700)
+            .create(dir)
+            .map_err(|e| e.to_string())?;
+        std::fs::set_permissions(dir, std::fs::Permissions::from_mode(0o700))
+            .map_err(|e| e.to_string())?;
+    }
+    if endpoint.exists() {
+        if UnixStream::connect(endpoint).await.is_ok() {
+            return Err(i18n::tf(
+                "error.control_in_use",
+                &[("path", &endpoint.display())],
+            ));
+        }
+        // Left behind by an instance that did not shut down cleanly.
+        let _ = std::fs::remove_file(endpoint);
+    }
+    let listener = UnixListener::bind(endpoint).map_err(|e| e.to_string())?;
+    std::fs::set_permissions(endpoint, std::fs::Permissions::from_mode(0o600))
+        .map_err(|e| e.to_string())?;
+    Ok(tokio::spawn(async move {
+        loop {
+            match listener.accept().await {
+                Ok((stream, _)) => {
+                    tokio::spawn(serve_connection(stream, controller.clone()));
+                }
+                Err(err) => eprintln!("control socket accept failed: {err}"),
+            }
+        }
+    }))
+}
+
+/// Accept connections on `endpoint` until the returned task is aborted.
+/// Fails if another process is already listening there.
+#[cfg(windows)]
+pub async fn listen<C: Controller>(
+    endpoint: &Path,
+    controller: C,
+) -> Result<JoinHandle<()>, String> {
+    use tokio::net::windows::named_pipe::ServerOptions;
+
+    let name = endpoint.as_os_str().to_owned();
+    let mut server = ServerOptions::new()
+        .first_pipe_instance(true)
+        .create(&name)
+        .map_err(|_| i18n::tf("error.control_in_use", &[("path", &endpoint.display())]))?;
+    Ok(tokio::spawn(async move {
+        loop {
+            if let Err(err) = server.connect().await {
+                eprintln!("control pipe connect failed: {err}");
+                return;
+            }
+            let next = match ServerOptions::new().create(&name) {
+                Ok(next) => next,
+                Err(err) => {
+                    eprintln!("control pipe create failed: {err}");
+                    return;
+                }
+            };
+            let connected = std::mem::replace(&mut server, next);
+            tokio::spawn(serve_connection(connected, controller.clone()));
+        }
+    }))
+}
+
+/// Send one request to the app listening on `endpoint`.
+pub async fn send(endpoint: &Path, request: &ControlRequest) -> Result<ControlResponse, String> {
+    #[cfg(unix)]
+    let stream = tokio::net::UnixStream::connect(endpoint).await;
+    #[cfg(windows)]
+    let stream = tokio::net::windows::named_pipe::ClientOptions::new().open(endpoint);
+    let stream = stream.map_err(|_| i18n::t("error.control_not_running"))?;
+
+    let (reader, mut writer) = tokio::io::split(stream);
+    let mut line = serde_json::to_string(request).map_err(|e| e.to_string())?;
+    line.push('\n');
+    writer
+        .write_all(line.as_bytes())
+        .await
+        .map_err(|e| e.to_string())?;
+    let response = BufReader::new(reader)
+        .lines()
+        .next_line()
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| i18n::t("error.control_not_running"))?;
+    serde_json::from_str(&response).map_err(|e| e.to_string())
+}
+
+/// Listen for control requests for as long as the app runs.
+pub fn start(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let endpoint = endpoint(app.state::<Db>().path());
+        if let Err(err) = listen(&endpoint, app.clone()).await {
+            eprintln!("control socket unavailable: {err}");
+        }
+    });
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    use crate::commands::categories::{create_category_by_pool, CreateCategoryInput};
+
+    #[derive(Clone)]
+    struct TestController {
+        pool: SqlitePool,
+        calls: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl TestController {
+        fn record(&self, call: String) {
+            self.calls.lock().unwrap().push(call);
+        }
+    }
+
+    impl Controller for TestController {
+        fn pool(&self) -> SqlitePool {
+            self.pool.clone()
+        }
+
+        fn show_overlay(&self, category_id: Option<i64>) {
+            self.record(format!("show {category_id:?}"));
+        }
+
+        fn hide_overlay(&self) {
+            self.record("hide".to_string());
+        }
+
+        fn toggle_overlay(&self) {
+            self.record("toggle".to_string());
+        }
+
+        fn open_main(&self) {
+            self.record("open".to_string());
+        }
+
+        fn reload(&self) {
+            self.record("reload".to_string());
+        }
+    }
+
+    async fn setup() -> (tempfile::TempDir, PathBuf, TestController) {
+        let pool = SqlitePool::connect("sqlite::memory:")
+            .await
+            .expect("connect sqlite memory");
+        sqlx::query(include_str!("../migrations/002_peeky_domain.sql"))
+            .execute(&pool)
+            .await
+            .expect("run migration 002");
+        let dir = tempfile::tempdir().unwrap();
+        let endpoint = endpoint(&dir.path().join("peeky.db"));
+        let controller = TestController {
+            pool,
+            calls: Arc::default(),
+        };
+        (dir, endpoint, controller)
+    }
+
+    #[test]
+    fn requests_use_the_documented_wire_format() {
+        let request: ControlRequest =
+            serde_json::from_str(r#"{"command":"show_overlay","category":"Git"}"#).unwrap();
+        assert_eq!(
+            request,
+            ControlRequest::ShowOverlay {
+                category: Some("Git".to_string())
+            }
+        );
+        assert_eq!(
+            serde_json::to_string(&ControlRequest::HideOverlay).unwrap(),
+            r#"{"command":"hide_overlay"}"#
+        );
+    }
+
+    #[tokio::test]
+    async fn dispatches_requests_from_clients() {
+        let (_dir, endpoint, controller) = setup().await;
+        let git = create_category_by_pool(
+            &controller.pool,
+            CreateCategoryInput {
+                name: "Git".to_string(),
+            },
+        )
+        .await
+        .unwrap();
+        let server = listen(&endpoint, controller.clone()).await.unwrap();
+
+        for request in [
+            ControlRequest::ShowOverlay { category: None },
+            ControlRequest::ShowOverlay {
+                category: Some("git".to_string()),
+            },
+            ControlRequest::ToggleOverlay,
+            ControlRequest::OpenMain,
+            ControlRequest::Reload,
+            ControlRequest::HideOverlay,
+        ] {
+            assert_eq!(
+                send(&endpoint, &request).await.unwrap(),
+                ControlResponse::ok()
+            );
+        }
+        let unknown = send(
+            &endpoint,
+            &ControlRequest::ShowOverlay {
+                category: Some("Docker".to_string()),
+            },
+        )
+        .await
+        .unwrap();
+        assert!(!unknown.ok);
+
+        assert_eq!(
+            *controller.calls.lock().unwrap(),
+            vec![
+                "show None".to_string(),
+                format!("show Some({})", git.id),
+                "toggle".to_string(),
+                "open".to_string(),
+                "reload".to_string(),
+                "hide".to_string(),
+            ]
+        );
+        server.abort();
+    }
+
+    #[tokio::test]
+    async fn answers_each_line_of_a_connection() {
+        let (_dir, endpoint, controller) = setup().await;
+        let _server = listen(&endpoint, controller).await.unwrap();
+
+        let stream = tokio::net::UnixStream::connect(&endpoint).await.unwrap();
+        let (reader, mut writer) = tokio::io::split(stream);
+        writer
+            .write_all(b"{\"command\":\"reload\"}\nnot json\n")
+            .await
+            .unwrap();
+        let mut lines = BufReader::new(reader).lines();
+        assert_eq!(lines.next_line().await.unwrap().unwrap(), r#"{"ok":true}"#);
+        let error: ControlResponse =
+            serde_json::from_str(&lines.next_line().await.unwrap().unwrap()).unwrap();
+        assert!(!error.ok && error.error.is_some());
+    }
+
+    #[tokio::test]
+    async fn refuses_a_live_socket_and_replaces_a_stale_one() {
+        let (_dir, endpoint, controller) = setup().await;
+        let first = listen(&endpoint, controller.clone()).await.unwrap();
+        assert!(listen(&endpoint, controller.clone()).await.is_err());
+
+        first.abort();
+        let _ = first.await;
+        // The socket file is still there, but nobody answers.
+        assert!(endpoint.exists());
+        let _second = listen(&endpoint, controller).await.unwrap();
+        assert!(send(&endpoint, &ControlRequest::Reload).await.unwrap().ok);
+    }
+
+    #[tokio::test]
+    async fn send_reports_when_the_app_is_not_running() {
+        let (_dir, endpoint, _) = setup().await;
+        assert!(send(&endpoint, &ControlRequest::Reload).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn only_the_owner_can_reach_the_socket() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let (_dir, endpoint, controller) = setup().await;
+        let _server = listen(&endpoint, controller).await.unwrap();
+        let mode = |path: &Path| std::fs::metadata(path).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode(endpoint.parent().unwrap()), 0o700);
+        assert_eq!(mode(&endpoint), 0o600);
+    }
+}
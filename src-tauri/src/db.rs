@@ -1,7 +1,15 @@
+use std::borrow::Cow;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+use sqlx::migrate::{Migration as SqlxMigration, MigrationType, Migrator};
 use sqlx::sqlite::SqlitePoolOptions;
 use sqlx::SqlitePool;
 use tauri_plugin_sql::{Migration, MigrationKind};
 
+/// Connection string the SQL plugin preloads and migrates at startup.
+pub const DB_URL: &str = "sqlite:peeky.db";
+
 pub fn migrations() -> Vec<Migration> {
     vec![
         Migration {
@@ -16,9 +24,137 @@ pub fn migrations() -> Vec<Migration> {
             sql: include_str!("../migrations/002_peeky_domain.sql"),
             kind: MigrationKind::Up,
         },
+        Migration {
+            version: 3,
+            description: "add_hold_to_peek_settings",
+            sql: include_str!("../migrations/003_hold_to_peek.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 4,
+            description: "create_hotkey_bindings_table",
+            sql: include_str!("../migrations/004_hotkey_bindings.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 5,
+            description: "move_settings_to_key_value_store",
+            sql: include_str!("../migrations/005_settings_store.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 6,
+            description: "create_ticker_items_table",
+            sql: include_str!("../migrations/006_tray_ticker.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 7,
+            description: "add_folder_sync_tables",
+            sql: include_str!("../migrations/007_folder_sync.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 8,
+            description: "add_change_feed",
+            sql: include_str!("../migrations/008_change_feed.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 9,
+            description: "add_item_kind",
+            sql: include_str!("../migrations/009_item_kind.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 10,
+            description: "add_category_links",
+            sql: include_str!("../migrations/010_category_links.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 11,
+            description: "add_item_revisions",
+            sql: include_str!("../migrations/011_item_revisions.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 12,
+            description: "add_item_expiry",
+            sql: include_str!("../migrations/012_item_expiry.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 13,
+            description: "move_http_api_token",
+            sql: include_str!("../migrations/013_http_api_token.sql"),
+            kind: MigrationKind::Up,
+        },
     ]
 }
 
+/// The plugin migrations as a sqlx [`Migrator`], for databases the plugin
+/// does not open itself. Checksums match the plugin's, so either can pick up
+/// where the other left off.
+pub fn migrator() -> Migrator {
+    let migrations = migrations()
+        .into_iter()
+        .filter(|migration| matches!(migration.kind, MigrationKind::Up))
+        .map(|migration| {
+            SqlxMigration::new(
+                migration.version,
+                migration.description.into(),
+                MigrationType::ReversibleUp,
+                migration.sql.into(),
+                false,
+            )
+        })
+        .collect::<Vec<_>>();
+    Migrator {
+        migrations: Cow::Owned(migrations),
+        ..Migrator::DEFAULT
+    }
+}
+
+/// Latest schema version this build knows about.
+pub fn latest_version() -> i64 {
+    migrations()
+        .iter()
+        .map(|migration| migration.version)
+        .max()
+        .unwrap_or(0)
+}
+
+/// Managed handle to the database. Restoring a backup swaps the pool, so
+/// take a fresh clone with [`Db::pool`] for each operation instead of
+/// keeping one around.
+pub struct Db {
+    path: PathBuf,
+    pool: RwLock<SqlitePool>,
+}
+
+impl Db {
+    pub fn new(path: PathBuf, pool: SqlitePool) -> Self {
+        Self {
+            path,
+            pool: RwLock::new(pool),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn pool(&self) -> SqlitePool {
+        self.pool.read().unwrap().clone()
+    }
+
+    /// Install a new pool, returning the previous one.
+    pub fn set_pool(&self, pool: SqlitePool) -> SqlitePool {
+        std::mem::replace(&mut *self.pool.write().unwrap(), pool)
+    }
+}
+
 pub async fn create_pool(db_path: &str) -> Result<SqlitePool, sqlx::Error> {
     let db_url = format!("sqlite:{}?mode=rwc", db_path);
     SqlitePoolOptions::new()
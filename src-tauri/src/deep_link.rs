@@ -0,0 +1,512 @@
+//! `peeky://` links, so wikis and chat messages can add and open memos:
+//!
+//! - `peeky://add?category=Git&label=Squash&value=git%20rebase%20-i`
+//! - `peeky://open?category=Oncall` (main window, on that category)
+//! - `peeky://show` or `peeky://show?category=Oncall` (overlay)
+//! - `peeky://import?format=markdown&data=...` (a whole library file, see
+//!   [`crate::library_file`]; `format` is `markdown` or `json`)
+//!
+//! Links come from anywhere, so [`parse`] is strict: unknown actions or
+//! parameters, repeated parameters, fragments and malformed escapes are all
+//! rejected rather than guessed at. Links that change the library are only
+//! carried out after the user confirms them.
+
+use sqlx::SqlitePool;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_deep_link::DeepLinkExt;
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+
+use crate::commands::categories::{
+    create_category_by_pool, find_category_by_name_by_pool, Category, CreateCategoryInput,
+};
+use crate::commands::items::{create_item_by_pool, CreateItemInput, ItemKind};
+use crate::commands::library::ImportMode;
+use crate::control;
+use crate::db::Db;
+use crate::events::{self, ChangeKind, LibraryChanged, SelectCategory};
+use crate::i18n;
+use crate::library_file::{self, LibraryFile};
+use crate::overlay;
+
+const PREFIX: &str = "peeky://";
+
+/// Longer links are refused before they are decoded; imports are meant for
+/// snippets, not whole backups.
+const MAX_LINK_LEN: usize = 64 * 1024;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeepLinkAction {
+    AddItem {
+        category: String,
+        label: String,
+        value: Option<String>,
+    },
+    OpenCategory {
+        category: String,
+    },
+    ShowOverlay {
+        category: Option<String>,
+    },
+    Import {
+        library: LibraryFile,
+    },
+}
+
+impl DeepLinkAction {
+    /// The question to ask before carrying out a link that changes the
+    /// library, or `None` if it only shows something.
+    pub fn confirmation(&self) -> Option<String> {
+        match self {
+            DeepLinkAction::AddItem {
+                category,
+                label,
+                value,
+            } => {
+                let question = i18n::tf(
+                    "deep_link.confirm_add",
+                    &[("label", label), ("category", category)],
+                );
+                Some(match value {
+                    Some(value) if !value.is_empty() => format!("{question}\n\n{value}"),
+                    _ => question,
+                })
+            }
+            DeepLinkAction::Import { library } => {
+                let items: usize = library.categories.iter().map(|c| c.items.len()).sum();
+                Some(i18n::tf(
+                    "deep_link.confirm_import",
+                    &[("items", &items), ("categories", &library.categories.len())],
+                ))
+            }
+            DeepLinkAction::OpenCategory { .. } | DeepLinkAction::ShowOverlay { .. } => None,
+        }
+    }
+}
+
+fn invalid(link: &str) -> String {
+    i18n::tf("error.deep_link_invalid", &[("link", &link)])
+}
+
+fn hex_value(byte: u8) -> Option<u8> {
+    match byte {
+        b'0'..=b'9' => Some(byte - b'0'),
+        b'a'..=b'f' => Some(byte - b'a' + 10),
+        b'A'..=b'F' => Some(byte - b'A' + 10),
+        _ => None,
+    }
+}
+
+/// Decode one `application/x-www-form-urlencoded` component. Characters
+/// that must be escaped in a URL (spaces, quotes, non-ASCII...) are refused
+/// when they appear raw, as are control characters other than line breaks
+/// and tabs once decoded.
+fn decode(component: &str) -> Option<String> {
+    let bytes = component.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' => {
+                let high = hex_value(*bytes.get(i + 1)?)?;
+                let low = hex_value(*bytes.get(i + 2)?)?;
+                out.push(high << 4 | low);
+                i += 3;
+                continue;
+            }
+            b'+' => out.push(b' '),
+            b if b.is_ascii_alphanumeric() || b"-._~!$'()*,;:@/?".contains(&b) => out.push(b),
+            _ => return None,
+        }
+        i += 1;
+    }
+    let text = String::from_utf8(out).ok()?;
+    let allowed = |c: char| !c.is_control() || matches!(c, '\n' | '\r' | '\t');
+    text.chars().all(allowed).then_some(text)
+}
+
+/// The query parameters of a link, each of which may be read once.
+struct Params(Vec<(String, String)>);
+
+impl Params {
+    /// Split `query`, refusing names not in `allowed` and repeated names.
+    fn parse(query: &str, allowed: &[&str], link: &str) -> Result<Self, String> {
+        let mut params: Vec<(String, String)> = Vec::new();
+        for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+            let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+            let name = decode(name).ok_or_else(|| invalid(link))?;
+            let value = decode(value).ok_or_else(|| invalid(link))?;
+            if !allowed.contains(&name.as_str()) {
+                return Err(i18n::tf(
+                    "error.deep_link_unknown_param",
+                    &[("name", &name)],
+                ));
+            }
+            if params.iter().any(|(seen, _)| *seen == name) {
+                return Err(i18n::tf(
+                    "error.deep_link_duplicate_param",
+                    &[("name", &name)],
+                ));
+            }
+            params.push((name, value));
+        }
+        Ok(Self(params))
+    }
+
+    fn optional(&mut self, name: &str) -> Option<String> {
+        let index = self.0.iter().position(|(seen, _)| seen == name)?;
+        Some(self.0.swap_remove(index).1)
+    }
+
+    /// A single-line, non-blank parameter such as a name or label.
+    fn optional_name(&mut self, name: &str) -> Result<Option<String>, String> {
+        match self.optional(name) {
+            None => Ok(None),
+            Some(value) => {
+                let value = value.trim();
+                if value.is_empty() || value.contains(['\n', '\r']) {
+                    return Err(i18n::tf("error.deep_link_bad_value", &[("name", &name)]));
+                }
+                Ok(Some(value.to_string()))
+            }
+        }
+    }
+
+    fn required_name(&mut self, name: &str) -> Result<String, String> {
+        self.optional_name(name)?
+            .ok_or_else(|| i18n::tf("error.deep_link_missing_param", &[("name", &name)]))
+    }
+}
+
+/// Parse a `peeky://` link into what it asks for.
+pub fn parse(link: &str) -> Result<DeepLinkAction, String> {
+    if link.len() > MAX_LINK_LEN {
+        return Err(i18n::t("error.deep_link_too_long"));
+    }
+    let rest = link
+        .get(..PREFIX.len())
+        .filter(|scheme| scheme.eq_ignore_ascii_case(PREFIX))
+        .map(|_| &link[PREFIX.len()..])
+        .ok_or_else(|| invalid(link))?;
+    if rest.contains('#') {
+        return Err(invalid(link));
+    }
+    let (action, query) = rest.split_once('?').unwrap_or((rest, ""));
+    let action = action.strip_suffix('/').unwrap_or(action);
+
+    match action {
+        "add" => {
+            let mut params = Params::parse(query, &["category", "label", "value"], link)?;
+            Ok(DeepLinkAction::AddItem {
+                category: params.required_name("category")?,
+                label: params.required_name("label")?,
+                value: params.optional("value"),
+            })
+        }
+        "open" => {
+            let mut params = Params::parse(query, &["category"], link)?;
+            Ok(DeepLinkAction::OpenCategory {
+                category: params.required_name("category")?,
+            })
+        }
+        "show" => {
+            let mut params = Params::parse(query, &["category"], link)?;
+            Ok(DeepLinkAction::ShowOverlay {
+                category: params.optional_name("category")?,
+            })
+        }
+        "import" => {
+            let mut params = Params::parse(query, &["format", "data"], link)?;
+            let data = params
+                .optional("data")
+                .ok_or_else(|| i18n::tf("error.deep_link_missing_param", &[("name", &"data")]))?;
+            let library = match params.optional_name("format")?.as_deref() {
+                None | Some("markdown") => library_file::parse_markdown(&data),
+                Some("json") => serde_json::from_str::<LibraryFile>(&data)
+                    .map_err(|_| i18n::tf("error.deep_link_bad_value", &[("name", &"data")]))?,
+                Some(_) => {
+                    return Err(i18n::tf(
+                        "error.deep_link_bad_value",
+                        &[("name", &"format")],
+                    ))
+                }
+            };
+            if library.categories.iter().all(|c| c.items.is_empty()) {
+                return Err(i18n::t("error.deep_link_empty_import"));
+            }
+            Ok(DeepLinkAction::Import { library })
+        }
+        _ => Err(i18n::tf(
+            "error.deep_link_unknown_action",
+            &[("action", &action)],
+        )),
+    }
+}
+
+async fn existing_category(db: &SqlitePool, name: &str) -> Result<Category, String> {
+    find_category_by_name_by_pool(db, name)
+        .await?
+        .ok_or_else(|| i18n::tf("error.category_name_not_found", &[("name", &name)]))
+}
+
+async fn perform(app: &AppHandle, action: DeepLinkAction) -> Result<(), String> {
+    let db = app.state::<Db>().pool();
+    match action {
+        DeepLinkAction::AddItem {
+            category,
+            label,
+            value,
+        } => {
+            let category = match find_category_by_name_by_pool(&db, &category).await? {
+                Some(existing) => existing,
+                None => {
+                    let created =
+                        create_category_by_pool(&db, CreateCategoryInput { name: category })
+                            .await?;
+                    events::emit(
+                        app,
+                        LibraryChanged::categories(ChangeKind::Created, vec![created.id]),
+                    );
+                    created
+                }
+            };
+            let item = create_item_by_pool(
+                &db,
+                CreateItemInput {
+                    category_id: category.id,
+                    label,
+                    value,
+                    kind: ItemKind::Text,
+                },
+            )
+            .await?;
+            events::emit(
+                app,
+                LibraryChanged::items(ChangeKind::Created, vec![item.id], item.category_id),
+            );
+        }
+        DeepLinkAction::OpenCategory { category } => {
+            let category = existing_category(&db, &category).await?;
+            if let Some(window) = app.get_webview_window("main") {
+                events::emit_to(app, "main", SelectCategory { category });
+                let _ = window.show();
+                let _ = window.set_focus();
+            }
+        }
+        DeepLinkAction::ShowOverlay { category } => {
+            let category_id = match category {
+                Some(name) => Some(existing_category(&db, &name).await?.id),
+                None => None,
+            };
+            let handle = app.clone();
+            let _ = app.run_on_main_thread(move || overlay::show_category(&handle, category_id));
+        }
+        DeepLinkAction::Import { library } => {
+            library_file::import_by_pool(&db, &library, ImportMode::Merge).await?;
+            control::reload_library(app);
+        }
+    }
+    Ok(())
+}
+
+fn report_error(app: &AppHandle, message: String) {
+    app.dialog()
+        .message(message)
+        .title(i18n::t("app.name"))
+        .kind(MessageDialogKind::Error)
+        .show(|_| {});
+}
+
+/// Carry out one link, asking first if it changes the library.
+pub fn handle(app: &AppHandle, link: &str) {
+    let action = match parse(link) {
+        Ok(action) => action,
+        Err(err) => return report_error(app, err),
+    };
+    let question = action.confirmation();
+    let run = {
+        let app = app.clone();
+        move || {
+            tauri::async_runtime::spawn(async move {
+                if let Err(err) = perform(&app, action).await {
+                    report_error(&app, err);
+                }
+            });
+        }
+    };
+    match question {
+        None => run(),
+        Some(question) => {
+            app.dialog()
+                .message(question)
+                .title(i18n::t("deep_link.confirm_title"))
+                .buttons(MessageDialogButtons::OkCancelCustom(
+                    i18n::t("deep_link.confirm_ok"),
+                    i18n::t("deep_link.confirm_cancel"),
+                ))
+                .show(move |confirmed| {
+                    if confirmed {
+                        run();
+                    }
+                });
+        }
+    }
+}
+
+/// Handle links that launched the app and every link opened while it runs.
+pub fn start(app: &AppHandle) {
+    #[cfg(any(windows, target_os = "linux"))]
+    if let Err(err) = app.deep_link().register_all() {
+        eprintln!("failed to register peeky:// links: {err}");
+    }
+
+    let app_handle = app.clone();
+    app.deep_link().on_open_url(move |event| {
+        for url in event.urls() {
+            handle(&app_handle, url.as_str());
+        }
+    });
+    if let Ok(Some(urls)) = app.deep_link().get_current() {
+        for url in urls {
+            handle(app, url.as_str());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::library_file::{FileCategory, FileItem};
+
+    fn add(category: &str, label: &str, value: Option<&str>) -> DeepLinkAction {
+        DeepLinkAction::AddItem {
+            category: category.to_string(),
+            label: label.to_string(),
+            value: value.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn parses_add_links() {
+        assert_eq!(
+            parse("peeky://add?category=Git&label=Squash&value=git%20rebase%20-i").unwrap(),
+            add("Git", "Squash", Some("git rebase -i"))
+        );
+        // Parameter order is free, `+` is a space and the value is optional.
+        assert_eq!(
+            parse("PEEKY://add/?label=Undo+add&category=Git").unwrap(),
+            add("Git", "Undo add", None)
+        );
+        // Values keep line breaks and surrounding spaces; names are trimmed.
+        assert_eq!(
+            parse("peeky://add?category=%20Shell%20&label=Loop&value=for%20f%3B%0A%20%20do")
+                .unwrap(),
+            add("Shell", "Loop", Some("for f;\n  do"))
+        );
+        assert_eq!(
+            parse("peeky://add?category=%E6%97%A5%E5%BF%97&label=tail&value=").unwrap(),
+            add("日志", "tail", Some(""))
+        );
+    }
+
+    #[test]
+    fn parses_open_and_show_links() {
+        assert_eq!(
+            parse("peeky://open?category=Oncall").unwrap(),
+            DeepLinkAction::OpenCategory {
+                category: "Oncall".to_string()
+            }
+        );
+        assert_eq!(
+            parse("peeky://show").unwrap(),
+            DeepLinkAction::ShowOverlay { category: None }
+        );
+        assert_eq!(
+            parse("peeky://show?category=Oncall").unwrap(),
+            DeepLinkAction::ShowOverlay {
+                category: Some("Oncall".to_string())
+            }
+        );
+    }
+
+    #[test]
+    fn parses_import_links() {
+        let expected = LibraryFile {
+            categories: vec![FileCategory {
+                name: "Docker".to_string(),
+                items: vec![FileItem {
+                    label: "Running".to_string(),
+                    value: "docker ps".to_string(),
+                    kind: ItemKind::Text,
+                }],
+            }],
+        };
+        assert_eq!(
+            parse("peeky://import?data=%23%20Docker%0A-%20Running%3A%20%60docker%20ps%60").unwrap(),
+            DeepLinkAction::Import {
+                library: expected.clone()
+            }
+        );
+        let json = "%7B%22categories%22%3A%5B%7B%22name%22%3A%22Docker%22%2C%22items%22%3A%5B%7B%22label%22%3A%22Running%22%2C%22value%22%3A%22docker%20ps%22%7D%5D%7D%5D%7D";
+        assert_eq!(
+            parse(&format!("peeky://import?format=json&data={json}")).unwrap(),
+            DeepLinkAction::Import { library: expected }
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_links() {
+        for link in [
+            "",
+            "peeky:add?category=Git&label=x",
+            "https://add?category=Git&label=x",
+            "peeky://",
+            "peeky://delete?category=Git",
+            "peeky://add/extra?category=Git&label=x",
+            "peeky://add?category=Git&label=x#top",
+            // Unknown, repeated, missing or blank parameters.
+            "peeky://add?category=Git&label=x&sort=1",
+            "peeky://add?category=Git&category=Docker&label=x",
+            "peeky://add?category=Git",
+            "peeky://add?category=%20&label=x",
+            "peeky://add?category=Git&label",
+            "peeky://show?category=",
+            "peeky://open",
+            "peeky://add?category=Git&label=a%0Ab",
+            // Bad escapes, raw characters that must be escaped, control
+            // characters and invalid UTF-8.
+            "peeky://add?category=Git&label=100%",
+            "peeky://add?category=Git&label=%zz",
+            "peeky://add?category=Git&label=two words",
+            "peeky://add?category=Git&label=\"x\"",
+            "peeky://add?category=Git&label=x&value=%00",
+            "peeky://add?category=Git&label=%FF",
+            // Imports need a known format and at least one item.
+            "peeky://import",
+            "peeky://import?format=yaml&data=x",
+            "peeky://import?format=json&data=%7B",
+            "peeky://import?data=%23%20Empty",
+        ] {
+            assert!(parse(link).is_err(), "accepted {link:?}");
+        }
+        let long = format!(
+            "peeky://add?category=Git&label=x&value={}",
+            "a".repeat(MAX_LINK_LEN)
+        );
+        assert!(parse(&long).is_err());
+    }
+
+    #[test]
+    fn only_mutations_need_confirmation() {
+        let question = add("Git", "Squash", Some("git rebase -i"))
+            .confirmation()
+            .unwrap();
+        assert!(question.contains("Squash") && question.contains("git rebase -i"));
+        let import = parse("peeky://import?data=%23%20A%0A-%20x%3A%201%0A-%20y%3A%202").unwrap();
+        assert!(import.confirmation().unwrap().contains('2'));
+        assert!(parse("peeky://show").unwrap().confirmation().is_none());
+        assert!(parse("peeky://open?category=Git")
+            .unwrap()
+            .confirmation()
+            .is_none());
+    }
+}
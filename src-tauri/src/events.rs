@@ -0,0 +1,168 @@
+//! Typed events pushed from the backend to the webviews.
+//!
+//! Every payload implements [`AppEvent`]; `scripts/generate-ipc-types.mjs`
+//! reads those impls to generate `EVENT_NAMES` and `EventPayloads` in
+//! `src/core/ipc.generated.ts`.
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+
+use crate::commands::appearance::EffectiveTheme;
+use crate::commands::categories::Category;
+use crate::commands::links::CategoryLink;
+use crate::commands::settings::AppSettings;
+use crate::commands::sync::SyncReport;
+
+pub trait AppEvent: Serialize + Clone {
+    const NAME: &'static str;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LibraryEntity {
+    Category,
+    Item,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    Created,
+    Updated,
+    Deleted,
+    Reordered,
+}
+
+/// Categories or items were created, changed or removed.
+#[derive(Debug, Clone, Serialize)]
+pub struct LibraryChanged {
+    pub entity: LibraryEntity,
+    pub kind: ChangeKind,
+    pub ids: Vec<i64>,
+    /// For item changes, the category the items belong to.
+    pub category_id: Option<i64>,
+}
+
+impl AppEvent for LibraryChanged {
+    const NAME: &'static str = "library-changed";
+}
+
+impl LibraryChanged {
+    pub fn categories(kind: ChangeKind, ids: Vec<i64>) -> Self {
+        Self {
+            entity: LibraryEntity::Category,
+            kind,
+            ids,
+            category_id: None,
+        }
+    }
+
+    pub fn items(kind: ChangeKind, ids: Vec<i64>, category_id: i64) -> Self {
+        Self {
+            entity: LibraryEntity::Item,
+            kind,
+            ids,
+            category_id: Some(category_id),
+        }
+    }
+}
+
+/// Settings were written; `changed` lists the keys whose value differs.
+#[derive(Debug, Clone, Serialize)]
+pub struct SettingsChanged {
+    pub settings: AppSettings,
+    pub changed: Vec<String>,
+}
+
+impl AppEvent for SettingsChanged {
+    const NAME: &'static str = "settings-changed";
+}
+
+/// Which category the overlay should show, or every category when `None`.
+#[derive(Debug, Clone, Serialize)]
+pub struct OverlayFilter {
+    pub category_id: Option<i64>,
+}
+
+impl AppEvent for OverlayFilter {
+    const NAME: &'static str = "overlay-filter";
+}
+
+/// Which category the main window should show, e.g. after a
+/// `peeky://open` link.
+#[derive(Debug, Clone, Serialize)]
+pub struct SelectCategory {
+    pub category: Category,
+}
+
+impl AppEvent for SelectCategory {
+    const NAME: &'static str = "select-category";
+}
+
+/// The resolved light/dark theme changed, either because the `theme`
+/// setting changed or because the OS appearance did while it is `system`.
+#[derive(Debug, Clone, Serialize)]
+pub struct EffectiveThemeChanged {
+    pub theme: EffectiveTheme,
+}
+
+impl AppEvent for EffectiveThemeChanged {
+    const NAME: &'static str = "effective-theme-changed";
+}
+
+/// The database was replaced by a backup; everything read from it before is
+/// stale.
+#[derive(Debug, Clone, Serialize)]
+pub struct DatabaseRestored {
+    /// Name of the backup that was restored.
+    pub backup: String,
+    /// Snapshot taken of the database just before it was replaced.
+    pub safety_backup: String,
+}
+
+impl AppEvent for DatabaseRestored {
+    const NAME: &'static str = "database-restored";
+}
+
+/// A folder sync finished. Reload the library if anything was pulled.
+#[derive(Debug, Clone, Serialize)]
+pub struct SyncCompleted {
+    pub report: SyncReport,
+}
+
+impl AppEvent for SyncCompleted {
+    const NAME: &'static str = "sync-completed";
+}
+
+/// Another program asked the app to re-read the library, e.g. after writing
+/// to the database directly.
+#[derive(Debug, Clone, Serialize)]
+pub struct LibraryReloaded {}
+
+impl AppEvent for LibraryReloaded {
+    const NAME: &'static str = "library-reloaded";
+}
+
+/// A linked category was re-read from its file, successfully or not.
+#[derive(Debug, Clone, Serialize)]
+pub struct CategoryLinkSynced {
+    pub link: CategoryLink,
+}
+
+impl AppEvent for CategoryLinkSynced {
+    const NAME: &'static str = "category-link-synced";
+}
+
+/// Broadcast to every webview window (and backend listeners).
+pub fn emit<E: AppEvent>(app: &AppHandle, event: E) {
+    if let Err(err) = app.emit(E::NAME, event) {
+        eprintln!("failed to emit {}: {err}", E::NAME);
+    }
+}
+
+/// Send to a single window.
+pub fn emit_to<E: AppEvent>(app: &AppHandle, window: &str, event: E) {
+    if let Err(err) = app.emit_to(window, E::NAME, event) {
+        eprintln!("failed to emit {} to {window}: {err}", E::NAME);
+    }
+}
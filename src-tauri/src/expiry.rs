@@ -0,0 +1,305 @@
+//! Cleanup of items past their `expires_at`.
+//!
+//! Expired items are already left out of [`get_all_items_by_pool`]; this task
+//! then deletes them, or moves them to the "Expired" category when the
+//! `expired_items` setting says so. Time comes from a [`Clock`], so tests can
+//! move it forward instead of waiting.
+//!
+//! [`get_all_items_by_pool`]: crate::commands::items::get_all_items_by_pool
+
+use std::time::Duration;
+
+use sqlx::{SqliteConnection, SqlitePool};
+use tauri::{AppHandle, Manager};
+
+use crate::clock::{Clock, SystemClock};
+use crate::commands::categories::{create_category_by_conn, CreateCategoryInput};
+use crate::commands::items::ExpiredItemAction;
+use crate::commands::settings::get_settings_by_pool;
+use crate::control;
+use crate::db::Db;
+use crate::i18n;
+
+const INTERVAL: Duration = Duration::from_secs(60);
+
+/// The category expired items are moved to. Created under the current
+/// locale's name the first time and remembered by id, so renaming it or
+/// switching locales keeps using the same one.
+async fn expired_category_by_conn(db: &mut SqliteConnection) -> Result<i64, String> {
+    let stored: Option<i64> =
+        sqlx::query_scalar("SELECT category_id FROM expired_category WHERE id = 1")
+            .fetch_optional(&mut *db)
+            .await
+            .map_err(|e| e.to_string())?;
+    if let Some(category_id) = stored {
+        return Ok(category_id);
+    }
+    let name = i18n::t("library.expired_category");
+    let category = create_category_by_conn(db, CreateCategoryInput { name }).await?;
+    sqlx::query("INSERT INTO expired_category (id, category_id) VALUES (1, ?)")
+        .bind(category.id)
+        .execute(&mut *db)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(category.id)
+}
+
+/// Delete or move every item that expired by `clock`'s current time and
+/// return how many there were.
+pub async fn sweep_by_pool(
+    db: &SqlitePool,
+    clock: &dyn Clock,
+    action: ExpiredItemAction,
+) -> Result<u64, String> {
+    // Linked categories are left to their file, which would only bring the
+    // items back.
+    let expired: Vec<i64> = sqlx::query_scalar(
+        "SELECT id FROM items
+         WHERE expires_at IS NOT NULL AND expires_at <= datetime(?, 'unixepoch')
+           AND category_id NOT IN (SELECT category_id FROM category_links)
+         ORDER BY id",
+    )
+    .bind(clock.unix_secs())
+    .fetch_all(db)
+    .await
+    .map_err(|e| e.to_string())?;
+    if expired.is_empty() {
+        return Ok(0);
+    }
+
+    let mut tx = db.begin().await.map_err(|e| e.to_string())?;
+    let target = match action {
+        ExpiredItemAction::Delete => None,
+        ExpiredItemAction::Move => Some(expired_category_by_conn(&mut tx).await?),
+    };
+    for id in &expired {
+        let query = match target {
+            // Without its expiry, so the item stays in "Expired" for good.
+            Some(category_id) => sqlx::query(
+                "UPDATE items SET category_id = ?1, expires_at = NULL,
+                   sort_order = (SELECT COALESCE(MAX(sort_order), -1) + 1 FROM items
+                                 WHERE category_id = ?1)
+                 WHERE id = ?2",
+            )
+            .bind(category_id)
+            .bind(id),
+            None => sqlx::query("DELETE FROM items WHERE id = ?").bind(id),
+        };
+        query.execute(&mut *tx).await.map_err(|e| e.to_string())?;
+    }
+    tx.commit().await.map_err(|e| e.to_string())?;
+    Ok(expired.len() as u64)
+}
+
+/// Sweep every minute for as long as the app runs.
+pub fn start(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let db = app.state::<Db>().pool();
+            let action = get_settings_by_pool(&db)
+                .await
+                .map_or(ExpiredItemAction::Delete, |settings| settings.expired_items);
+            match sweep_by_pool(&db, &SystemClock, action).await {
+                Ok(0) => {}
+                Ok(_) => control::reload_library(&app),
+                Err(err) => eprintln!("failed to clean up expired items: {err}"),
+            }
+            tokio::time::sleep(INTERVAL).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+    use crate::commands::categories::{
+        create_category_by_pool, delete_category_by_pool, find_category_by_name_by_pool,
+        update_category_by_pool, UpdateCategoryInput,
+    };
+    use crate::commands::items::{
+        create_item_by_pool, get_all_items_by_pool, get_item_by_pool, search_items_by_pool,
+        set_item_expiry_by_pool, CreateItemInput, ItemKind,
+    };
+    use crate::db;
+
+    async fn setup_db(dir: &std::path::Path) -> SqlitePool {
+        let pool = db::create_pool(&dir.join("peeky.db").to_string_lossy())
+            .await
+            .expect("create pool");
+        db::migrator().run(&pool).await.expect("run migrations");
+        pool
+    }
+
+    async fn create_item(db: &SqlitePool, label: &str) -> i64 {
+        let category = match find_category_by_name_by_pool(db, "Office").await.unwrap() {
+            Some(category) => category,
+            None => create_category_by_pool(
+                db,
+                CreateCategoryInput {
+                    name: "Office".to_string(),
+                },
+            )
+            .await
+            .expect("create category"),
+        };
+        create_item_by_pool(
+            db,
+            CreateItemInput {
+                category_id: category.id,
+                label: label.to_string(),
+                value: Some("1234".to_string()),
+                kind: ItemKind::Text,
+            },
+        )
+        .await
+        .expect("create item")
+        .id
+    }
+
+    /// `clock`'s time plus `secs`, in the format `expires_at` is stored in.
+    async fn in_secs(db: &SqlitePool, clock: &ManualClock, secs: i64) -> String {
+        sqlx::query_scalar("SELECT datetime(?, 'unixepoch')")
+            .bind(clock.unix_secs() + secs)
+            .fetch_one(db)
+            .await
+            .unwrap()
+    }
+
+    async fn visible(db: &SqlitePool, clock: &ManualClock) -> Vec<String> {
+        get_all_items_by_pool(db, clock)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|item| item.label)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn items_hide_at_their_expiry_by_the_clock() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let db = setup_db(dir.path()).await;
+        let clock = ManualClock::new();
+        let room = create_item(&db, "Room code").await;
+        let at = in_secs(&db, &clock, 3600).await;
+        set_item_expiry_by_pool(&db, room, Some(&at)).await.unwrap();
+
+        clock.advance(Duration::from_secs(3599));
+        assert_eq!(visible(&db, &clock).await, vec!["Room code"]);
+        let found = search_items_by_pool(&db, "room", &clock).await.unwrap();
+        assert_eq!(found.len(), 1);
+
+        clock.advance(Duration::from_secs(1));
+        assert!(visible(&db, &clock).await.is_empty());
+        let found = search_items_by_pool(&db, "room", &clock).await.unwrap();
+        assert!(found.is_empty());
+    }
+
+    #[tokio::test]
+    async fn deletes_items_once_they_expire() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let db = setup_db(dir.path()).await;
+        let clock = ManualClock::new();
+        let room = create_item(&db, "Room code").await;
+        let wifi = create_item(&db, "Guest Wi-Fi").await;
+        let kept = create_item(&db, "Door").await;
+        let soon = in_secs(&db, &clock, 3600).await;
+        let later = in_secs(&db, &clock, 7200).await;
+        set_item_expiry_by_pool(&db, room, Some(&soon))
+            .await
+            .unwrap();
+        set_item_expiry_by_pool(&db, wifi, Some(&later))
+            .await
+            .unwrap();
+
+        let delete = ExpiredItemAction::Delete;
+        assert_eq!(sweep_by_pool(&db, &clock, delete).await.unwrap(), 0);
+        clock.advance(Duration::from_secs(3600));
+        assert_eq!(sweep_by_pool(&db, &clock, delete).await.unwrap(), 1);
+        assert!(get_item_by_pool(&db, room).await.is_err());
+        assert!(get_item_by_pool(&db, wifi).await.is_ok());
+
+        // Clearing the expiry keeps the item for good.
+        set_item_expiry_by_pool(&db, wifi, None).await.unwrap();
+        clock.advance(Duration::from_secs(86_400));
+        assert_eq!(sweep_by_pool(&db, &clock, delete).await.unwrap(), 0);
+        assert!(get_item_by_pool(&db, kept).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn moves_expired_items_and_hides_them_until_then() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let db = setup_db(dir.path()).await;
+        let clock = ManualClock::new();
+        let room = create_item(&db, "Room code").await;
+        create_item(&db, "Door").await;
+
+        // Already past: hidden before any sweep.
+        let item = set_item_expiry_by_pool(&db, room, Some("2020-01-01T09:00:00+01:00"))
+            .await
+            .unwrap();
+        assert_eq!(item.expires_at.as_deref(), Some("2020-01-01 08:00:00"));
+        assert_eq!(visible(&db, &clock).await, vec!["Door"]);
+
+        let moved = sweep_by_pool(&db, &clock, ExpiredItemAction::Move)
+            .await
+            .unwrap();
+        assert_eq!(moved, 1);
+        let item = get_item_by_pool(&db, room).await.unwrap();
+        let expired = find_category_by_name_by_pool(&db, "Expired")
+            .await
+            .unwrap()
+            .expect("expired category");
+        assert_eq!(item.category_id, expired.id);
+        assert_eq!(item.expires_at, None);
+
+        assert!(set_item_expiry_by_pool(&db, room, Some("next tuesday"))
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn keeps_moving_to_the_expired_category_once_renamed() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let db = setup_db(dir.path()).await;
+        let clock = ManualClock::new();
+        let past = Some("2020-01-01 08:00:00");
+        let room = create_item(&db, "Room code").await;
+        set_item_expiry_by_pool(&db, room, past).await.unwrap();
+        let action = ExpiredItemAction::Move;
+        sweep_by_pool(&db, &clock, action).await.unwrap();
+        let expired = get_item_by_pool(&db, room).await.unwrap().category_id;
+
+        // As a switch to another locale would show it.
+        update_category_by_pool(
+            &db,
+            UpdateCategoryInput {
+                id: expired,
+                name: Some("已过期".to_string()),
+                sort_order: None,
+            },
+        )
+        .await
+        .unwrap();
+        let wifi = create_item(&db, "Guest Wi-Fi").await;
+        set_item_expiry_by_pool(&db, wifi, past).await.unwrap();
+        sweep_by_pool(&db, &clock, action).await.unwrap();
+        assert_eq!(
+            get_item_by_pool(&db, wifi).await.unwrap().category_id,
+            expired
+        );
+        assert!(find_category_by_name_by_pool(&db, "Expired")
+            .await
+            .unwrap()
+            .is_none());
+
+        // Once deleted, the next move starts a new one.
+        delete_category_by_pool(&db, expired).await.unwrap();
+        let door = create_item(&db, "Door").await;
+        set_item_expiry_by_pool(&db, door, past).await.unwrap();
+        sweep_by_pool(&db, &clock, action).await.unwrap();
+        let moved_to = get_item_by_pool(&db, door).await.unwrap().category_id;
+        assert_ne!(moved_to, expired);
+    }
+}
@@ -0,0 +1,428 @@
+//! Hold-to-peek: show the overlay after a modifier key has been held alone
+//! for a while.
+//!
+//! [`HoldTrigger`] is a pure state machine. The platform key monitor feeds it
+//! key-down/key-up events, a ticker calls [`HoldTrigger::tick`] so the hold
+//! can complete without further input, and the returned [`HoldAction`]s are
+//! applied to the overlay window by the caller.
+
+// Key events are only produced by the macOS monitor for now.
+#![cfg_attr(not(target_os = "macos"), allow(dead_code))]
+
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::commands::settings::AppSettings;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HoldModifier {
+    Command,
+    Option,
+    Control,
+    Shift,
+}
+
+impl HoldModifier {
+    pub const ALL: [HoldModifier; 4] = [
+        HoldModifier::Command,
+        HoldModifier::Option,
+        HoldModifier::Control,
+        HoldModifier::Shift,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            HoldModifier::Command => "command",
+            HoldModifier::Option => "option",
+            HoldModifier::Control => "control",
+            HoldModifier::Shift => "shift",
+        }
+    }
+
+    fn bit(self) -> u8 {
+        match self {
+            HoldModifier::Command => 1,
+            HoldModifier::Option => 1 << 1,
+            HoldModifier::Control => 1 << 2,
+            HoldModifier::Shift => 1 << 3,
+        }
+    }
+}
+
+impl FromStr for HoldModifier {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        HoldModifier::ALL
+            .into_iter()
+            .find(|m| m.as_str() == s)
+            .ok_or_else(|| format!("Unknown hold modifier: {s}"))
+    }
+}
+
+/// Parse the `hold_modifier` setting; `"none"` disables the trigger.
+pub fn parse_modifier_setting(value: &str) -> Result<Option<HoldModifier>, String> {
+    if value == "none" {
+        return Ok(None);
+    }
+    value.parse().map(Some)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    Modifier(HoldModifier),
+    /// Any non-modifier key.
+    Other,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyEvent {
+    Down(Key),
+    Up(Key),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HoldAction {
+    Show,
+    Hide,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HoldConfig {
+    /// Modifier to hold; `None` disables the trigger.
+    pub modifier: Option<HoldModifier>,
+    pub hold_duration: Duration,
+    /// Hide the overlay again when the modifier is released. When false the
+    /// overlay stays until dismissed some other way.
+    pub hide_on_release: bool,
+}
+
+impl HoldConfig {
+    /// Build from persisted settings. Settings are validated on write, so an
+    /// unknown modifier here can only come from a hand-edited database and
+    /// disables the trigger.
+    pub fn from_settings(settings: &AppSettings) -> Self {
+        Self {
+            modifier: parse_modifier_setting(&settings.hold_modifier).unwrap_or(None),
+            hold_duration: Duration::from_millis(settings.hold_duration_ms.max(0) as u64),
+            hide_on_release: settings.hold_hide_on_release,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Idle,
+    /// Target modifier is held alone; fires once `since + hold_duration` passes.
+    Holding {
+        since: Instant,
+    },
+    /// The hold completed and the overlay was shown.
+    Shown,
+    /// Something else was pressed during the hold; wait for a full release.
+    Cancelled,
+}
+
+#[derive(Debug)]
+pub struct HoldTrigger {
+    config: HoldConfig,
+    state: State,
+    held_modifiers: u8,
+}
+
+impl HoldTrigger {
+    pub fn new(config: HoldConfig) -> Self {
+        Self {
+            config,
+            state: State::Idle,
+            held_modifiers: 0,
+        }
+    }
+
+    /// Replace the configuration. Any hold in progress is abandoned so a new
+    /// modifier or duration never fires half-way through.
+    pub fn set_config(&mut self, config: HoldConfig) {
+        self.config = config;
+        if self.state != State::Idle {
+            self.state = State::Cancelled;
+        }
+    }
+
+    /// When the current hold will complete, if one is in progress.
+    pub fn deadline(&self) -> Option<Instant> {
+        match self.state {
+            State::Holding { since } => Some(since + self.config.hold_duration),
+            _ => None,
+        }
+    }
+
+    pub fn handle(&mut self, event: KeyEvent, now: Instant) -> Option<HoldAction> {
+        match event {
+            KeyEvent::Down(Key::Modifier(m)) => self.held_modifiers |= m.bit(),
+            KeyEvent::Up(Key::Modifier(m)) => self.held_modifiers &= !m.bit(),
+            KeyEvent::Down(Key::Other) | KeyEvent::Up(Key::Other) => {}
+        }
+
+        let Some(target) = self.config.modifier else {
+            self.state = State::Idle;
+            return None;
+        };
+        let target_released = event == KeyEvent::Up(Key::Modifier(target));
+
+        match self.state {
+            State::Idle => {
+                if event == KeyEvent::Down(Key::Modifier(target))
+                    && self.held_modifiers == target.bit()
+                {
+                    self.state = State::Holding { since: now };
+                }
+                None
+            }
+            State::Holding { .. } => {
+                if target_released {
+                    self.state = State::Idle;
+                } else if matches!(event, KeyEvent::Down(_)) {
+                    self.state = State::Cancelled;
+                }
+                None
+            }
+            State::Shown => {
+                if !target_released {
+                    return None;
+                }
+                self.state = self.rest_state();
+                self.config.hide_on_release.then_some(HoldAction::Hide)
+            }
+            State::Cancelled => {
+                if target_released || self.held_modifiers == 0 {
+                    self.state = self.rest_state();
+                }
+                None
+            }
+        }
+    }
+
+    /// Advance time; fires [`HoldAction::Show`] once the hold duration has
+    /// elapsed without interruption.
+    pub fn tick(&mut self, now: Instant) -> Option<HoldAction> {
+        let deadline = self.deadline()?;
+        if now < deadline {
+            return None;
+        }
+        self.state = State::Shown;
+        Some(HoldAction::Show)
+    }
+
+    /// State to return to once the target modifier is up. If other modifiers
+    /// are still down, wait for those too before arming again.
+    fn rest_state(&self) -> State {
+        if self.held_modifiers == 0 {
+            State::Idle
+        } else {
+            State::Cancelled
+        }
+    }
+}
+
+/// Trigger shared between the key monitor, the ticker and `set_settings`.
+#[derive(Debug, Clone)]
+pub struct HoldTriggerHandle(pub Arc<Mutex<HoldTrigger>>);
+
+impl HoldTriggerHandle {
+    pub fn new(config: HoldConfig) -> Self {
+        Self(Arc::new(Mutex::new(HoldTrigger::new(config))))
+    }
+
+    pub fn set_config(&self, config: HoldConfig) {
+        self.0.lock().unwrap().set_config(config);
+    }
+
+    pub fn handle(&self, event: KeyEvent, now: Instant) -> Option<HoldAction> {
+        self.0.lock().unwrap().handle(event, now)
+    }
+
+    pub fn tick(&self, now: Instant) -> Option<HoldAction> {
+        self.0.lock().unwrap().tick(now)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::clock::{Clock, ManualClock};
+
+    const CMD: Key = Key::Modifier(HoldModifier::Command);
+    const SHIFT: Key = Key::Modifier(HoldModifier::Shift);
+
+    enum Step {
+        Key(KeyEvent),
+        Wait(u64),
+    }
+
+    fn config(hide_on_release: bool) -> HoldConfig {
+        HoldConfig {
+            modifier: Some(HoldModifier::Command),
+            hold_duration: Duration::from_millis(1000),
+            hide_on_release,
+        }
+    }
+
+    /// Replay an event stream against a simulated clock, ticking every 10ms
+    /// while waiting, and collect the emitted actions.
+    fn run(trigger: &mut HoldTrigger, steps: &[Step]) -> Vec<HoldAction> {
+        let clock = ManualClock::new();
+        let mut actions = Vec::new();
+        for step in steps {
+            match step {
+                Step::Key(event) => actions.extend(trigger.handle(*event, clock.now())),
+                Step::Wait(ms) => {
+                    for _ in 0..ms / 10 {
+                        clock.advance_ms(10);
+                        actions.extend(trigger.tick(clock.now()));
+                    }
+                }
+            }
+        }
+        actions
+    }
+
+    #[test]
+    fn holding_modifier_shows_after_duration_and_hides_on_release() {
+        let mut trigger = HoldTrigger::new(config(true));
+        let actions = run(
+            &mut trigger,
+            &[
+                Step::Key(KeyEvent::Down(CMD)),
+                Step::Wait(990),
+                Step::Key(KeyEvent::Up(CMD)),
+                Step::Key(KeyEvent::Down(CMD)),
+                Step::Wait(1000),
+                Step::Wait(500),
+                Step::Key(KeyEvent::Up(CMD)),
+            ],
+        );
+        assert_eq!(actions, vec![HoldAction::Show, HoldAction::Hide]);
+    }
+
+    #[test]
+    fn overlay_stays_after_release_when_configured() {
+        let mut trigger = HoldTrigger::new(config(false));
+        let actions = run(
+            &mut trigger,
+            &[
+                Step::Key(KeyEvent::Down(CMD)),
+                Step::Wait(1000),
+                Step::Key(KeyEvent::Up(CMD)),
+            ],
+        );
+        assert_eq!(actions, vec![HoldAction::Show]);
+    }
+
+    #[test]
+    fn pressing_another_key_cancels_until_released() {
+        let mut trigger = HoldTrigger::new(config(true));
+        let actions = run(
+            &mut trigger,
+            &[
+                Step::Key(KeyEvent::Down(CMD)),
+                Step::Wait(300),
+                Step::Key(KeyEvent::Down(Key::Other)),
+                Step::Key(KeyEvent::Up(Key::Other)),
+                Step::Wait(2000),
+                Step::Key(KeyEvent::Up(CMD)),
+            ],
+        );
+        assert!(actions.is_empty());
+
+        // Armed again after the release.
+        let actions = run(
+            &mut trigger,
+            &[Step::Key(KeyEvent::Down(CMD)), Step::Wait(1000)],
+        );
+        assert_eq!(actions, vec![HoldAction::Show]);
+    }
+
+    #[test]
+    fn chord_with_other_modifier_never_fires() {
+        let mut trigger = HoldTrigger::new(config(true));
+        let actions = run(
+            &mut trigger,
+            &[
+                Step::Key(KeyEvent::Down(SHIFT)),
+                Step::Key(KeyEvent::Down(CMD)),
+                Step::Wait(2000),
+                Step::Key(KeyEvent::Up(SHIFT)),
+                Step::Wait(2000),
+            ],
+        );
+        assert!(actions.is_empty());
+
+        let actions = run(
+            &mut trigger,
+            &[
+                Step::Key(KeyEvent::Up(CMD)),
+                Step::Key(KeyEvent::Down(CMD)),
+                Step::Key(KeyEvent::Down(SHIFT)),
+                Step::Wait(2000),
+            ],
+        );
+        assert!(actions.is_empty());
+    }
+
+    #[test]
+    fn other_keys_while_shown_keep_overlay_open() {
+        let mut trigger = HoldTrigger::new(config(true));
+        let actions = run(
+            &mut trigger,
+            &[
+                Step::Key(KeyEvent::Down(CMD)),
+                Step::Wait(1000),
+                Step::Key(KeyEvent::Down(Key::Other)),
+                Step::Key(KeyEvent::Up(Key::Other)),
+                Step::Wait(500),
+                Step::Key(KeyEvent::Up(CMD)),
+            ],
+        );
+        assert_eq!(actions, vec![HoldAction::Show, HoldAction::Hide]);
+    }
+
+    #[test]
+    fn disabled_trigger_ignores_everything() {
+        let mut trigger = HoldTrigger::new(HoldConfig {
+            modifier: None,
+            ..config(true)
+        });
+        let actions = run(
+            &mut trigger,
+            &[Step::Key(KeyEvent::Down(CMD)), Step::Wait(5000)],
+        );
+        assert!(actions.is_empty());
+    }
+
+    #[test]
+    fn changing_config_mid_hold_abandons_it() {
+        let mut trigger = HoldTrigger::new(config(true));
+        let clock = ManualClock::new();
+        trigger.handle(KeyEvent::Down(CMD), clock.now());
+        trigger.set_config(HoldConfig {
+            hold_duration: Duration::from_millis(200),
+            ..config(true)
+        });
+        clock.advance_ms(500);
+        assert_eq!(trigger.tick(clock.now()), None);
+        assert_eq!(trigger.deadline(), None);
+    }
+
+    #[test]
+    fn modifier_setting_parses_none_and_names() {
+        assert_eq!(parse_modifier_setting("none"), Ok(None));
+        assert_eq!(
+            parse_modifier_setting("option"),
+            Ok(Some(HoldModifier::Option))
+        );
+        assert!(parse_modifier_setting("hyper").is_err());
+    }
+}
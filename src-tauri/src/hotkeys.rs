@@ -0,0 +1,164 @@
+//! Runtime side of user hotkey bindings: registers them with the
+//! global-shortcut plugin and runs the bound action when one fires.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+use tauri::{AppHandle, Manager};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut};
+use tauri_plugin_notification::NotificationExt;
+
+use crate::chord::Chord;
+use crate::commands::hotkeys::{
+    get_hotkey_bindings_by_pool, HotkeyBinding, ACTION_COPY_ITEM, ACTION_SHOW_CATEGORY,
+    BUILTIN_SHORTCUTS,
+};
+use crate::commands::items::get_item_by_pool;
+use crate::db::Db;
+use crate::i18n;
+use crate::overlay;
+
+#[derive(Default)]
+pub struct HotkeyRegistry {
+    /// Currently registered bindings, keyed by the plugin's shortcut id.
+    registered: Mutex<HashMap<u32, (Shortcut, HotkeyBinding)>>,
+    /// Set from the tray menu; while paused nothing is registered with the
+    /// OS and the hold trigger does not open the overlay.
+    paused: AtomicBool,
+}
+
+pub fn to_shortcut(notation: &str) -> Result<Shortcut, String> {
+    let chord: Chord = notation.parse()?;
+    chord
+        .to_accelerator()
+        .parse::<Shortcut>()
+        .map_err(|e| e.to_string())
+}
+
+/// Register the app's own shortcuts (toggle overlay, toggle main window).
+pub fn register_builtins(app: &AppHandle) {
+    for (notation, _) in BUILTIN_SHORTCUTS {
+        let result = to_shortcut(notation).and_then(|shortcut| {
+            app.global_shortcut()
+                .register(shortcut)
+                .map_err(|e| e.to_string())
+        });
+        if let Err(err) = result {
+            eprintln!("failed to register {notation}: {err}");
+        }
+    }
+}
+
+pub fn is_paused(app: &AppHandle) -> bool {
+    app.try_state::<HotkeyRegistry>()
+        .is_some_and(|registry| registry.paused.load(Ordering::SeqCst))
+}
+
+/// Pause or resume every global shortcut, built-in and user-defined.
+pub async fn set_paused(app: &AppHandle, paused: bool) -> Result<(), String> {
+    let registry = app.state::<HotkeyRegistry>();
+    if registry.paused.swap(paused, Ordering::SeqCst) == paused {
+        return Ok(());
+    }
+    if paused {
+        registry.registered.lock().unwrap().clear();
+        app.global_shortcut()
+            .unregister_all()
+            .map_err(|e| e.to_string())
+    } else {
+        register_builtins(app);
+        reload(app).await.map(|_| ())
+    }
+}
+
+/// Re-register every binding from the database, replacing whatever was
+/// registered before. Returns the bindings that could not be registered,
+/// keyed by binding id. Does nothing while shortcuts are paused; resuming
+/// reloads.
+pub async fn reload(app: &AppHandle) -> Result<HashMap<i64, String>, String> {
+    if is_paused(app) {
+        return Ok(HashMap::new());
+    }
+    let db = app.state::<Db>().pool();
+    let bindings = get_hotkey_bindings_by_pool(&db).await?;
+
+    let registry = app.state::<HotkeyRegistry>();
+    let mut registered = registry.registered.lock().unwrap();
+    let global_shortcut = app.global_shortcut();
+
+    for (shortcut, _) in registered.values() {
+        let _ = global_shortcut.unregister(*shortcut);
+    }
+    registered.clear();
+
+    let mut failures = HashMap::new();
+    for binding in bindings {
+        let result = to_shortcut(&binding.shortcut).and_then(|shortcut| {
+            global_shortcut
+                .register(shortcut)
+                .map(|_| shortcut)
+                .map_err(|e| e.to_string())
+        });
+        match result {
+            Ok(shortcut) => {
+                registered.insert(shortcut.id(), (shortcut, binding));
+            }
+            Err(err) => {
+                eprintln!("failed to register {}: {err}", binding.shortcut);
+                failures.insert(binding.id, err);
+            }
+        }
+    }
+    Ok(failures)
+}
+
+/// Run the action bound to `shortcut`. Returns false if it is not a user
+/// binding.
+pub fn dispatch(app: &AppHandle, shortcut: &Shortcut) -> bool {
+    let binding = {
+        let registry = app.state::<HotkeyRegistry>();
+        let registered = registry.registered.lock().unwrap();
+        match registered.get(&shortcut.id()) {
+            Some((_, binding)) => binding.clone(),
+            None => return false,
+        }
+    };
+
+    match (
+        binding.action.as_str(),
+        binding.category_id,
+        binding.item_id,
+    ) {
+        (ACTION_SHOW_CATEGORY, Some(category_id), _) => {
+            overlay::show_category(app, Some(category_id));
+        }
+        (ACTION_COPY_ITEM, _, Some(item_id)) => {
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(err) = copy_item(&app, item_id).await {
+                    eprintln!("hotkey {} failed: {err}", binding.shortcut);
+                }
+            });
+        }
+        _ => eprintln!("ignoring malformed hotkey binding {}", binding.id),
+    }
+    true
+}
+
+/// Copy an item's value to the clipboard and confirm with a notification.
+pub async fn copy_item(app: &AppHandle, item_id: i64) -> Result<(), String> {
+    let db = app.state::<Db>().pool();
+    let item = get_item_by_pool(&db, item_id).await?;
+    app.clipboard()
+        .write_text(item.value)
+        .map_err(|e| e.to_string())?;
+    let _ = app
+        .notification()
+        .builder()
+        .title(i18n::t("app.name"))
+        .body(i18n::tf("notification.copied", &[("label", &item.label)]))
+        .show();
+    Ok(())
+}
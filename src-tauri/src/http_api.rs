@@ -0,0 +1,628 @@
+//! Opt-in HTTP API on `127.0.0.1` for shell scripts and editor plugins.
+//!
+//! Every request needs `Authorization: Bearer <http_api_token>`. The server
+//! sends no CORS headers, so web pages cannot read its responses or send
+//! the header at all. Handlers call the same `*_by_pool` functions as the
+//! Tauri commands and take the same JSON bodies; on `PATCH` the `id` comes
+//! from the path.
+//!
+//! | Method   | Path                        | Body                  |
+//! |----------|-----------------------------|-----------------------|
+//! | `GET`    | `/v1/categories`            |                       |
+//! | `POST`   | `/v1/categories`            | `CreateCategoryInput` |
+//! | `PATCH`  | `/v1/categories/{id}`       | `UpdateCategoryInput` |
+//! | `DELETE` | `/v1/categories/{id}`       |                       |
+//! | `GET`    | `/v1/items?q=&category_id=` |                       |
+//! | `GET`    | `/v1/items/{id}`            |                       |
+//! | `POST`   | `/v1/items`                 | `CreateItemInput`     |
+//! | `PATCH`  | `/v1/items/{id}`            | `UpdateItemInput`     |
+//! | `DELETE` | `/v1/items/{id}`            |                       |
+//! | `POST`   | `/v1/overlay/toggle`        |                       |
+
+use axum::extract::{Path, Query, Request, State};
+use axum::http::{header, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, patch, post};
+use axum::{Json, Router};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use sqlx::SqlitePool;
+use tauri::{AppHandle, Manager};
+use tokio::net::TcpListener;
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+
+use crate::clock::SystemClock;
+use crate::commands::categories::{
+    create_category_by_pool, delete_category_by_pool, get_categories_by_pool,
+    update_category_by_pool, Category, CreateCategoryInput, UpdateCategoryInput,
+};
+use crate::commands::http_api::get_http_api_token_by_pool;
+use crate::commands::items::{
+    create_item_by_pool, delete_item_by_pool, get_item_by_pool, search_items_by_pool,
+    update_item_by_pool, CreateItemInput, Item, ItemWithCategory, UpdateItemInput,
+};
+use crate::commands::settings::get_settings_by_pool;
+use crate::db::Db;
+use crate::events::{self, ChangeKind, LibraryChanged};
+use crate::i18n;
+use crate::overlay;
+
+const MIN_TOKEN_LEN: usize = 16;
+
+/// The running server, if any.
+static SERVER: Mutex<Option<JoinHandle<()>>> = Mutex::const_new(None);
+
+/// Setting validator: empty (API off) or a token too long to guess.
+pub fn validate_token(value: &str) -> Result<(), String> {
+    if value.is_empty() || (value.len() >= MIN_TOKEN_LEN && !value.chars().any(char::is_whitespace))
+    {
+        Ok(())
+    } else {
+        Err(i18n::t("error.http_api_token_invalid"))
+    }
+}
+
+/// Compare without stopping at the first difference.
+fn same_token(given: &str, expected: &str) -> bool {
+    given.len() == expected.len()
+        && given
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// What the handlers need from the app.
+pub trait Host: Clone + Send + Sync + 'static {
+    fn pool(&self) -> SqlitePool;
+    fn library_changed(&self, event: LibraryChanged);
+    fn toggle_overlay(&self);
+}
+
+impl Host for AppHandle {
+    fn pool(&self) -> SqlitePool {
+        self.state::<Db>().pool()
+    }
+
+    fn library_changed(&self, event: LibraryChanged) {
+        events::emit(self, event);
+    }
+
+    fn toggle_overlay(&self) {
+        overlay::toggle(self);
+    }
+}
+
+#[derive(Clone)]
+struct ApiState<H> {
+    host: H,
+    token: String,
+}
+
+struct ApiError(StatusCode, String);
+
+impl ApiError {
+    fn bad_request(message: String) -> Self {
+        Self(StatusCode::BAD_REQUEST, message)
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.0, Json(json!({ "error": self.1 }))).into_response()
+    }
+}
+
+type ApiResult<T> = Result<T, ApiError>;
+
+async fn require_token<H: Host>(
+    State(state): State<ApiState<H>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let authorized = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|token| same_token(token, &state.token));
+    if !authorized {
+        return ApiError(
+            StatusCode::UNAUTHORIZED,
+            i18n::t("error.http_api_unauthorized"),
+        )
+        .into_response();
+    }
+    next.run(request).await
+}
+
+/// A `PATCH` body with the path's `id` filled in, as one of the update
+/// input structs.
+fn with_id<T: DeserializeOwned>(id: i64, mut body: Map<String, Value>) -> ApiResult<T> {
+    body.insert("id".to_string(), id.into());
+    serde_json::from_value(Value::Object(body)).map_err(|e| ApiError::bad_request(e.to_string()))
+}
+
+async fn list_categories<H: Host>(
+    State(state): State<ApiState<H>>,
+) -> ApiResult<Json<Vec<Category>>> {
+    let categories = get_categories_by_pool(&state.host.pool())
+        .await
+        .map_err(ApiError::bad_request)?;
+    Ok(Json(categories))
+}
+
+async fn create_category<H: Host>(
+    State(state): State<ApiState<H>>,
+    Json(input): Json<CreateCategoryInput>,
+) -> ApiResult<(StatusCode, Json<Category>)> {
+    let category = create_category_by_pool(&state.host.pool(), input)
+        .await
+        .map_err(ApiError::bad_request)?;
+    state.host.library_changed(LibraryChanged::categories(
+        ChangeKind::Created,
+        vec![category.id],
+    ));
+    Ok((StatusCode::CREATED, Json(category)))
+}
+
+async fn update_category<H: Host>(
+    State(state): State<ApiState<H>>,
+    Path(id): Path<i64>,
+    Json(body): Json<Map<String, Value>>,
+) -> ApiResult<Json<Category>> {
+    let input: UpdateCategoryInput = with_id(id, body)?;
+    let category = update_category_by_pool(&state.host.pool(), input)
+        .await
+        .map_err(ApiError::bad_request)?;
+    state.host.library_changed(LibraryChanged::categories(
+        ChangeKind::Updated,
+        vec![category.id],
+    ));
+    Ok(Json(category))
+}
+
+async fn delete_category<H: Host>(
+    State(state): State<ApiState<H>>,
+    Path(id): Path<i64>,
+) -> ApiResult<StatusCode> {
+    delete_category_by_pool(&state.host.pool(), id)
+        .await
+        .map_err(ApiError::bad_request)?;
+    state
+        .host
+        .library_changed(LibraryChanged::categories(ChangeKind::Deleted, vec![id]));
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Debug, Deserialize)]
+struct ItemQuery {
+    /// Text to look for in labels and values.
+    #[serde(default)]
+    q: String,
+    category_id: Option<i64>,
+}
+
+async fn list_items<H: Host>(
+    State(state): State<ApiState<H>>,
+    Query(query): Query<ItemQuery>,
+) -> ApiResult<Json<Vec<ItemWithCategory>>> {
+    let mut items = search_items_by_pool(&state.host.pool(), &query.q, &SystemClock)
+        .await
+        .map_err(ApiError::bad_request)?;
+    if let Some(category_id) = query.category_id {
+        items.retain(|item| item.category_id == category_id);
+    }
+    Ok(Json(items))
+}
+
+async fn get_item<H: Host>(
+    State(state): State<ApiState<H>>,
+    Path(id): Path<i64>,
+) -> ApiResult<Json<Item>> {
+    let item = get_item_by_pool(&state.host.pool(), id)
+        .await
+        .map_err(|e| ApiError(StatusCode::NOT_FOUND, e))?;
+    Ok(Json(item))
+}
+
+async fn create_item<H: Host>(
+    State(state): State<ApiState<H>>,
+    Json(input): Json<CreateItemInput>,
+) -> ApiResult<(StatusCode, Json<Item>)> {
+    let item = create_item_by_pool(&state.host.pool(), input)
+        .await
+        .map_err(ApiError::bad_request)?;
+    state.host.library_changed(LibraryChanged::items(
+        ChangeKind::Created,
+        vec![item.id],
+        item.category_id,
+    ));
+    Ok((StatusCode::CREATED, Json(item)))
+}
+
+async fn update_item<H: Host>(
+    State(state): State<ApiState<H>>,
+    Path(id): Path<i64>,
+    Json(body): Json<Map<String, Value>>,
+) -> ApiResult<Json<Item>> {
+    let input: UpdateItemInput = with_id(id, body)?;
+    let item = update_item_by_pool(&state.host.pool(), input)
+        .await
+        .map_err(ApiError::bad_request)?;
+    state.host.library_changed(LibraryChanged::items(
+        ChangeKind::Updated,
+        vec![item.id],
+        item.category_id,
+    ));
+    Ok(Json(item))
+}
+
+async fn delete_item<H: Host>(
+    State(state): State<ApiState<H>>,
+    Path(id): Path<i64>,
+) -> ApiResult<StatusCode> {
+    let pool = state.host.pool();
+    let item = get_item_by_pool(&pool, id)
+        .await
+        .map_err(|e| ApiError(StatusCode::NOT_FOUND, e))?;
+    delete_item_by_pool(&pool, id)
+        .await
+        .map_err(ApiError::bad_request)?;
+    state.host.library_changed(LibraryChanged::items(
+        ChangeKind::Deleted,
+        vec![id],
+        item.category_id,
+    ));
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn toggle_overlay<H: Host>(State(state): State<ApiState<H>>) -> StatusCode {
+    state.host.toggle_overlay();
+    StatusCode::NO_CONTENT
+}
+
+pub fn router<H: Host>(host: H, token: String) -> Router {
+    let state = ApiState { host, token };
+    Router::new()
+        .route(
+            "/v1/categories",
+            get(list_categories::<H>).post(create_category::<H>),
+        )
+        .route(
+            "/v1/categories/{id}",
+            patch(update_category::<H>).delete(delete_category::<H>),
+        )
+        .route("/v1/items", get(list_items::<H>).post(create_item::<H>))
+        .route(
+            "/v1/items/{id}",
+            get(get_item::<H>)
+                .patch(update_item::<H>)
+                .delete(delete_item::<H>),
+        )
+        .route("/v1/overlay/toggle", post(toggle_overlay::<H>))
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            require_token::<H>,
+        ))
+        .with_state(state)
+}
+
+/// Stop the server and start it again with the current settings, if it is
+/// enabled and has a token.
+pub async fn restart(app: &AppHandle) -> Result<(), String> {
+    let mut server = SERVER.lock().await;
+    if let Some(running) = server.take() {
+        running.abort();
+        let _ = running.await;
+    }
+
+    let db = app.state::<Db>().pool();
+    let settings = get_settings_by_pool(&db).await?;
+    let token = get_http_api_token_by_pool(&db).await?;
+    if !settings.http_api_enabled || token.is_empty() {
+        return Ok(());
+    }
+    let listener = TcpListener::bind(("127.0.0.1", settings.http_api_port as u16))
+        .await
+        .map_err(|e| e.to_string())?;
+    let app = router(app.clone(), token);
+    *server = Some(tokio::spawn(async move {
+        if let Err(err) = axum::serve(listener, app).await {
+            eprintln!("HTTP API stopped: {err}");
+        }
+    }));
+    Ok(())
+}
+
+/// A random token for the `http_api_token` table.
+pub async fn generate_token(db: &SqlitePool) -> Result<String, String> {
+    sqlx::query_scalar("SELECT lower(hex(randomblob(24)))")
+        .fetch_one(db)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Start the server if the settings ask for it.
+pub fn start(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(err) = restart(&app).await {
+            eprintln!("failed to start HTTP API: {err}");
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex as StdMutex};
+
+    use crate::commands::links::link_category_by_pool;
+
+    const TOKEN: &str = "test-token-0123456789";
+
+    #[derive(Clone)]
+    struct TestHost {
+        pool: SqlitePool,
+        changes: Arc<StdMutex<Vec<LibraryChanged>>>,
+        toggles: Arc<AtomicUsize>,
+    }
+
+    impl Host for TestHost {
+        fn pool(&self) -> SqlitePool {
+            self.pool.clone()
+        }
+
+        fn library_changed(&self, event: LibraryChanged) {
+            self.changes.lock().unwrap().push(event);
+        }
+
+        fn toggle_overlay(&self) {
+            self.toggles.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    async fn setup_db() -> SqlitePool {
+        let db = SqlitePool::connect("sqlite::memory:")
+            .await
+            .expect("connect sqlite memory");
+        sqlx::query(include_str!("../migrations/002_peeky_domain.sql"))
+            .execute(&db)
+            .await
+            .expect("run migration 002");
+        sqlx::query(include_str!("../migrations/009_item_kind.sql"))
+            .execute(&db)
+            .await
+            .expect("run migration 009");
+        sqlx::query(include_str!("../migrations/010_category_links.sql"))
+            .execute(&db)
+            .await
+            .expect("run migration 010");
+        sqlx::query(include_str!("../migrations/012_item_expiry.sql"))
+            .execute(&db)
+            .await
+            .expect("run migration 012");
+        db
+    }
+
+    /// Serve on an ephemeral port; returns the host and the base URL.
+    async fn serve() -> (TestHost, String) {
+        let host = TestHost {
+            pool: setup_db().await,
+            changes: Arc::default(),
+            toggles: Arc::default(),
+        };
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let app = router(host.clone(), TOKEN.to_string());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (host, url)
+    }
+
+    fn client() -> reqwest::Client {
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.insert(
+            reqwest::header::AUTHORIZATION,
+            format!("Bearer {TOKEN}").parse().unwrap(),
+        );
+        reqwest::Client::builder()
+            .default_headers(headers)
+            .build()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn tokens_must_be_long_and_unbroken() {
+        assert!(validate_token("").is_ok());
+        assert!(validate_token(TOKEN).is_ok());
+        let generated = generate_token(&setup_db().await).await.unwrap();
+        assert!(validate_token(&generated).is_ok());
+        assert!(validate_token("short").is_err());
+        assert!(validate_token("has a space in the middle").is_err());
+    }
+
+    #[tokio::test]
+    async fn rejects_requests_without_the_token() {
+        let (_, url) = serve().await;
+        let anonymous = reqwest::Client::new();
+
+        let missing = anonymous
+            .get(format!("{url}/v1/categories"))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(missing.status(), 401);
+        let wrong = anonymous
+            .get(format!("{url}/v1/categories"))
+            .bearer_auth("test-token-9876543210")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(wrong.status(), 401);
+    }
+
+    #[tokio::test]
+    async fn manages_categories_and_items() {
+        let (host, url) = serve().await;
+        let client = client();
+
+        let category: Category = client
+            .post(format!("{url}/v1/categories"))
+            .json(&json!({ "name": "Shell" }))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        let item: Item = client
+            .post(format!("{url}/v1/items"))
+            .json(&json!({ "category_id": category.id, "label": "grep", "value": "rg -n" }))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+
+        let updated: Item = client
+            .patch(format!("{url}/v1/items/{}", item.id))
+            .json(&json!({ "value": "rg -n --hidden" }))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(updated.label, "grep");
+        assert_eq!(updated.value, "rg -n --hidden");
+
+        let found: Vec<ItemWithCategory> = client
+            .get(format!("{url}/v1/items?q=HIDDEN"))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].category_name, "Shell");
+
+        let deleted = client
+            .delete(format!("{url}/v1/items/{}", item.id))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(deleted.status(), 204);
+        let missing = client
+            .get(format!("{url}/v1/items/{}", item.id))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(missing.status(), 404);
+
+        let kinds: Vec<ChangeKind> = host
+            .changes
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|change| change.kind)
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ChangeKind::Created,
+                ChangeKind::Created,
+                ChangeKind::Updated,
+                ChangeKind::Deleted
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn reports_invalid_input_as_json_errors() {
+        let (_, url) = serve().await;
+        let response = client()
+            .post(format!("{url}/v1/items"))
+            .json(&json!({ "category_id": 999, "label": "orphan" }))
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), 400);
+        let body: Value = response.json().await.unwrap();
+        assert!(body["error"].is_string());
+    }
+
+    #[tokio::test]
+    async fn linked_categories_refuse_item_writes() {
+        let (host, url) = serve().await;
+        let client = client();
+        let category: Category = client
+            .post(format!("{url}/v1/categories"))
+            .json(&json!({ "name": "Oncall" }))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        let item: Item = client
+            .post(format!("{url}/v1/items"))
+            .json(&json!({ "category_id": category.id, "label": "Ack", "value": "pd ack" }))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("oncall.md");
+        link_category_by_pool(&host.pool, category.id, &file.to_string_lossy())
+            .await
+            .unwrap();
+
+        let created = client
+            .post(format!("{url}/v1/items"))
+            .json(&json!({ "category_id": category.id, "label": "Logs" }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(created.status(), 400);
+        let updated = client
+            .patch(format!("{url}/v1/items/{}", item.id))
+            .json(&json!({ "value": "pd resolve" }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(updated.status(), 400);
+        let deleted = client
+            .delete(format!("{url}/v1/items/{}", item.id))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(deleted.status(), 400);
+        assert_eq!(
+            get_item_by_pool(&host.pool, item.id).await.unwrap().value,
+            "pd ack"
+        );
+    }
+
+    #[tokio::test]
+    async fn toggles_the_overlay() {
+        let (host, url) = serve().await;
+        let response = client()
+            .post(format!("{url}/v1/overlay/toggle"))
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), 204);
+        assert_eq!(host.toggles.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,528 @@
+//! Message catalog for text the backend shows itself: tray, native menus,
+//! notifications and user-facing errors.
+//!
+//! Messages are looked up in the locale from the `locale` setting, which
+//! [`set_locale`] records process-wide so that code without an `AppHandle`
+//! (the `*_by_pool` functions) can localize its errors. Placeholders are
+//! written `{name}` and filled from the `args` of [`tf`].
+
+use std::fmt::Display;
+use std::sync::RwLock;
+
+use crate::locale::DEFAULT_LOCALE;
+
+type Catalog = &'static [(&'static str, &'static str)];
+
+const EN_US: Catalog = &[
+    ("app.name", "Peeky"),
+    ("notification.copied", "Copied {label}"),
+    ("shortcut.toggle_overlay", "toggle overlay"),
+    ("shortcut.toggle_main", "toggle main window"),
+    ("tray.menu.show_overlay", "Show overlay"),
+    ("tray.menu.manage", "Manage…"),
+    ("tray.menu.pause_shortcuts", "Pause shortcuts"),
+    ("tray.menu.quit", "Quit"),
+    ("tray.menu.no_items", "No items"),
+    ("tray.menu.no_categories", "No categories"),
+    ("error.category_not_found", "Category {id} not found"),
+    ("error.revision_not_found", "Revision {id} not found"),
+    ("error.expiry_invalid", "Not a date and time: {value}"),
+    ("error.item_not_found", "Item {id} not found"),
+    (
+        "error.hotkey_binding_not_found",
+        "Hotkey binding {id} not found",
+    ),
+    (
+        "error.hotkey_needs_category",
+        "show_category bindings need a category_id",
+    ),
+    (
+        "error.hotkey_needs_item",
+        "copy_item bindings need an item_id",
+    ),
+    (
+        "error.hotkey_unknown_action",
+        "Unknown hotkey action: {action}",
+    ),
+    ("error.shortcut_invalid", "Invalid shortcut: {shortcut}"),
+    (
+        "error.shortcut_unknown_modifier",
+        "Unknown modifier '{token}' in shortcut: {shortcut}",
+    ),
+    (
+        "error.shortcut_duplicate_modifier",
+        "Duplicate modifier '{token}' in shortcut: {shortcut}",
+    ),
+    (
+        "error.shortcut_unknown_key",
+        "Unknown key '{key}' in shortcut: {shortcut}",
+    ),
+    (
+        "error.shortcut_needs_modifier",
+        "Shortcut {shortcut} needs at least one modifier key",
+    ),
+    (
+        "error.shortcut_reserved",
+        "Shortcut {shortcut} is reserved for {action}",
+    ),
+    (
+        "error.shortcut_taken",
+        "Shortcut {shortcut} is already bound",
+    ),
+    (
+        "error.shortcut_register_failed",
+        "Could not register {shortcut}: {error}",
+    ),
+    ("error.setting_unknown", "Unknown setting: {key}"),
+    (
+        "error.setting_range",
+        "{key} must be between {min} and {max}",
+    ),
+    ("error.setting_choice", "{key} must be one of: {choices}"),
+    ("error.setting_bool", "{key} must be a boolean"),
+    ("error.setting_integer", "{key} must be an integer"),
+    ("error.setting_string", "{key} must be a string"),
+    (
+        "error.locale_invalid",
+        "{tag} is not a valid BCP 47 language tag",
+    ),
+    ("error.locale_case", "Locale must be written as {canonical}"),
+    (
+        "error.locale_unsupported",
+        "Unsupported locale {tag}; expected one of: {supported}",
+    ),
+    (
+        "error.autostart_unsupported",
+        "Launch on login is not supported on this platform",
+    ),
+    (
+        "error.autostart_failed",
+        "Could not update login item: {error}",
+    ),
+    ("error.backup_not_found", "Backup {name} not found"),
+    (
+        "error.backup_invalid",
+        "Backup {name} is not a usable database: {error}",
+    ),
+    (
+        "error.backup_too_new",
+        "Backup {name} was made by a newer version of Peeky",
+    ),
+    ("error.sync_folder_unset", "Choose a sync folder first"),
+    (
+        "error.sync_folder_relative",
+        "Sync folder must be an absolute path",
+    ),
+    ("error.sync_folder_missing", "Sync folder {path} not found"),
+    (
+        "error.sync_conflict_not_found",
+        "Sync conflict {id} not found",
+    ),
+    (
+        "error.http_api_token_invalid",
+        "API token must be at least 16 characters without spaces",
+    ),
+    ("error.http_api_unauthorized", "Missing or wrong API token"),
+    ("error.category_name_not_found", "Category {name} not found"),
+    ("error.cli_no_match", "No item matches {query}"),
+    (
+        "error.cli_ambiguous",
+        "{count} items match {query}; be more specific:",
+    ),
+    (
+        "error.cli_database_missing",
+        "No database at {path}; start Peeky once or pass --db",
+    ),
+    (
+        "error.cli_database_unknown",
+        "Could not find the Peeky data directory; pass --db",
+    ),
+    (
+        "error.cli_stdin_terminal",
+        "A value of - is read from stdin; pipe it in or pass the value",
+    ),
+    (
+        "error.control_in_use",
+        "Another Peeky is already listening on {path}",
+    ),
+    ("error.control_not_running", "Peeky is not running"),
+    ("error.deep_link_invalid", "Not a valid Peeky link: {link}"),
+    ("error.deep_link_too_long", "Peeky link is too long to open"),
+    (
+        "error.deep_link_unknown_action",
+        "Unknown Peeky link action: {action}",
+    ),
+    (
+        "error.deep_link_unknown_param",
+        "Unexpected parameter {name} in Peeky link",
+    ),
+    (
+        "error.deep_link_duplicate_param",
+        "Parameter {name} appears more than once in Peeky link",
+    ),
+    (
+        "error.deep_link_missing_param",
+        "Peeky link is missing {name}",
+    ),
+    (
+        "error.deep_link_bad_value",
+        "Peeky link has an invalid {name}",
+    ),
+    (
+        "error.deep_link_empty_import",
+        "Peeky link has nothing to import",
+    ),
+    ("deep_link.confirm_title", "Open Peeky link?"),
+    ("deep_link.confirm_add", "Add {label} to {category}?"),
+    (
+        "deep_link.confirm_import",
+        "Import {items} items in {categories} categories?",
+    ),
+    ("deep_link.confirm_ok", "Continue"),
+    ("error.import_not_keymap", "Not a JetBrains keymap file"),
+    ("error.import_not_bookmarks", "Not a bookmarks HTML file"),
+    (
+        "error.markdown_unclosed_fence",
+        "The code block starting on line {line} is never closed",
+    ),
+    ("error.linked_file_empty", "The file has no items"),
+    (
+        "error.duplicate_threshold",
+        "The similarity threshold must be between 0.5 and 1",
+    ),
+    (
+        "error.merge_items_invalid",
+        "Choose one item to keep and at least one other to merge into it",
+    ),
+    ("error.link_path_relative", "The linked file must be an absolute path"),
+    ("error.category_not_linked", "Category {id} is not linked to a file"),
+    (
+        "error.category_linked",
+        "This category mirrors {path}; edit the file instead",
+    ),
+    (
+        "error.import_no_cheatsheets",
+        "No cheat sheet pages with examples in {path}",
+    ),
+    (
+        "error.ssh_config_no_home",
+        "Cannot find your home directory to read ~/.ssh/config",
+    ),
+    ("deep_link.confirm_cancel", "Cancel"),
+    ("cli.added", "Added {label} to {category}"),
+    (
+        "cli.imported",
+        "Imported {items} items and {categories} new categories, updated {updated} ({skipped} already present)",
+    ),
+    ("library.imported_category", "Imported"),
+    ("library.expired_category", "Expired"),
+];
+
+const ZH_CN: Catalog = &[
+    ("app.name", "Peeky"),
+    ("notification.copied", "已复制 {label}"),
+    ("shortcut.toggle_overlay", "切换浮层"),
+    ("shortcut.toggle_main", "切换主窗口"),
+    ("tray.menu.show_overlay", "显示浮层"),
+    ("tray.menu.manage", "管理…"),
+    ("tray.menu.pause_shortcuts", "暂停快捷键"),
+    ("tray.menu.quit", "退出"),
+    ("tray.menu.no_items", "没有条目"),
+    ("tray.menu.no_categories", "没有分类"),
+    ("error.category_not_found", "找不到分类 {id}"),
+    ("error.revision_not_found", "找不到历史版本 {id}"),
+    ("error.expiry_invalid", "不是有效的日期时间：{value}"),
+    ("error.item_not_found", "找不到条目 {id}"),
+    ("error.hotkey_binding_not_found", "找不到快捷键绑定 {id}"),
+    (
+        "error.hotkey_needs_category",
+        "show_category 绑定需要 category_id",
+    ),
+    ("error.hotkey_needs_item", "copy_item 绑定需要 item_id"),
+    ("error.hotkey_unknown_action", "未知的快捷键动作：{action}"),
+    ("error.shortcut_invalid", "无效的快捷键：{shortcut}"),
+    (
+        "error.shortcut_unknown_modifier",
+        "快捷键 {shortcut} 中有未知的修饰键“{token}”",
+    ),
+    (
+        "error.shortcut_duplicate_modifier",
+        "快捷键 {shortcut} 中的修饰键“{token}”重复",
+    ),
+    (
+        "error.shortcut_unknown_key",
+        "快捷键 {shortcut} 中有未知的按键“{key}”",
+    ),
+    (
+        "error.shortcut_needs_modifier",
+        "快捷键 {shortcut} 至少需要一个修饰键",
+    ),
+    (
+        "error.shortcut_reserved",
+        "快捷键 {shortcut} 已保留用于{action}",
+    ),
+    ("error.shortcut_taken", "快捷键 {shortcut} 已被绑定"),
+    (
+        "error.shortcut_register_failed",
+        "无法注册快捷键 {shortcut}：{error}",
+    ),
+    ("error.setting_unknown", "未知的设置项：{key}"),
+    ("error.setting_range", "{key} 必须在 {min} 到 {max} 之间"),
+    ("error.setting_choice", "{key} 必须是以下之一：{choices}"),
+    ("error.setting_bool", "{key} 必须是布尔值"),
+    ("error.setting_integer", "{key} 必须是整数"),
+    ("error.setting_string", "{key} 必须是字符串"),
+    ("error.locale_invalid", "{tag} 不是有效的 BCP 47 语言标签"),
+    ("error.locale_case", "语言区域应写作 {canonical}"),
+    (
+        "error.locale_unsupported",
+        "不支持的语言区域 {tag}，可选：{supported}",
+    ),
+    ("error.autostart_unsupported", "此平台不支持登录时启动"),
+    ("error.autostart_failed", "无法更新登录项：{error}"),
+    ("error.backup_not_found", "找不到备份 {name}"),
+    (
+        "error.backup_invalid",
+        "备份 {name} 不是可用的数据库：{error}",
+    ),
+    (
+        "error.backup_too_new",
+        "备份 {name} 由更新版本的 Peeky 创建",
+    ),
+    ("error.sync_folder_unset", "请先选择同步文件夹"),
+    ("error.sync_folder_relative", "同步文件夹必须是绝对路径"),
+    ("error.sync_folder_missing", "找不到同步文件夹 {path}"),
+    ("error.sync_conflict_not_found", "找不到同步冲突 {id}"),
+    (
+        "error.http_api_token_invalid",
+        "API 令牌至少需要 16 个字符且不能包含空格",
+    ),
+    ("error.http_api_unauthorized", "API 令牌缺失或错误"),
+    ("error.category_name_not_found", "找不到分类 {name}"),
+    ("error.cli_no_match", "没有匹配 {query} 的条目"),
+    (
+        "error.cli_ambiguous",
+        "有 {count} 个条目匹配 {query}，请更具体一些：",
+    ),
+    (
+        "error.cli_database_missing",
+        "{path} 处没有数据库；请先启动一次 Peeky 或使用 --db",
+    ),
+    (
+        "error.cli_database_unknown",
+        "找不到 Peeky 数据目录；请使用 --db",
+    ),
+    (
+        "error.cli_stdin_terminal",
+        "值 - 表示从标准输入读取；请通过管道传入或直接给出值",
+    ),
+    ("error.control_in_use", "已有另一个 Peeky 在监听 {path}"),
+    ("error.control_not_running", "Peeky 没有在运行"),
+    ("error.deep_link_invalid", "无效的 Peeky 链接：{link}"),
+    ("error.deep_link_too_long", "Peeky 链接过长，无法打开"),
+    (
+        "error.deep_link_unknown_action",
+        "未知的 Peeky 链接操作：{action}",
+    ),
+    (
+        "error.deep_link_unknown_param",
+        "Peeky 链接中有意外的参数 {name}",
+    ),
+    (
+        "error.deep_link_duplicate_param",
+        "Peeky 链接中的参数 {name} 重复出现",
+    ),
+    ("error.deep_link_missing_param", "Peeky 链接缺少 {name}"),
+    ("error.deep_link_bad_value", "Peeky 链接中的 {name} 无效"),
+    (
+        "error.deep_link_empty_import",
+        "Peeky 链接中没有可导入的内容",
+    ),
+    ("deep_link.confirm_title", "打开 Peeky 链接？"),
+    ("deep_link.confirm_add", "将 {label} 添加到 {category}？"),
+    (
+        "deep_link.confirm_import",
+        "导入 {categories} 个分类中的 {items} 个条目？",
+    ),
+    ("deep_link.confirm_ok", "继续"),
+    ("error.import_not_keymap", "不是 JetBrains 快捷键方案文件"),
+    ("error.import_not_bookmarks", "不是书签 HTML 文件"),
+    (
+        "error.markdown_unclosed_fence",
+        "第 {line} 行开始的代码块没有结束",
+    ),
+    ("error.linked_file_empty", "文件中没有条目"),
+    (
+        "error.duplicate_threshold",
+        "相似度阈值必须在 0.5 到 1 之间",
+    ),
+    (
+        "error.merge_items_invalid",
+        "请选择一个保留的条目，以及至少一个要合并进来的条目",
+    ),
+    ("error.link_path_relative", "链接的文件必须是绝对路径"),
+    ("error.category_not_linked", "分类 {id} 没有链接到文件"),
+    (
+        "error.category_linked",
+        "此分类与 {path} 同步；请直接编辑该文件",
+    ),
+    (
+        "error.import_no_cheatsheets",
+        "{path} 中没有含示例的速查页面",
+    ),
+    (
+        "error.ssh_config_no_home",
+        "找不到主目录，无法读取 ~/.ssh/config",
+    ),
+    ("deep_link.confirm_cancel", "取消"),
+    ("cli.added", "已将 {label} 添加到 {category}"),
+    (
+        "cli.imported",
+        "已导入 {items} 个条目和 {categories} 个新分类，更新 {updated} 个（{skipped} 个已存在）",
+    ),
+    ("library.imported_category", "导入"),
+    ("library.expired_category", "已过期"),
+];
+
+const CATALOGS: &[(&str, Catalog)] = &[("en-US", EN_US), ("zh-CN", ZH_CN)];
+
+static CURRENT_LOCALE: RwLock<&str> = RwLock::new(DEFAULT_LOCALE);
+
+/// Switch the process-wide locale. Unknown locales fall back to the default.
+pub fn set_locale(tag: &str) {
+    let locale = CATALOGS
+        .iter()
+        .map(|(locale, _)| *locale)
+        .find(|locale| *locale == tag)
+        .unwrap_or(DEFAULT_LOCALE);
+    *CURRENT_LOCALE.write().unwrap() = locale;
+}
+
+pub fn current_locale() -> &'static str {
+    *CURRENT_LOCALE.read().unwrap()
+}
+
+fn lookup(locale: &str, key: &str) -> Option<&'static str> {
+    CATALOGS
+        .iter()
+        .find(|(tag, _)| *tag == locale)
+        .and_then(|(_, catalog)| catalog.iter().find(|(k, _)| *k == key))
+        .map(|(_, message)| *message)
+}
+
+/// Message for `key` in `locale`, falling back to the default locale and
+/// then to the key itself.
+pub fn translate(locale: &str, key: &str, args: &[(&str, &dyn Display)]) -> String {
+    let template = lookup(locale, key)
+        .or_else(|| lookup(DEFAULT_LOCALE, key))
+        .unwrap_or(key);
+    fill(template, args)
+}
+
+/// Replace each `{name}` in `template` with its value from `args`, in one
+/// pass, so that values are never themselves searched for placeholders.
+/// Unknown placeholders are kept as written.
+fn fill(template: &str, args: &[(&str, &dyn Display)]) -> String {
+    let mut message = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        message.push_str(&rest[..open]);
+        let after = &rest[open + 1..];
+        let value = after.find('}').and_then(|close| {
+            let name = &after[..close];
+            let (_, value) = args.iter().find(|(arg, _)| *arg == name)?;
+            Some((value, close))
+        });
+        match value {
+            Some((value, close)) => {
+                message.push_str(&value.to_string());
+                rest = &after[close + 1..];
+            }
+            None => {
+                message.push('{');
+                rest = after;
+            }
+        }
+    }
+    message.push_str(rest);
+    message
+}
+
+/// Message for `key` in the current locale.
+pub fn t(key: &str) -> String {
+    translate(current_locale(), key, &[])
+}
+
+/// Message for `key` in the current locale with placeholders filled in.
+pub fn tf(key: &str, args: &[(&str, &dyn Display)]) -> String {
+    translate(current_locale(), key, args)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::*;
+    use crate::locale::SUPPORTED_LOCALES;
+
+    fn placeholders(message: &str) -> BTreeSet<&str> {
+        message
+            .split('{')
+            .skip(1)
+            .filter_map(|rest| rest.split_once('}').map(|(name, _)| name))
+            .collect()
+    }
+
+    #[test]
+    fn every_key_exists_in_every_locale() {
+        let reference: BTreeSet<&str> = EN_US.iter().map(|(key, _)| *key).collect();
+        assert_eq!(reference.len(), EN_US.len(), "duplicate key in en-US");
+
+        for (tag, catalog) in CATALOGS {
+            let keys: BTreeSet<&str> = catalog.iter().map(|(key, _)| *key).collect();
+            assert_eq!(keys.len(), catalog.len(), "duplicate key in {tag}");
+            assert_eq!(keys, reference, "{tag} keys differ from en-US");
+
+            for (key, message) in catalog.iter() {
+                let english = lookup(DEFAULT_LOCALE, key).unwrap();
+                assert_eq!(
+                    placeholders(message),
+                    placeholders(english),
+                    "{tag} {key} placeholders differ from en-US"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn every_supported_locale_has_a_catalog() {
+        for (tag, _) in SUPPORTED_LOCALES {
+            assert!(CATALOGS.iter().any(|(locale, _)| locale == tag), "{tag}");
+        }
+    }
+
+    #[test]
+    fn translate_fills_placeholders_and_falls_back() {
+        assert_eq!(
+            translate("zh-CN", "error.item_not_found", &[("id", &7)]),
+            "找不到条目 7"
+        );
+        assert_eq!(
+            translate("fr-FR", "error.item_not_found", &[("id", &7)]),
+            "Item 7 not found"
+        );
+        assert_eq!(translate("en-US", "no.such.key", &[]), "no.such.key");
+    }
+
+    #[test]
+    fn fill_substitutes_each_placeholder_once() {
+        assert_eq!(
+            fill(
+                "Added {label} to {category}",
+                &[("label", &"{category}"), ("category", &"Git")]
+            ),
+            "Added {category} to Git"
+        );
+        assert_eq!(fill("{a}{a} {b} {", &[("a", &1)]), "11 {b} {");
+    }
+}
@@ -0,0 +1,215 @@
+//! Alfred snippet collections (`.alfredsnippets`): a zip holding one JSON
+//! file per snippet, `{"alfredsnippet": {"name", "keyword", "snippet",
+//! "uid"}}`, and an `info.plist` with a keyword prefix and suffix that
+//! Alfred adds to every keyword in the collection.
+
+use std::io::{Cursor, Read, Write};
+
+use serde::{Deserialize, Serialize};
+use zip::write::SimpleFileOptions;
+use zip::{ZipArchive, ZipWriter};
+
+use crate::commands::items::ItemKind;
+use crate::library_file::{FileCategory, FileItem};
+
+const INFO_PLIST: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+	<key>snippetkeywordprefix</key>
+	<string></string>
+	<key>snippetkeywordsuffix</key>
+	<string></string>
+</dict>
+</plist>
+"#;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SnippetFile {
+    alfredsnippet: Snippet,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Snippet {
+    #[serde(default)]
+    snippet: String,
+    #[serde(default)]
+    uid: String,
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    keyword: String,
+}
+
+/// The `<string>` following `<key>{key}</key>` in a plist, unescaped.
+fn plist_string(plist: &str, key: &str) -> String {
+    let Some((_, rest)) = plist.split_once(&format!("<key>{key}</key>")) else {
+        return String::new();
+    };
+    let rest = rest.trim_start();
+    rest.strip_prefix("<string>")
+        .and_then(|rest| rest.split_once("</string>"))
+        .map(|(value, _)| {
+            value
+                .replace("&lt;", "<")
+                .replace("&gt;", ">")
+                .replace("&quot;", "\"")
+                .replace("&apos;", "'")
+                .replace("&amp;", "&")
+        })
+        .unwrap_or_default()
+}
+
+/// A stable UUID-shaped uid, so exporting twice gives the same files and
+/// Alfred updates snippets instead of duplicating them.
+fn uid(category: &str, index: usize, label: &str) -> String {
+    let hash = |seed: u64| {
+        let mut hash = 0xcbf2_9ce4_8422_2325_u64 ^ seed;
+        for byte in category.bytes().chain([0]).chain(label.bytes()) {
+            hash = (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3);
+        }
+        hash ^ index as u64
+    };
+    let hex = format!("{:016X}{:016X}", hash(0), hash(1));
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    )
+}
+
+pub fn parse(name: &str, bytes: &[u8]) -> Result<FileCategory, String> {
+    let mut archive = ZipArchive::new(Cursor::new(bytes)).map_err(|e| e.to_string())?;
+    let mut plist = String::new();
+    let mut snippets = Vec::new();
+    for index in 0..archive.len() {
+        let mut entry = archive.by_index(index).map_err(|e| e.to_string())?;
+        let entry_name = entry.name().to_string();
+        if !entry.is_file() {
+            continue;
+        }
+        if entry_name.ends_with("info.plist") {
+            entry
+                .read_to_string(&mut plist)
+                .map_err(|e| e.to_string())?;
+        } else if entry_name.ends_with(".json") {
+            let mut text = String::new();
+            entry.read_to_string(&mut text).map_err(|e| e.to_string())?;
+            let file: SnippetFile =
+                serde_json::from_str(&text).map_err(|e| format!("{entry_name}: {e}"))?;
+            snippets.push(file.alfredsnippet);
+        }
+    }
+
+    let prefix = plist_string(&plist, "snippetkeywordprefix");
+    let suffix = plist_string(&plist, "snippetkeywordsuffix");
+    Ok(FileCategory {
+        name: name.to_string(),
+        items: snippets
+            .into_iter()
+            .filter_map(|snippet| {
+                let keyword = if snippet.keyword.trim().is_empty() {
+                    String::new()
+                } else {
+                    format!("{prefix}{}{suffix}", snippet.keyword.trim())
+                };
+                Some(FileItem {
+                    label: super::label(&keyword, &snippet.name)?,
+                    value: snippet.snippet,
+                    kind: ItemKind::Text,
+                })
+            })
+            .collect(),
+    })
+}
+
+pub fn render(category: &FileCategory) -> Result<Vec<u8>, String> {
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    let options = SimpleFileOptions::default();
+    zip.start_file("info.plist", options)
+        .map_err(|e| e.to_string())?;
+    zip.write_all(INFO_PLIST.as_bytes())
+        .map_err(|e| e.to_string())?;
+    for (index, item) in category.items.iter().enumerate() {
+        let file = SnippetFile {
+            alfredsnippet: Snippet {
+                snippet: item.value.clone(),
+                uid: uid(&category.name, index, &item.label),
+                name: item.label.clone(),
+                keyword: super::keyword(&item.label).unwrap_or_default().to_string(),
+            },
+        };
+        let json = serde_json::to_string_pretty(&file).map_err(|e| e.to_string())?;
+        let entry = format!(
+            "{} [{}].json",
+            super::file_stem(&item.label),
+            file.alfredsnippet.uid
+        );
+        zip.start_file(entry, options).map_err(|e| e.to_string())?;
+        zip.write_all(json.as_bytes()).map_err(|e| e.to_string())?;
+    }
+    let cursor = zip.finish().map_err(|e| e.to_string())?;
+    Ok(cursor.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn collection(files: &[(&str, &str)]) -> Vec<u8> {
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, content) in files {
+            zip.start_file(*name, SimpleFileOptions::default()).unwrap();
+            zip.write_all(content.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn applies_the_collection_keyword_prefix() {
+        let bytes = collection(&[
+            (
+                "info.plist",
+                &INFO_PLIST.replacen("<string></string>", "<string>&amp;</string>", 1),
+            ),
+            (
+                "Signature [A1].json",
+                r#"{"alfredsnippet":{"snippet":"Best,\nMe","uid":"A1","name":"Signature","keyword":"sig"}}"#,
+            ),
+            (
+                "Address [B2].json",
+                r#"{"alfredsnippet":{"snippet":"1 Main St","uid":"B2","name":"Address","keyword":""}}"#,
+            ),
+        ]);
+
+        let category = parse("Work", &bytes).unwrap();
+        assert_eq!(category.name, "Work");
+        assert_eq!(
+            category.items,
+            vec![
+                FileItem {
+                    label: "&sig".to_string(),
+                    value: "Best,\nMe".to_string(),
+                    kind: ItemKind::Text,
+                },
+                FileItem {
+                    label: "Address".to_string(),
+                    value: "1 Main St".to_string(),
+                    kind: ItemKind::Text,
+                },
+            ]
+        );
+        assert!(parse("Work", b"not a zip").is_err());
+    }
+
+    #[test]
+    fn uids_are_stable_and_distinct() {
+        assert_eq!(uid("Git", 0, "st"), uid("Git", 0, "st"));
+        assert_ne!(uid("Git", 0, "st"), uid("Git", 1, "st"));
+        assert_ne!(uid("Git", 0, "st"), uid("Docker", 0, "st"));
+        assert_eq!(uid("Git", 0, "st").len(), 36);
+    }
+}
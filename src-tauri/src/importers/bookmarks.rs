@@ -0,0 +1,403 @@
+//! Browser bookmarks in the Netscape HTML format every browser exports.
+//!
+//! Each folder with bookmarks becomes a category named by its path, such as
+//! `Work / Dashboards`, and each bookmark a link item with the title as
+//! label and the URL as value. Bookmarks outside any folder go to
+//! "Bookmarks". Folders and bookmarks keep the file's order, so they get
+//! `sort_order`s in that order when imported.
+
+use std::path::Path;
+
+use sqlx::SqlitePool;
+
+use crate::clock::SystemClock;
+use crate::commands::items::{get_all_items_by_pool, ItemKind};
+use crate::commands::library::{BookmarkFolder, ImportMode, ImportSummary};
+use crate::i18n;
+use crate::library_file::{self, FileCategory, FileItem, LibraryFile};
+
+/// Category for bookmarks that are not in a folder.
+pub const ROOT_CATEGORY: &str = "Bookmarks";
+
+const PATH_SEPARATOR: &str = " / ";
+
+fn decode_entities(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let decoded = rest.find(';').filter(|end| *end <= 10).and_then(|end| {
+            let c = match &rest[1..end] {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some(' '),
+                entity => match entity.strip_prefix("#x").or(entity.strip_prefix("#X")) {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok().and_then(char::from_u32),
+                    None => entity
+                        .strip_prefix('#')
+                        .and_then(|n| n.parse().ok())
+                        .and_then(char::from_u32),
+                },
+            };
+            c.map(|c| (c, end))
+        });
+        match decoded {
+            Some((c, end)) => {
+                out.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Value of attribute `name` in the inside of a start tag.
+fn attribute(tag: &str, name: &str) -> Option<String> {
+    let mut rest = tag;
+    loop {
+        let start = rest.find(|c: char| c.is_ascii_alphabetic())?;
+        rest = &rest[start..];
+        let end = rest
+            .find(|c: char| c.is_whitespace() || c == '=')
+            .unwrap_or(rest.len());
+        let key = &rest[..end];
+        rest = rest[end..].trim_start();
+        let value = match rest.strip_prefix('=') {
+            Some(after) => {
+                let after = after.trim_start();
+                let (value, remaining) = match after.chars().next() {
+                    Some(quote @ ('"' | '\'')) => {
+                        let close = after[1..].find(quote).map_or(after.len(), |i| i + 1);
+                        (&after[1..close], after.get(close + 1..).unwrap_or(""))
+                    }
+                    _ => {
+                        let close = after.find(char::is_whitespace).unwrap_or(after.len());
+                        (&after[..close], &after[close..])
+                    }
+                };
+                rest = remaining;
+                value
+            }
+            None => "",
+        };
+        if key.eq_ignore_ascii_case(name) {
+            return Some(decode_entities(value));
+        }
+    }
+}
+
+/// Text up to the closing `</name>` tag, and the input after it.
+fn element_text<'a>(html: &'a str, name: &str) -> (String, &'a str) {
+    let end = html
+        .match_indices("</")
+        .find(|(i, _)| {
+            html[i + 2..]
+                .get(..name.len())
+                .is_some_and(|tag| tag.eq_ignore_ascii_case(name))
+        })
+        .map_or(html.len(), |(i, _)| i);
+    let after = html[end..].find('>').map_or("", |i| &html[end + i + 1..]);
+    (decode_entities(html[..end].trim()), after)
+}
+
+/// Whether `url` can be opened as a link; browsers also export smart
+/// folders (`place:`) and bookmarklets (`javascript:`).
+fn is_link(url: &str) -> bool {
+    let scheme = url.split_once(':').map_or("", |(scheme, _)| scheme);
+    !scheme.is_empty()
+        && !scheme.eq_ignore_ascii_case("place")
+        && !scheme.eq_ignore_ascii_case("javascript")
+}
+
+/// The folders of a bookmarks file as categories, in file order.
+pub fn parse(html: &str) -> Result<LibraryFile, String> {
+    if !html
+        .trim_start()
+        .get(..22)
+        .is_some_and(|start| start.eq_ignore_ascii_case("<!DOCTYPE NETSCAPE-Boo"))
+    {
+        return Err(i18n::t("error.import_not_bookmarks"));
+    }
+
+    let mut categories: Vec<FileCategory> = Vec::new();
+    let mut path: Vec<String> = Vec::new();
+    // Length of `path` before each open `<DL>`, which may open a folder.
+    let mut lists: Vec<usize> = Vec::new();
+    let mut pending_folder: Option<String> = None;
+    let mut rest = html;
+    while let Some(start) = rest.find('<') {
+        rest = &rest[start + 1..];
+        let Some(end) = rest.find('>') else {
+            break;
+        };
+        let tag = &rest[..end];
+        rest = &rest[end + 1..];
+        let name_end = tag.find(char::is_whitespace).unwrap_or(tag.len());
+        match tag[..name_end].to_ascii_lowercase().as_str() {
+            "h3" => {
+                let (name, after) = element_text(rest, "h3");
+                pending_folder = Some(name);
+                rest = after;
+            }
+            "dl" => {
+                lists.push(path.len());
+                path.extend(pending_folder.take());
+            }
+            "/dl" => path.truncate(lists.pop().unwrap_or(0)),
+            "a" => {
+                let (title, after) = element_text(rest, "a");
+                rest = after;
+                let Some(url) = attribute(&tag[name_end..], "href").filter(|u| is_link(u)) else {
+                    continue;
+                };
+                let name = match path.is_empty() {
+                    true => ROOT_CATEGORY.to_string(),
+                    false => path.join(PATH_SEPARATOR),
+                };
+                let item = FileItem {
+                    label: if title.is_empty() { url.clone() } else { title },
+                    value: url,
+                    kind: ItemKind::Link,
+                };
+                match categories.iter_mut().find(|c| c.name == name) {
+                    Some(category) => category.items.push(item),
+                    None => categories.push(FileCategory {
+                        name,
+                        items: vec![item],
+                    }),
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(LibraryFile { categories })
+}
+
+pub fn read(path: &Path) -> Result<LibraryFile, String> {
+    let html = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    parse(&html)
+}
+
+pub fn folders(library: &LibraryFile) -> Vec<BookmarkFolder> {
+    library
+        .categories
+        .iter()
+        .map(|category| BookmarkFolder {
+            name: category.name.clone(),
+            bookmarks: category.items.len() as u32,
+        })
+        .collect()
+}
+
+/// Keep only the `selected` folders and their subfolders.
+pub fn select(library: &mut LibraryFile, selected: &[String]) {
+    library.categories.retain(|category| {
+        selected.iter().any(|folder| {
+            category.name == *folder
+                || category
+                    .name
+                    .strip_prefix(folder.as_str())
+                    .is_some_and(|rest| rest.starts_with(PATH_SEPARATOR))
+        })
+    });
+}
+
+/// The URL with case-insensitive parts lowercased and a trailing slash
+/// dropped, for spotting the same link saved twice.
+fn normalize_url(url: &str) -> String {
+    let url = url.trim();
+    let url = url.strip_suffix('/').unwrap_or(url);
+    match url.split_once("://") {
+        Some((scheme, rest)) => {
+            let host_end = rest.find('/').unwrap_or(rest.len());
+            format!(
+                "{}://{}{}",
+                scheme.to_ascii_lowercase(),
+                rest[..host_end].to_ascii_lowercase(),
+                &rest[host_end..]
+            )
+        }
+        None => url.to_string(),
+    }
+}
+
+/// Drop bookmarks whose URL is in `known` or earlier in the file, and
+/// return how many were dropped.
+pub fn remove_duplicates<'a>(
+    library: &mut LibraryFile,
+    known: impl IntoIterator<Item = &'a str>,
+) -> u32 {
+    let mut seen: Vec<String> = known.into_iter().map(normalize_url).collect();
+    let mut removed = 0;
+    for category in &mut library.categories {
+        category.items.retain(|item| {
+            let url = normalize_url(&item.value);
+            if seen.contains(&url) {
+                removed += 1;
+                false
+            } else {
+                seen.push(url);
+                true
+            }
+        });
+    }
+    library
+        .categories
+        .retain(|category| !category.items.is_empty());
+    removed
+}
+
+/// Import the `selected` folders of the bookmarks file at `path`, or all of
+/// them, skipping URLs the library already has.
+pub async fn import_by_pool(
+    db: &SqlitePool,
+    path: &Path,
+    selected: Option<&[String]>,
+) -> Result<ImportSummary, String> {
+    let mut library = read(path)?;
+    if let Some(selected) = selected {
+        select(&mut library, selected);
+    }
+    let existing = get_all_items_by_pool(db, &SystemClock).await?;
+    let duplicates = remove_duplicates(
+        &mut library,
+        existing.iter().map(|item| item.value.as_str()),
+    );
+    let mut summary = library_file::import_by_pool(db, &library, ImportMode::Merge).await?;
+    summary.items_skipped += duplicates;
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::items::get_items_by_pool;
+
+    const EXPORT: &str = r#"<!DOCTYPE NETSCAPE-Bookmark-file-1>
+<!-- This is an automatically generated file. -->
+<META HTTP-EQUIV="Content-Type" CONTENT="text/html; charset=UTF-8">
+<TITLE>Bookmarks</TITLE>
+<H1>Bookmarks</H1>
+<DL><p>
+    <DT><H3 ADD_DATE="1700000000" PERSONAL_TOOLBAR_FOLDER="true">Work</H3>
+    <DL><p>
+        <DT><A HREF="https://grafana.example.com/d/api" ADD_DATE="1700000001">API &amp; latency</A>
+        <DT><H3>Dashboards</H3>
+        <DL><p>
+            <DT><A HREF="https://status.example.com/">Status</A>
+            <DT><A HREF="javascript:alert(1)">Bookmarklet</A>
+        </DL><p>
+        <DT><A HREF='https://CI.example.com/builds'></A>
+    </DL><p>
+    <DT><A HREF="https://news.example.org">News</A>
+    <DT><H3>Empty</H3>
+    <DL><p>
+    </DL><p>
+    <DT><H3>Personal</H3>
+    <DL><p>
+        <DT><A HREF="https://status.example.com">Status again</A>
+    </DL><p>
+</DL><p>
+"#;
+
+    async fn setup_db() -> SqlitePool {
+        let db = SqlitePool::connect("sqlite::memory:")
+            .await
+            .expect("connect sqlite memory");
+        sqlx::query(include_str!("../../migrations/002_peeky_domain.sql"))
+            .execute(&db)
+            .await
+            .expect("run migration 002");
+        sqlx::query(include_str!("../../migrations/009_item_kind.sql"))
+            .execute(&db)
+            .await
+            .expect("run migration 009");
+        sqlx::query(include_str!("../../migrations/010_category_links.sql"))
+            .execute(&db)
+            .await
+            .expect("run migration 010");
+        sqlx::query(include_str!("../../migrations/012_item_expiry.sql"))
+            .execute(&db)
+            .await
+            .expect("run migration 012");
+        db
+    }
+
+    #[test]
+    fn parses_folders_in_file_order() {
+        let library = parse(EXPORT).unwrap();
+        let names: Vec<&str> = library.categories.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec!["Work", "Work / Dashboards", "Bookmarks", "Personal"]
+        );
+        assert_eq!(
+            library.categories[0].items,
+            vec![
+                FileItem {
+                    label: "API & latency".to_string(),
+                    value: "https://grafana.example.com/d/api".to_string(),
+                    kind: ItemKind::Link,
+                },
+                FileItem {
+                    label: "https://CI.example.com/builds".to_string(),
+                    value: "https://CI.example.com/builds".to_string(),
+                    kind: ItemKind::Link,
+                },
+            ]
+        );
+        assert_eq!(library.categories[1].items.len(), 1);
+        assert!(parse("<html><body>hi</body></html>").is_err());
+    }
+
+    #[test]
+    fn selects_folders_with_their_subfolders() {
+        let mut library = parse(EXPORT).unwrap();
+        select(&mut library, &["Work".to_string()]);
+        assert_eq!(
+            folders(&library),
+            vec![
+                BookmarkFolder {
+                    name: "Work".to_string(),
+                    bookmarks: 2,
+                },
+                BookmarkFolder {
+                    name: "Work / Dashboards".to_string(),
+                    bookmarks: 1,
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn import_skips_urls_already_saved() {
+        let db = setup_db().await;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bookmarks.html");
+        std::fs::write(&path, EXPORT).unwrap();
+
+        let summary = import_by_pool(&db, &path, None).await.unwrap();
+        assert_eq!(summary.categories_created, 3);
+        assert_eq!(summary.items_created, 4);
+        // "Status again" repeats a URL from Work / Dashboards.
+        assert_eq!(summary.items_skipped, 1);
+
+        let summary = import_by_pool(&db, &path, None).await.unwrap();
+        assert_eq!(summary.items_created, 0);
+        assert_eq!(summary.items_skipped, 5);
+
+        let items = get_items_by_pool(&db, 1).await.unwrap();
+        assert_eq!(items[1].value, "https://CI.example.com/builds");
+        assert_eq!(items[1].sort_order, 1);
+        assert_eq!(items[1].kind, ItemKind::Link);
+    }
+}
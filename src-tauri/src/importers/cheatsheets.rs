@@ -0,0 +1,306 @@
+//! Community cheat sheets from a local checkout: tldr-pages Markdown and
+//! `cheat/cheatsheets` plain text. Each page becomes a category named after
+//! its command, with one item per example.
+//!
+//! A tldr page gives each example as `- Description:` followed by a
+//! backticked command; `{{placeholders}}` are kept as written. A cheat sheet
+//! gives `# comment` lines followed by the command, examples separated by
+//! blank lines, after optional YAML front matter.
+
+use std::path::{Path, PathBuf};
+
+use crate::commands::items::ItemKind;
+use crate::commands::library::CheatsheetFormat;
+use crate::i18n;
+use crate::library_file::{FileCategory, FileItem, LibraryFile};
+
+/// `text` without trailing colons and tldr's `[c]reate`-style mnemonic
+/// brackets.
+fn description(text: &str) -> String {
+    let text = text.trim().trim_end_matches(':').trim_end();
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(open) = rest.find('[') {
+        let after = &rest[open + 1..];
+        match after.find(']') {
+            Some(close) if close > 0 && !after[..close].contains(char::is_whitespace) => {
+                out.push_str(&rest[..open]);
+                out.push_str(&after[..close]);
+                rest = &after[close + 1..];
+            }
+            _ => {
+                out.push_str(&rest[..=open]);
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+pub fn parse_tldr(text: &str) -> Option<FileCategory> {
+    let mut lines = text.lines().map(str::trim);
+    let name = lines
+        .by_ref()
+        .find_map(|line| line.strip_prefix("# "))?
+        .trim();
+    let mut items = Vec::new();
+    let mut pending: Option<String> = None;
+    for line in lines {
+        if let Some(text) = line.strip_prefix("- ") {
+            pending = Some(description(text));
+        } else if let Some(command) = line
+            .strip_prefix('`')
+            .and_then(|line| line.strip_suffix('`'))
+        {
+            if let Some(label) = pending.take() {
+                items.push(FileItem {
+                    label,
+                    value: command.to_string(),
+                    kind: ItemKind::Text,
+                });
+            }
+        }
+    }
+    Some(FileCategory {
+        name: name.to_string(),
+        items,
+    })
+}
+
+pub fn parse_cheat(name: &str, text: &str) -> FileCategory {
+    let mut body = text;
+    if let Some(rest) = text.strip_prefix("---\n") {
+        if let Some(end) = rest.find("\n---") {
+            body = rest[end + 4..].trim_start_matches(|c| c != '\n');
+        }
+    }
+
+    let mut items = Vec::new();
+    let mut comments: Vec<String> = Vec::new();
+    let mut command: Vec<&str> = Vec::new();
+    let mut flush = |comments: &mut Vec<String>, command: &mut Vec<&str>| {
+        if !command.is_empty() {
+            let label = if comments.is_empty() {
+                command[0].trim().to_string()
+            } else {
+                description(&comments.join(" "))
+            };
+            items.push(FileItem {
+                label,
+                value: command.join("\n"),
+                kind: ItemKind::Text,
+            });
+        }
+        comments.clear();
+        command.clear();
+    };
+    for line in body.lines() {
+        if line.trim().is_empty() {
+            flush(&mut comments, &mut command);
+        } else if let Some(comment) = line.trim_start().strip_prefix('#') {
+            // A comment after a command starts the next example.
+            if !command.is_empty() {
+                flush(&mut comments, &mut command);
+            }
+            comments.push(comment.trim().to_string());
+        } else {
+            command.push(line.trim_end());
+        }
+    }
+    flush(&mut comments, &mut command);
+
+    FileCategory {
+        name: name.to_string(),
+        items,
+    }
+}
+
+fn is_page(format: CheatsheetFormat, path: &Path) -> bool {
+    let Some(name) = path.file_name().map(|n| n.to_string_lossy()) else {
+        return false;
+    };
+    match format {
+        CheatsheetFormat::Tldr => path.extension().is_some_and(|ext| ext == "md"),
+        // Sheets have no extension; LICENSE and the like are all caps.
+        CheatsheetFormat::Cheat => {
+            path.extension().is_none() && name.chars().any(|c| c.is_ascii_lowercase())
+        }
+    }
+}
+
+/// Pages under `dir`, sorted. Hidden entries are skipped, and so are tldr's
+/// translations (`pages.de`, ...) unless `dir` is one of them.
+fn pages(format: CheatsheetFormat, dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let mut found = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(current) = pending.pop() {
+        let entries = std::fs::read_dir(&current).map_err(|e| e.to_string())?;
+        for entry in entries {
+            let path = entry.map_err(|e| e.to_string())?.path();
+            let name = path
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            if name.starts_with('.') {
+                continue;
+            }
+            if path.is_dir() {
+                if !(format == CheatsheetFormat::Tldr && name.starts_with("pages.")) {
+                    pending.push(path);
+                }
+            } else if is_page(format, &path) {
+                found.push(path);
+            }
+        }
+    }
+    found.sort();
+    Ok(found)
+}
+
+/// Every page under `dir` that has at least one example.
+pub fn read_dir(format: CheatsheetFormat, dir: &Path) -> Result<LibraryFile, String> {
+    let mut library = LibraryFile::default();
+    for path in pages(format, dir)? {
+        let Ok(text) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+        let page = match format {
+            CheatsheetFormat::Tldr => parse_tldr(&text),
+            CheatsheetFormat::Cheat => Some(parse_cheat(&super::collection_name(&path), &text)),
+        };
+        library
+            .categories
+            .extend(page.filter(|page| !page.items.is_empty()));
+    }
+    if library.categories.is_empty() {
+        return Err(i18n::tf(
+            "error.import_no_cheatsheets",
+            &[("path", &dir.display())],
+        ));
+    }
+    Ok(library)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn items(category: &FileCategory) -> Vec<(&str, &str)> {
+        category
+            .items
+            .iter()
+            .map(|item| (item.label.as_str(), item.value.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn parses_tldr_pages() {
+        let page = parse_tldr(
+            "# tar\n\
+             \n\
+             > Archiving utility.\n\
+             > More information: <https://www.gnu.org/software/tar>.\n\
+             \n\
+             - [c]reate an archive and write it to a [f]ile:\n\
+             \n\
+             `tar cf {{path/to/target.tar}} {{path/to/file1}}`\n\
+             \n\
+             - List the contents of a tar file [v]erbosely:\n\
+             \n\
+             `tar tvf {{path/to/source.tar}}`\n\
+             \n\
+             - Keep [literal brackets] alone:\n\
+             \n\
+             `tar --help`\n",
+        )
+        .unwrap();
+
+        assert_eq!(page.name, "tar");
+        assert_eq!(
+            items(&page),
+            vec![
+                (
+                    "create an archive and write it to a file",
+                    "tar cf {{path/to/target.tar}} {{path/to/file1}}"
+                ),
+                (
+                    "List the contents of a tar file verbosely",
+                    "tar tvf {{path/to/source.tar}}"
+                ),
+                ("Keep [literal brackets] alone", "tar --help"),
+            ]
+        );
+        assert!(parse_tldr("no title here").is_none());
+    }
+
+    #[test]
+    fn parses_cheat_sheets() {
+        let sheet = parse_cheat(
+            "tar",
+            "---\n\
+             syntax: bash\n\
+             tags: [ compression ]\n\
+             ---\n\
+             # To extract an uncompressed archive:\n\
+             tar -xvf /path/to/foo.tar\n\
+             \n\
+             # To create an archive\n\
+             # excluding a directory:\n\
+             tar -cvf foo.tar \\\n\
+             \x20   --exclude=bar .\n\
+             # To list an archive:\n\
+             tar -tvf foo.tar\n\
+             \n\
+             tar --version\n\
+             \n\
+             # Only a heading\n",
+        );
+
+        assert_eq!(
+            items(&sheet),
+            vec![
+                (
+                    "To extract an uncompressed archive",
+                    "tar -xvf /path/to/foo.tar"
+                ),
+                (
+                    "To create an archive excluding a directory",
+                    "tar -cvf foo.tar \\\n    --exclude=bar ."
+                ),
+                ("To list an archive", "tar -tvf foo.tar"),
+                ("tar --version", "tar --version"),
+            ]
+        );
+    }
+
+    #[test]
+    fn reads_pages_from_a_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        let write = |path: &str, text: &str| {
+            let path = dir.path().join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, text).unwrap();
+        };
+        write("pages/common/ls.md", "# ls\n- List files:\n`ls -la`\n");
+        write("pages/linux/ip.md", "# ip\n- Show addresses:\n`ip a`\n");
+        write("pages/common/empty.md", "# empty\n> Nothing here.\n");
+        write(
+            "pages.de/common/ls.md",
+            "# ls\n- Dateien auflisten:\n`ls -la`\n",
+        );
+        write(".github/template.md", "# template\n- Do:\n`it`\n");
+        write("README.md", "# tldr-pages\n");
+
+        let library = read_dir(CheatsheetFormat::Tldr, dir.path()).unwrap();
+        let names: Vec<&str> = library.categories.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["ls", "ip"]);
+
+        write("sheets/git", "# To see status:\ngit status\n");
+        write("sheets/LICENSE", "# Not a sheet\nGPL\n");
+        let library = read_dir(CheatsheetFormat::Cheat, &dir.path().join("sheets")).unwrap();
+        assert_eq!(library.categories.len(), 1);
+        assert_eq!(library.categories[0].name, "git");
+        assert!(read_dir(CheatsheetFormat::Cheat, &dir.path().join("pages")).is_err());
+    }
+}
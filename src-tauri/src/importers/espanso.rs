@@ -0,0 +1,125 @@
+//! Espanso match files: YAML with a `matches` list of
+//! `{trigger | triggers, replace | markdown | html}`. Each file is one
+//! category named after it; matches that expand to something other than
+//! text (forms, images) or only have a `regex` are skipped.
+
+use serde::{Deserialize, Serialize};
+
+use crate::commands::items::ItemKind;
+use crate::library_file::{FileCategory, FileItem};
+
+#[derive(Debug, Default, Deserialize)]
+struct MatchFile {
+    #[serde(default)]
+    matches: Vec<Match>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Match {
+    trigger: Option<String>,
+    #[serde(default)]
+    triggers: Vec<String>,
+    replace: Option<String>,
+    markdown: Option<String>,
+    html: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct OutFile<'a> {
+    matches: Vec<OutMatch<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+struct OutMatch<'a> {
+    trigger: &'a str,
+    replace: &'a str,
+}
+
+pub fn parse(name: &str, text: &str) -> Result<FileCategory, String> {
+    // An empty file (or one with only comments) has no matches.
+    let file: MatchFile = serde_yaml_ng::from_str::<Option<MatchFile>>(text)
+        .map_err(|e| e.to_string())?
+        .unwrap_or_default();
+    Ok(FileCategory {
+        name: name.to_string(),
+        items: file
+            .matches
+            .into_iter()
+            .filter_map(|m| {
+                let trigger = m.trigger.or_else(|| m.triggers.into_iter().next())?;
+                Some(FileItem {
+                    label: super::label(&trigger, "")?,
+                    value: m.replace.or(m.markdown).or(m.html)?,
+                    kind: ItemKind::Text,
+                })
+            })
+            .collect(),
+    })
+}
+
+pub fn render(category: &FileCategory) -> Result<String, String> {
+    let file = OutFile {
+        matches: category
+            .items
+            .iter()
+            .map(|item| OutMatch {
+                trigger: &item.label,
+                replace: &item.value,
+            })
+            .collect(),
+    };
+    serde_yaml_ng::to_string(&file).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_text_matches_and_skips_the_rest() {
+        let category = parse(
+            "base",
+            r#"
+# Comments are fine
+matches:
+  - trigger: ":sig"
+    replace: |
+      Best,
+      Me
+  - triggers: [":ty", ":thx"]
+    replace: "Thank you!"
+  - trigger: ":bold"
+    markdown: "**bold**"
+  - regex: ":(?P<n>\\d+)x"
+    replace: "{{n}} times"
+  - trigger: ":form"
+    form: "Hi [[name]]"
+  - trigger: ":now"
+    replace: "{{time}}"
+    vars:
+      - name: time
+        type: date
+        params:
+          format: "%H:%M"
+"#,
+        )
+        .unwrap();
+
+        let items: Vec<(&str, &str)> = category
+            .items
+            .iter()
+            .map(|item| (item.label.as_str(), item.value.as_str()))
+            .collect();
+        assert_eq!(
+            items,
+            vec![
+                (":sig", "Best,\nMe\n"),
+                (":ty", "Thank you!"),
+                (":bold", "**bold**"),
+                (":now", "{{time}}"),
+            ]
+        );
+        assert!(parse("empty", "# nothing yet\n").unwrap().items.is_empty());
+        assert!(parse("broken", "matches: [").is_err());
+    }
+}
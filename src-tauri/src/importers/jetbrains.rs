@@ -0,0 +1,160 @@
+//! JetBrains keymap XML (`<keymap name="...">` with `<action id>` holding
+//! `<keyboard-shortcut first-keystroke second-keystroke>`). Keystrokes use
+//! Java's notation, e.g. `shift meta OPEN_BRACKET`. Actions without
+//! keyboard shortcuts, which is how a keymap unbinds an inherited one, add
+//! nothing.
+
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+
+use crate::commands::items::ItemKind;
+use crate::i18n;
+use crate::library_file::{FileCategory, FileItem};
+
+const DEFAULT_CATEGORY: &str = "JetBrains";
+
+/// Java key names that differ from Peeky's.
+const KEY_NAMES: &[(&str, &str)] = &[
+    ("OPEN_BRACKET", "["),
+    ("CLOSE_BRACKET", "]"),
+    ("BACK_SLASH", "\\"),
+    ("SLASH", "/"),
+    ("SEMICOLON", ";"),
+    ("QUOTE", "'"),
+    ("BACK_QUOTE", "`"),
+    ("COMMA", ","),
+    ("PERIOD", "."),
+    ("MINUS", "-"),
+    ("EQUALS", "="),
+    ("BACK_SPACE", "Backspace"),
+    ("PAGE_UP", "PageUp"),
+    ("PAGE_DOWN", "PageDown"),
+];
+
+/// One keystroke (`ctrl alt L`) in Peeky's notation, or as written if it
+/// does not parse.
+fn normalize_keystroke(keystroke: &str) -> String {
+    let tokens: Vec<&str> = keystroke
+        .split_whitespace()
+        .filter(|token| *token != "pressed")
+        .collect();
+    let Some((key, modifiers)) = tokens.split_last() else {
+        return String::new();
+    };
+    let key = KEY_NAMES
+        .iter()
+        .find(|(java, _)| java == key)
+        .map_or(*key, |(_, peeky)| *peeky);
+    let notation = modifiers
+        .iter()
+        .copied()
+        .chain([key])
+        .collect::<Vec<_>>()
+        .join("+");
+    crate::chord::normalize(&notation).unwrap_or_else(|_| keystroke.trim().to_string())
+}
+
+fn attribute(element: &BytesStart, name: &str) -> Result<Option<String>, String> {
+    for attr in element.attributes() {
+        let attr = attr.map_err(|e| e.to_string())?;
+        if attr.key.as_ref() == name.as_bytes() {
+            let value = attr.unescape_value().map_err(|e| e.to_string())?;
+            return Ok(Some(value.into_owned()));
+        }
+    }
+    Ok(None)
+}
+
+pub fn parse(xml: &str) -> Result<FileCategory, String> {
+    let mut reader = Reader::from_str(xml);
+    let mut category = FileCategory {
+        name: DEFAULT_CATEGORY.to_string(),
+        items: Vec::new(),
+    };
+    let mut saw_keymap = false;
+    let mut action: Option<String> = None;
+    loop {
+        let event = reader.read_event().map_err(|e| e.to_string())?;
+        match &event {
+            Event::Start(element) | Event::Empty(element) => match element.name().as_ref() {
+                b"keymap" => {
+                    saw_keymap = true;
+                    if let Some(name) = attribute(element, "name")?.filter(|n| !n.trim().is_empty())
+                    {
+                        category.name = name.trim().to_string();
+                    }
+                }
+                b"action" if matches!(event, Event::Start(_)) => {
+                    action = attribute(element, "id")?;
+                }
+                b"keyboard-shortcut" => {
+                    let (Some(id), Some(first)) =
+                        (action.as_ref(), attribute(element, "first-keystroke")?)
+                    else {
+                        continue;
+                    };
+                    let mut value = normalize_keystroke(&first);
+                    if let Some(second) = attribute(element, "second-keystroke")? {
+                        value = format!("{value} {}", normalize_keystroke(&second));
+                    }
+                    category.items.push(FileItem {
+                        label: id.clone(),
+                        value,
+                        kind: ItemKind::Text,
+                    });
+                }
+                _ => {}
+            },
+            Event::End(element) if element.name().as_ref() == b"action" => action = None,
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    if !saw_keymap {
+        return Err(i18n::t("error.import_not_keymap"));
+    }
+    Ok(category)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_keyboard_shortcuts_of_each_action() {
+        let category = parse(
+            r#"<keymap version="1" name="macOS &amp; mine" parent="Mac OS X 10.5+">
+  <action id="EditorDuplicate">
+    <keyboard-shortcut first-keystroke="meta d" />
+    <keyboard-shortcut first-keystroke="shift meta OPEN_BRACKET" />
+  </action>
+  <action id="ReformatCode">
+    <keyboard-shortcut first-keystroke="ctrl k" second-keystroke="alt BACK_SPACE" />
+    <mouse-shortcut keystroke="button2" />
+  </action>
+  <action id="RemovedAction" />
+  <action id="Odd">
+    <keyboard-shortcut first-keystroke="altGraph NUMPAD1" />
+  </action>
+</keymap>"#,
+        )
+        .unwrap();
+
+        assert_eq!(category.name, "macOS & mine");
+        let items: Vec<(&str, &str)> = category
+            .items
+            .iter()
+            .map(|item| (item.label.as_str(), item.value.as_str()))
+            .collect();
+        assert_eq!(
+            items,
+            vec![
+                ("EditorDuplicate", "Cmd+D"),
+                ("EditorDuplicate", "Shift+Cmd+["),
+                ("ReformatCode", "Ctrl+K Option+Backspace"),
+                ("Odd", "altGraph NUMPAD1"),
+            ]
+        );
+        assert!(parse("<settings/>").is_err());
+    }
+}
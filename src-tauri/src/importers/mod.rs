@@ -0,0 +1,204 @@
+//! Snippet files of other tools, read into a [`LibraryFile`] and written
+//! back out from one.
+//!
+//! Each format's collections (a file, or a collection inside one) become
+//! categories and its triggers or keywords become item labels. Importing
+//! then goes through [`library_file::import_by_pool`] like our own files.
+//! Editor keymaps are read the same way, with commands as labels and key
+//! chords as values, and so are cheat sheet pages, SSH config hosts and
+//! browser bookmarks; none of these can be written back.
+//!
+//! [`library_file::import_by_pool`]: crate::library_file::import_by_pool
+
+pub mod alfred;
+pub mod bookmarks;
+pub mod cheatsheets;
+pub mod espanso;
+pub mod jetbrains;
+pub mod raycast;
+pub mod ssh_config;
+pub mod vscode;
+
+use std::path::{Path, PathBuf};
+
+use crate::commands::library::{KeymapFormat, SnippetFormat};
+use crate::library_file::LibraryFile;
+
+/// The label for a snippet: its keyword if it has one, else its name.
+fn label(keyword: &str, name: &str) -> Option<String> {
+    [keyword, name]
+        .into_iter()
+        .map(str::trim)
+        .find(|text| !text.is_empty())
+        .map(str::to_string)
+}
+
+/// A label usable as a trigger keyword: one word, no spaces. Labels like
+/// "Undo last commit" are only exported as names.
+fn keyword(label: &str) -> Option<&str> {
+    (!label.is_empty() && !label.contains(char::is_whitespace)).then_some(label)
+}
+
+/// `name` as a file name without extension, safe on every platform.
+fn file_stem(name: &str) -> String {
+    let stem: String = name
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    let stem = stem.trim().trim_matches('.');
+    if stem.is_empty() {
+        "_".to_string()
+    } else {
+        stem.to_string()
+    }
+}
+
+/// The collection a file holds, named after the file.
+fn collection_name(path: &Path) -> String {
+    path.file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+fn extension(format: SnippetFormat) -> &'static str {
+    match format {
+        SnippetFormat::Alfred => "alfredsnippets",
+        SnippetFormat::Raycast => "json",
+        SnippetFormat::Espanso => "yml",
+    }
+}
+
+/// Read the snippet file at `path` as one category.
+pub fn read(format: SnippetFormat, path: &Path) -> Result<LibraryFile, String> {
+    let name = collection_name(path);
+    let bytes = std::fs::read(path).map_err(|e| e.to_string())?;
+    let text = || String::from_utf8(bytes.clone()).map_err(|e| e.to_string());
+    let category = match format {
+        SnippetFormat::Alfred => alfred::parse(&name, &bytes)?,
+        SnippetFormat::Raycast => raycast::parse(&name, &text()?)?,
+        SnippetFormat::Espanso => espanso::parse(&name, &text()?)?,
+    };
+    Ok(LibraryFile {
+        categories: vec![category],
+    })
+}
+
+/// Read the editor keymap at `path` as one category.
+pub fn read_keymap(format: KeymapFormat, path: &Path) -> Result<LibraryFile, String> {
+    let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let category = match format {
+        KeymapFormat::Vscode => vscode::parse(&text)?,
+        KeymapFormat::Jetbrains => jetbrains::parse(&text)?,
+    };
+    Ok(LibraryFile {
+        categories: vec![category],
+    })
+}
+
+/// Write one file per category into `dir`, replacing files of the same
+/// name, and return their paths.
+pub fn write(
+    format: SnippetFormat,
+    library: &LibraryFile,
+    dir: &Path,
+) -> Result<Vec<PathBuf>, String> {
+    std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    let mut paths: Vec<PathBuf> = Vec::new();
+    for category in &library.categories {
+        let bytes = match format {
+            SnippetFormat::Alfred => alfred::render(category)?,
+            SnippetFormat::Raycast => raycast::render(category)?.into_bytes(),
+            SnippetFormat::Espanso => espanso::render(category)?.into_bytes(),
+        };
+        // Category names are unique only up to case, file names may not be.
+        let stem = file_stem(&category.name);
+        let mut path = dir.join(format!("{stem}.{}", extension(format)));
+        let mut n = 2;
+        while paths.iter().any(|p| {
+            p.to_string_lossy()
+                .eq_ignore_ascii_case(&path.to_string_lossy())
+        }) {
+            path = dir.join(format!("{stem} ({n}).{}", extension(format)));
+            n += 1;
+        }
+        std::fs::write(&path, bytes).map_err(|e| e.to_string())?;
+        paths.push(path);
+    }
+    Ok(paths)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::items::ItemKind;
+    use crate::library_file::{FileCategory, FileItem};
+
+    fn library() -> LibraryFile {
+        let category = |name: &str, items: &[(&str, &str)]| FileCategory {
+            name: name.to_string(),
+            items: items
+                .iter()
+                .map(|(label, value)| FileItem {
+                    label: label.to_string(),
+                    value: value.to_string(),
+                    kind: ItemKind::Text,
+                })
+                .collect(),
+        };
+        LibraryFile {
+            categories: vec![
+                category(
+                    "Git / GitHub",
+                    &[
+                        (";squash", "git rebase -i"),
+                        ("Undo last commit", "git reset HEAD~1"),
+                    ],
+                ),
+                category("git / github", &[("Sign-off", "Signed-off-by: Me\n")]),
+            ],
+        }
+    }
+
+    #[test]
+    fn labels_and_file_names() {
+        assert_eq!(label(" ", " Name "), Some("Name".to_string()));
+        assert_eq!(label(";sig", "Signature"), Some(";sig".to_string()));
+        assert_eq!(label("", ""), None);
+        assert_eq!(keyword(":date"), Some(":date"));
+        assert_eq!(keyword("two words"), None);
+        assert_eq!(file_stem("a/b: c?"), "a_b_ c_");
+        assert_eq!(file_stem(".."), "_");
+    }
+
+    #[test]
+    fn every_format_round_trips_through_files() {
+        for format in [
+            SnippetFormat::Alfred,
+            SnippetFormat::Raycast,
+            SnippetFormat::Espanso,
+        ] {
+            let dir = tempfile::tempdir().unwrap();
+            let paths = write(format, &library(), dir.path()).unwrap();
+            assert_eq!(paths.len(), 2, "{format:?}");
+            assert_ne!(paths[0], paths[1]);
+
+            let first = read(format, &paths[0]).unwrap();
+            assert_eq!(first.categories[0].name, "Git _ GitHub");
+            assert_eq!(
+                first.categories[0].items,
+                library().categories[0].items,
+                "{format:?}"
+            );
+            let second = read(format, &paths[1]).unwrap();
+            assert_eq!(
+                second.categories[0].items,
+                library().categories[1].items,
+                "{format:?}"
+            );
+        }
+    }
+}
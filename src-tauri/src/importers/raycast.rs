@@ -0,0 +1,83 @@
+//! Raycast snippet exports: a JSON array of `{"name", "text", "keyword"}`.
+//! Raycast has no collections, so each file is one category named after it.
+
+use serde::{Deserialize, Serialize};
+
+use crate::commands::items::ItemKind;
+use crate::library_file::{FileCategory, FileItem};
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Snippet {
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    text: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    keyword: Option<String>,
+}
+
+pub fn parse(name: &str, text: &str) -> Result<FileCategory, String> {
+    let snippets: Vec<Snippet> = serde_json::from_str(text).map_err(|e| e.to_string())?;
+    Ok(FileCategory {
+        name: name.to_string(),
+        items: snippets
+            .into_iter()
+            .filter_map(|snippet| {
+                Some(FileItem {
+                    label: super::label(snippet.keyword.as_deref().unwrap_or(""), &snippet.name)?,
+                    value: snippet.text,
+                    kind: ItemKind::Text,
+                })
+            })
+            .collect(),
+    })
+}
+
+pub fn render(category: &FileCategory) -> Result<String, String> {
+    let snippets: Vec<Snippet> = category
+        .items
+        .iter()
+        .map(|item| Snippet {
+            name: item.label.clone(),
+            text: item.value.clone(),
+            keyword: super::keyword(&item.label).map(str::to_string),
+        })
+        .collect();
+    serde_json::to_string_pretty(&snippets).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keywords_become_labels() {
+        let category = parse(
+            "snippets",
+            r#"[
+              {"name": "Email signature", "text": "Best,\nMe", "keyword": "!sig"},
+              {"name": "Office address", "text": "1 Main St"},
+              {"name": "", "text": "nameless", "keyword": ""}
+            ]"#,
+        )
+        .unwrap();
+
+        assert_eq!(category.name, "snippets");
+        assert_eq!(
+            category.items,
+            vec![
+                FileItem {
+                    label: "!sig".to_string(),
+                    value: "Best,\nMe".to_string(),
+                    kind: ItemKind::Text,
+                },
+                FileItem {
+                    label: "Office address".to_string(),
+                    value: "1 Main St".to_string(),
+                    kind: ItemKind::Text,
+                },
+            ]
+        );
+        assert!(parse("snippets", r#"{"categories": []}"#).is_err());
+    }
+}
@@ -0,0 +1,384 @@
+//! OpenSSH client config (`~/.ssh/config`) as an "SSH Hosts" category with
+//! one item per host alias, valued `user@hostname:port via jump`.
+//!
+//! Options are resolved the way `ssh` does for the keywords we show: every
+//! `Host` block whose patterns match the alias contributes, and the first
+//! value seen for a keyword wins. `Include` is followed, with `~`, globs and
+//! paths relative to the config's own directory. `Match` blocks depend on
+//! more than the alias and are skipped, as are wildcard-only `Host` lines.
+//!
+//! With the `ssh_hosts_sync` setting on, the category is re-synced from the
+//! file at startup, dropping hosts that were removed from it.
+
+use std::path::{Path, PathBuf};
+
+use sqlx::SqlitePool;
+use tauri::{AppHandle, Manager};
+
+use crate::commands::items::ItemKind;
+use crate::commands::library::{ImportMode, ImportSummary};
+use crate::commands::settings::get_settings_by_pool;
+use crate::control;
+use crate::db::Db;
+use crate::library_file::{self, FileCategory, FileItem, LibraryFile};
+
+pub const CATEGORY: &str = "SSH Hosts";
+
+/// `ssh` refuses to nest `Include` deeper than this.
+const MAX_INCLUDE_DEPTH: usize = 16;
+
+#[derive(Debug)]
+struct Block {
+    /// `Host` patterns; empty for `Match` blocks, which match nothing here.
+    patterns: Vec<String>,
+    /// Lowercased keyword and its arguments, in file order.
+    options: Vec<(String, String)>,
+}
+
+fn home_dir() -> Option<PathBuf> {
+    std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .map(PathBuf::from)
+}
+
+/// `~/.ssh/config`.
+pub fn default_path() -> Option<PathBuf> {
+    Some(home_dir()?.join(".ssh").join("config"))
+}
+
+/// `*` and `?` wildcard match, ignoring ASCII case as `ssh` does for hosts.
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.to_ascii_lowercase().chars().collect();
+    let text: Vec<char> = text.to_ascii_lowercase().chars().collect();
+    let (mut p, mut t) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = star {
+            p = star_p + 1;
+            t = star_t + 1;
+            star = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+fn block_matches(patterns: &[String], host: &str) -> bool {
+    let mut matched = false;
+    for pattern in patterns {
+        match pattern.strip_prefix('!') {
+            Some(negated) if wildcard_match(negated, host) => return false,
+            Some(_) => {}
+            None => matched |= wildcard_match(pattern, host),
+        }
+    }
+    matched
+}
+
+/// Keyword and arguments of a config line, or `None` for blanks and
+/// comments. Arguments may be double-quoted; the keyword may be followed by
+/// `=` instead of a space.
+fn split_line(line: &str) -> Option<(String, Vec<String>)> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+    let end = line
+        .find(|c: char| c.is_whitespace() || c == '=')
+        .unwrap_or(line.len());
+    let keyword = line[..end].to_ascii_lowercase();
+    let rest = line[end..].trim_start();
+    let rest = rest.strip_prefix('=').unwrap_or(rest);
+
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    let mut has_arg = false;
+    for c in rest.chars() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                has_arg = true;
+            }
+            c if c.is_whitespace() && !quoted => {
+                if has_arg {
+                    args.push(std::mem::take(&mut current));
+                    has_arg = false;
+                }
+            }
+            c => {
+                current.push(c);
+                has_arg = true;
+            }
+        }
+    }
+    if has_arg {
+        args.push(current);
+    }
+    Some((keyword, args))
+}
+
+/// Files matching `pattern`, which may use wildcards in any component,
+/// sorted like `ssh` sorts glob results.
+fn glob(pattern: &Path) -> Vec<PathBuf> {
+    let mut matches = vec![PathBuf::new()];
+    for component in pattern.components() {
+        let part = component.as_os_str().to_string_lossy();
+        if !part.contains(['*', '?']) {
+            for path in &mut matches {
+                path.push(component);
+            }
+            continue;
+        }
+        let mut next = Vec::new();
+        for dir in &matches {
+            let Ok(entries) = std::fs::read_dir(dir) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let name = entry.file_name().to_string_lossy().into_owned();
+                if !name.starts_with('.') && wildcard_match(&part, &name) {
+                    next.push(dir.join(name));
+                }
+            }
+        }
+        matches = next;
+    }
+    matches.retain(|path| path.is_file());
+    matches.sort();
+    matches
+}
+
+fn load(path: &Path, dir: &Path, depth: usize, blocks: &mut Vec<Block>) -> Result<(), String> {
+    let text = std::fs::read_to_string(path).map_err(|e| format!("{}: {e}", path.display()))?;
+    for line in text.lines() {
+        let Some((keyword, args)) = split_line(line) else {
+            continue;
+        };
+        match keyword.as_str() {
+            "host" => blocks.push(Block {
+                patterns: args,
+                options: Vec::new(),
+            }),
+            "match" => blocks.push(Block {
+                patterns: Vec::new(),
+                options: Vec::new(),
+            }),
+            "include" if depth < MAX_INCLUDE_DEPTH => {
+                let parent = blocks.len() - 1;
+                for arg in args {
+                    let target = match arg.strip_prefix("~/") {
+                        Some(rest) => home_dir().unwrap_or_default().join(rest),
+                        None => dir.join(arg),
+                    };
+                    for file in glob(&target) {
+                        load(&file, dir, depth + 1, blocks)?;
+                    }
+                }
+                // Lines after the Include belong to the block it was in,
+                // even if the included files started new ones.
+                if blocks.len() - 1 != parent {
+                    let patterns = blocks[parent].patterns.clone();
+                    blocks.push(Block {
+                        patterns,
+                        options: Vec::new(),
+                    });
+                }
+            }
+            _ => {
+                if let (Some(block), Some(value)) = (blocks.last_mut(), args.first()) {
+                    block.options.push((keyword, value.clone()));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Items for every alias in `blocks`, in the order they first appear.
+fn hosts(blocks: &[Block]) -> Vec<FileItem> {
+    let mut aliases: Vec<&str> = Vec::new();
+    for block in blocks {
+        for pattern in &block.patterns {
+            let concrete = !pattern.contains(['*', '?', '!']);
+            if concrete && !aliases.iter().any(|a| a.eq_ignore_ascii_case(pattern)) {
+                aliases.push(pattern);
+            }
+        }
+    }
+
+    aliases
+        .into_iter()
+        .map(|alias| {
+            let option = |keyword: &str| {
+                blocks
+                    .iter()
+                    .filter(|block| block_matches(&block.patterns, alias))
+                    .flat_map(|block| &block.options)
+                    .find(|(k, _)| k == keyword)
+                    .map(|(_, value)| value.as_str())
+            };
+            let mut host = option("hostname")
+                .map(|name| name.replace("%h", alias).replace("%%", "%"))
+                .unwrap_or_else(|| alias.to_string());
+            if let Some(port) = option("port").filter(|port| *port != "22") {
+                if host.contains(':') {
+                    host = format!("[{host}]");
+                }
+                host = format!("{host}:{port}");
+            }
+            let mut value = match option("user") {
+                Some(user) => format!("{user}@{host}"),
+                None => host,
+            };
+            if let Some(jump) = option("proxyjump").filter(|j| !j.eq_ignore_ascii_case("none")) {
+                value = format!("{value} via {jump}");
+            }
+            FileItem {
+                label: alias.to_string(),
+                value,
+                kind: ItemKind::Text,
+            }
+        })
+        .collect()
+}
+
+/// The hosts of the config at `path` as the "SSH Hosts" category.
+pub fn read(path: &Path) -> Result<LibraryFile, String> {
+    let dir = path.parent().unwrap_or(Path::new("."));
+    // Options before the first Host line apply to every host.
+    let mut blocks = vec![Block {
+        patterns: vec!["*".to_string()],
+        options: Vec::new(),
+    }];
+    load(path, dir, 0, &mut blocks)?;
+    Ok(LibraryFile {
+        categories: vec![FileCategory {
+            name: CATEGORY.to_string(),
+            items: hosts(&blocks),
+        }],
+    })
+}
+
+pub async fn import_by_pool(
+    db: &SqlitePool,
+    path: &Path,
+    mode: ImportMode,
+) -> Result<ImportSummary, String> {
+    library_file::import_by_pool(db, &read(path)?, mode).await
+}
+
+/// Re-sync the category from `~/.ssh/config` if `ssh_hosts_sync` is on.
+pub fn start(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let db = app.state::<Db>().pool();
+        let enabled = get_settings_by_pool(&db)
+            .await
+            .is_ok_and(|settings| settings.ssh_hosts_sync);
+        let Some(path) = default_path().filter(|_| enabled) else {
+            return;
+        };
+        match import_by_pool(&db, &path, ImportMode::Replace).await {
+            Ok(summary) => {
+                if summary.items_created + summary.items_updated + summary.items_deleted > 0 {
+                    control::reload_library(&app);
+                }
+            }
+            Err(err) => eprintln!("failed to sync SSH hosts: {err}"),
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn items(library: &LibraryFile) -> Vec<(&str, &str)> {
+        library.categories[0]
+            .items
+            .iter()
+            .map(|item| (item.label.as_str(), item.value.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn matches_host_patterns_like_ssh() {
+        assert!(wildcard_match("*.example.com", "web.EXAMPLE.com"));
+        assert!(wildcard_match("db-?", "db-1"));
+        assert!(!wildcard_match("db-?", "db-10"));
+        let patterns = vec!["*.internal".to_string(), "!bastion.internal".to_string()];
+        assert!(block_matches(&patterns, "web.internal"));
+        assert!(!block_matches(&patterns, "bastion.internal"));
+        assert_eq!(
+            split_line("  IdentityFile=\"~/My Keys/id\" extra # no"),
+            Some((
+                "identityfile".to_string(),
+                vec![
+                    "~/My Keys/id".to_string(),
+                    "extra".to_string(),
+                    "#".to_string(),
+                    "no".to_string()
+                ]
+            ))
+        );
+        assert_eq!(split_line("# comment"), None);
+    }
+
+    #[test]
+    fn resolves_hosts_through_includes() {
+        let dir = tempfile::tempdir().unwrap();
+        let write = |name: &str, text: &str| {
+            let path = dir.path().join(name);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, text).unwrap();
+        };
+        write(
+            "config",
+            "Include conf.d/*.conf\n\
+             \n\
+             Host web web-alias\n\
+             \x20   HostName web.example.com\n\
+             \x20   Port 2222\n\
+             \n\
+             Host db\n\
+             \x20   Include extra\n\
+             \x20   HostName %h.internal\n\
+             \n\
+             Match host *.example.com\n\
+             \x20   User matched\n\
+             \n\
+             Host *.internal !bastion.internal\n\
+             \x20   ProxyJump bastion\n\
+             \n\
+             Host *\n\
+             \x20   User default\n",
+        );
+        write(
+            "conf.d/10-work.conf",
+            "Host bastion.internal\n  User admin\n  ProxyJump none\n",
+        );
+        write("conf.d/README", "Host ignored\n");
+        write("extra", "User dba\n");
+
+        let library = read(&dir.path().join("config")).unwrap();
+        assert_eq!(library.categories[0].name, "SSH Hosts");
+        assert_eq!(
+            items(&library),
+            vec![
+                ("bastion.internal", "admin@bastion.internal"),
+                ("web", "default@web.example.com:2222"),
+                ("web-alias", "default@web.example.com:2222"),
+                ("db", "dba@db.internal"),
+            ]
+        );
+        assert!(read(&dir.path().join("missing")).is_err());
+    }
+}
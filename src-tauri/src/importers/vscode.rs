@@ -0,0 +1,137 @@
+//! VS Code `keybindings.json`: a JSON-with-comments array of
+//! `{"key", "command", "when"}`. Entries whose command starts with `-`
+//! remove a default binding and are skipped.
+
+use serde::Deserialize;
+
+use crate::commands::items::ItemKind;
+use crate::library_file::{FileCategory, FileItem};
+
+const CATEGORY: &str = "VS Code";
+
+#[derive(Debug, Deserialize)]
+struct Binding {
+    #[serde(default)]
+    key: String,
+    #[serde(default)]
+    command: String,
+}
+
+/// `text` without `//` and `/* */` comments and trailing commas, which VS
+/// Code allows in its settings files and `serde_json` does not.
+fn strip_jsonc(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    let mut in_string = false;
+    while let Some(c) = chars.next() {
+        if in_string {
+            out.push(c);
+            match c {
+                '\\' => out.extend(chars.next()),
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                out.push(c);
+            }
+            ('/', Some('/')) => while chars.next_if(|&c| c != '\n').is_some() {},
+            ('/', Some('*')) => {
+                chars.next();
+                let mut previous = ' ';
+                for c in chars.by_ref() {
+                    if previous == '*' && c == '/' {
+                        break;
+                    }
+                    previous = c;
+                }
+                out.push(' ');
+            }
+            (']' | '}', _) => {
+                let kept = out.trim_end().len();
+                if out[..kept].ends_with(',') {
+                    out.truncate(kept - 1);
+                }
+                out.push(c);
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// A VS Code key sequence (`ctrl+k ctrl+c`) in Peeky's notation, or as
+/// written if a key has no Peeky name (numpad keys, for instance).
+fn normalize_keys(keys: &str) -> String {
+    keys.split_whitespace()
+        .map(crate::chord::normalize)
+        .collect::<Result<Vec<_>, _>>()
+        .map(|chords| chords.join(" "))
+        .unwrap_or_else(|_| keys.trim().to_string())
+}
+
+pub fn parse(text: &str) -> Result<FileCategory, String> {
+    let bindings: Vec<Binding> =
+        serde_json::from_str(&strip_jsonc(text)).map_err(|e| e.to_string())?;
+    Ok(FileCategory {
+        name: CATEGORY.to_string(),
+        items: bindings
+            .into_iter()
+            .filter(|binding| {
+                let command = binding.command.trim();
+                !command.is_empty() && !command.starts_with('-') && !binding.key.trim().is_empty()
+            })
+            .map(|binding| FileItem {
+                label: binding.command.trim().to_string(),
+                value: normalize_keys(&binding.key),
+                kind: ItemKind::Text,
+            })
+            .collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_user_overrides() {
+        let category = parse(
+            r#"// Place your key bindings in this file to override the defaults
+[
+    {
+        "key": "cmd+/",
+        "command": "editor.action.commentLine",
+        "when": "editorTextFocus && !editorReadonly" // comment "quoted"
+    },
+    /* removed default */
+    { "key": "ctrl+shift+k", "command": "-editor.action.deleteLines" },
+    { "key": "ctrl+k ctrl+alt+s", "command": "git.stageAll" },
+    { "key": "shift+numpad_add", "command": "editor.fold" },
+    { "key": "", "command": "workbench.action.files.save" },
+    { "key": "alt+up", "command": "editor.action.moveLinesUpAction", "args": {"text": "//,]"}, },
+]"#,
+        )
+        .unwrap();
+
+        assert_eq!(category.name, "VS Code");
+        let items: Vec<(&str, &str)> = category
+            .items
+            .iter()
+            .map(|item| (item.label.as_str(), item.value.as_str()))
+            .collect();
+        assert_eq!(
+            items,
+            vec![
+                ("editor.action.commentLine", "Cmd+/"),
+                ("git.stageAll", "Ctrl+K Ctrl+Option+S"),
+                ("editor.fold", "shift+numpad_add"),
+                ("editor.action.moveLinesUpAction", "Option+Up"),
+            ]
+        );
+        assert!(parse("{").is_err());
+    }
+}
@@ -0,0 +1,137 @@
+//! Feeds system-wide key events into the hold-to-peek trigger.
+//!
+//! Only macOS is supported: modifier-only holds cannot be expressed as a
+//! global shortcut, so the app watches raw `flagsChanged`/`keyDown` events
+//! through NSEvent monitors. Global key monitoring requires the app to be
+//! trusted for Accessibility; without it the monitor simply never fires.
+
+use tauri::AppHandle;
+
+use crate::hold_trigger::HoldTriggerHandle;
+
+#[cfg(target_os = "macos")]
+pub fn start(app: &AppHandle, trigger: HoldTriggerHandle) {
+    macos::install_monitors(app.clone(), trigger.clone());
+    macos::spawn_ticker(app.clone(), trigger);
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn start(_app: &AppHandle, _trigger: HoldTriggerHandle) {
+    eprintln!("hold-to-peek is only supported on macOS");
+}
+
+#[cfg(target_os = "macos")]
+mod macos {
+    use std::cell::Cell;
+    use std::ptr::NonNull;
+    use std::rc::Rc;
+    use std::time::Duration;
+
+    use block2::RcBlock;
+    use objc2_app_kit::{NSEvent, NSEventMask, NSEventModifierFlags, NSEventType};
+    use tauri::AppHandle;
+
+    use crate::clock::{Clock, SystemClock};
+    use crate::hold_trigger::{HoldAction, HoldModifier, HoldTriggerHandle, Key, KeyEvent};
+    use crate::hotkeys;
+    use crate::overlay;
+
+    /// How often a hold in progress is checked for completion.
+    const TICK_INTERVAL: Duration = Duration::from_millis(20);
+
+    const MODIFIER_FLAGS: [(NSEventModifierFlags, HoldModifier); 4] = [
+        (NSEventModifierFlags::Command, HoldModifier::Command),
+        (NSEventModifierFlags::Option, HoldModifier::Option),
+        (NSEventModifierFlags::Control, HoldModifier::Control),
+        (NSEventModifierFlags::Shift, HoldModifier::Shift),
+    ];
+
+    fn apply(app: &AppHandle, action: HoldAction) {
+        if action == HoldAction::Show && hotkeys::is_paused(app) {
+            return;
+        }
+        let handle = app.clone();
+        let _ = app.run_on_main_thread(move || match action {
+            HoldAction::Show => overlay::show(&handle),
+            HoldAction::Hide => overlay::hide(&handle),
+        });
+    }
+
+    /// Translate an NSEvent into trigger key events. `previous` holds the
+    /// modifier flags seen last so `flagsChanged` can be split into
+    /// individual downs and ups.
+    fn translate(event: &NSEvent, previous: &Cell<NSEventModifierFlags>) -> Vec<KeyEvent> {
+        match event.r#type() {
+            NSEventType::FlagsChanged => {
+                let current = event.modifierFlags();
+                let before = previous.replace(current);
+                MODIFIER_FLAGS
+                    .iter()
+                    .filter_map(|(flag, modifier)| {
+                        let key = Key::Modifier(*modifier);
+                        match (before.contains(*flag), current.contains(*flag)) {
+                            (false, true) => Some(KeyEvent::Down(key)),
+                            (true, false) => Some(KeyEvent::Up(key)),
+                            _ => None,
+                        }
+                    })
+                    .collect()
+            }
+            NSEventType::KeyDown => vec![KeyEvent::Down(Key::Other)],
+            _ => Vec::new(),
+        }
+    }
+
+    /// Install a global monitor (other apps focused) and a local monitor
+    /// (our overlay focused, e.g. while the hold keeps it open). Both stay
+    /// registered for the lifetime of the app and share the last seen flags,
+    /// since a hold usually starts in one and ends in the other.
+    pub fn install_monitors(app: AppHandle, trigger: HoldTriggerHandle) {
+        let mask = NSEventMask::FlagsChanged | NSEventMask::KeyDown;
+        let clock = SystemClock;
+        let previous = Rc::new(Cell::new(NSEventModifierFlags::empty()));
+
+        let global_previous = previous.clone();
+        let global_app = app.clone();
+        let global_trigger = trigger.clone();
+        let global = RcBlock::new(move |event: NonNull<NSEvent>| {
+            let event = unsafe { event.as_ref() };
+            for key_event in translate(event, &global_previous) {
+                if let Some(action) = global_trigger.handle(key_event, clock.now()) {
+                    apply(&global_app, action);
+                }
+            }
+        });
+        match NSEvent::addGlobalMonitorForEventsMatchingMask_handler(mask, &global) {
+            Some(monitor) => std::mem::forget(monitor),
+            None => eprintln!("failed to install global key monitor"),
+        }
+
+        let local = RcBlock::new(move |event: NonNull<NSEvent>| -> *mut NSEvent {
+            let event_ref = unsafe { event.as_ref() };
+            for key_event in translate(event_ref, &previous) {
+                if let Some(action) = trigger.handle(key_event, clock.now()) {
+                    apply(&app, action);
+                }
+            }
+            event.as_ptr()
+        });
+        // SAFETY: the handler returns the event it was given, unmodified.
+        match unsafe { NSEvent::addLocalMonitorForEventsMatchingMask_handler(mask, &local) } {
+            Some(monitor) => std::mem::forget(monitor),
+            None => eprintln!("failed to install local key monitor"),
+        }
+    }
+
+    pub fn spawn_ticker(app: AppHandle, trigger: HoldTriggerHandle) {
+        std::thread::spawn(move || {
+            let clock = SystemClock;
+            loop {
+                std::thread::sleep(TICK_INTERVAL);
+                if let Some(action) = trigger.tick(clock.now()) {
+                    apply(&app, action);
+                }
+            }
+        });
+    }
+}
@@ -1,86 +1,46 @@
+mod appearance;
+mod autostart;
+mod backup;
+mod chord;
+pub mod cli;
+mod clock;
 mod commands;
+mod control;
 mod db;
+mod deep_link;
+mod events;
+mod expiry;
+mod hold_trigger;
+mod hotkeys;
+mod http_api;
+mod i18n;
+mod importers;
+mod key_monitor;
+mod library;
+mod library_file;
+mod linked_files;
+mod locale;
+mod overlay;
+mod settings_schema;
+mod sync;
+mod tray;
+mod tray_menu;
+mod tray_ticker;
 
-use tauri::tray::TrayIconBuilder;
+use appearance::Appearance;
+use commands::autostart::Autostart;
+use commands::hotkeys::{TOGGLE_MAIN_SHORTCUT, TOGGLE_OVERLAY_SHORTCUT};
+use db::Db;
+use hold_trigger::{HoldConfig, HoldTriggerHandle};
+use hotkeys::HotkeyRegistry;
 use tauri::Manager;
-use tauri_plugin_global_shortcut::{Code, GlobalShortcutExt, Modifiers, Shortcut, ShortcutState};
-
-/// Configure the overlay NSWindow so it can appear above fullscreen apps.
-///
-/// Based on KeyClu's approach (reverse-engineered):
-/// 1. collectionBehavior: moveToActiveSpace + stationary + ignoresCycle
-///    + fullScreenAuxiliary + auxiliary
-/// 2. window level: floating (3)
-/// 3. Temporarily switch activation policy to Regular, activate the app,
-///    bring window to front, then switch back to Accessory.
-#[cfg(target_os = "macos")]
-fn configure_overlay_for_fullscreen(window: &tauri::WebviewWindow) {
-    let _ = window.with_webview(|webview| {
-        use objc2::rc::Retained;
-        use objc2::MainThreadMarker;
-        use objc2_app_kit::{
-            NSApplication, NSApplicationActivationPolicy, NSWindow,
-            NSWindowCollectionBehavior,
-        };
-
-        unsafe {
-            let ns_window_ptr = webview.ns_window();
-            let ns_window: Retained<NSWindow> = Retained::retain(ns_window_ptr.cast()).unwrap();
-
-            // floating level (3) — matches KeyClu's primary approach
-            ns_window.setLevel(3);
-
-            // Replicate KeyClu's collectionBehavior:
-            //   moveToActiveSpace (1<<1) | stationary (1<<4) | ignoresCycle (1<<6)
-            //   | fullScreenAuxiliary (1<<8) | auxiliary (1<<17)
-            let behavior = NSWindowCollectionBehavior::MoveToActiveSpace
-                | NSWindowCollectionBehavior::Stationary
-                | NSWindowCollectionBehavior::IgnoresCycle
-                | NSWindowCollectionBehavior::FullScreenAuxiliary
-                | NSWindowCollectionBehavior::Auxiliary;
-            ns_window.setCollectionBehavior(behavior);
-
-            ns_window.setCanHide(false);
-
-            // with_webview callback runs on the main thread
-            let mtm = MainThreadMarker::new().unwrap();
-
-            // Temporarily switch to Regular policy so the app can activate
-            // and steal focus even over a fullscreen app, then revert to Accessory.
-            let app = NSApplication::sharedApplication(mtm);
-            app.setActivationPolicy(NSApplicationActivationPolicy::Regular);
-            #[allow(deprecated)]
-            app.activateIgnoringOtherApps(true);
-
-            ns_window.orderFrontRegardless();
-            ns_window.makeKeyAndOrderFront(None);
-
-            // Switch back to Accessory so the Dock icon stays hidden.
-            app.setActivationPolicy(NSApplicationActivationPolicy::Accessory);
-        }
-    });
-}
-
-/// Deactivate overlay: hide the window and ensure Accessory policy.
-#[cfg(target_os = "macos")]
-fn deactivate_overlay(window: &tauri::WebviewWindow) {
-    let _ = window.hide();
-    let _ = window.with_webview(|_webview| {
-        use objc2::MainThreadMarker;
-        use objc2_app_kit::{NSApplication, NSApplicationActivationPolicy};
-
-        let mtm = MainThreadMarker::new().unwrap();
-        let app = NSApplication::sharedApplication(mtm);
-        app.setActivationPolicy(NSApplicationActivationPolicy::Accessory);
-        app.deactivate();
-    });
-}
+use tauri_plugin_global_shortcut::ShortcutState;
+use tray_ticker::TrayTicker;
 
+/// Set the tray title shown while no items are pinned to the ticker.
 #[tauri::command]
 fn update_tray_title(app: tauri::AppHandle, title: String) {
-    if let Some(tray) = app.tray_by_id("main-tray") {
-        let _ = tray.set_title(Some(&title));
-    }
+    tray_ticker::set_static_title(&app, title);
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -88,40 +48,25 @@ pub fn run() {
     tauri::Builder::default()
         .plugin(
             tauri_plugin_sql::Builder::default()
-                .add_migrations("sqlite:peeky.db", db::migrations())
+                .add_migrations(db::DB_URL, db::migrations())
                 .build(),
         )
         .plugin(tauri_plugin_notification::init())
+        .plugin(tauri_plugin_clipboard_manager::init())
+        .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_deep_link::init())
         .plugin({
             let toggle_overlay =
-                Shortcut::new(Some(Modifiers::CONTROL | Modifiers::ALT), Code::KeyO);
-            let toggle_main = Shortcut::new(Some(Modifiers::CONTROL | Modifiers::ALT), Code::KeyL);
+                hotkeys::to_shortcut(TOGGLE_OVERLAY_SHORTCUT).expect("valid built-in shortcut");
+            let toggle_main =
+                hotkeys::to_shortcut(TOGGLE_MAIN_SHORTCUT).expect("valid built-in shortcut");
             tauri_plugin_global_shortcut::Builder::new()
                 .with_handler(move |app, shortcut, event| {
                     if event.state != ShortcutState::Pressed {
                         return;
                     }
                     if shortcut == &toggle_overlay {
-                        if let Some(w) = app.get_webview_window("overlay") {
-                            if w.is_visible().unwrap_or(false) {
-                                #[cfg(target_os = "macos")]
-                                deactivate_overlay(&w);
-                                #[cfg(not(target_os = "macos"))]
-                                {
-                                    let _ = w.hide();
-                                }
-                            } else {
-                                let _ = w.show();
-                                #[cfg(target_os = "macos")]
-                                configure_overlay_for_fullscreen(&w);
-                                #[cfg(not(target_os = "macos"))]
-                                {
-                                    let _ = w.set_visible_on_all_workspaces(true);
-                                    let _ = w.set_always_on_top(true);
-                                    let _ = w.set_focus();
-                                }
-                            }
-                        }
+                        overlay::toggle(app);
                     } else if shortcut == &toggle_main {
                         if let Some(w) = app.get_webview_window("main") {
                             if w.is_visible().unwrap_or(false) {
@@ -131,6 +76,8 @@ pub fn run() {
                                 let _ = w.set_focus();
                             }
                         }
+                    } else {
+                        hotkeys::dispatch(app, shortcut);
                     }
                 })
                 .build()
@@ -152,50 +99,71 @@ pub fn run() {
 
             let pool = tauri::async_runtime::block_on(db::create_pool(&db_path_str))
                 .expect("failed to create database pool");
-            app.manage(pool);
+            if let Err(err) =
+                tauri::async_runtime::block_on(commands::settings::migrate_settings_by_pool(&pool))
+            {
+                eprintln!("failed to migrate settings: {err}");
+            }
+            let settings =
+                tauri::async_runtime::block_on(commands::settings::get_settings_by_pool(&pool))
+                    .unwrap_or_default();
+            i18n::set_locale(&settings.locale);
+            app.manage(Db::new(db_path, pool));
+            backup::start(app.handle());
 
-            let tray_icon = app.default_window_icon().cloned().unwrap();
-            TrayIconBuilder::with_id("main-tray")
-                .icon(tray_icon)
-                .icon_as_template(true)
-                .tooltip("Peeky")
-                .title("Peeky")
-                .on_tray_icon_event(|tray, event| {
-                    if let tauri::tray::TrayIconEvent::Click {
-                        button: tauri::tray::MouseButton::Left,
-                        button_state: tauri::tray::MouseButtonState::Up,
-                        ..
-                    } = event
-                    {
-                        let app = tray.app_handle();
-                        if let Some(window) = app.get_webview_window("main") {
-                            let _ = window.show();
-                            let _ = window.set_focus();
-                        }
-                    }
-                })
-                .build(app)?;
+            let hold_trigger = HoldTriggerHandle::new(HoldConfig::from_settings(&settings));
+            key_monitor::start(app.handle(), hold_trigger.clone());
+            app.manage(hold_trigger);
 
-            if let Err(err) = app.global_shortcut().register(Shortcut::new(
-                Some(Modifiers::CONTROL | Modifiers::ALT),
-                Code::KeyO,
-            )) {
-                eprintln!("failed to register Ctrl+Option+O: {err}");
-            }
-            if let Err(err) = app.global_shortcut().register(Shortcut::new(
-                Some(Modifiers::CONTROL | Modifiers::ALT),
-                Code::KeyL,
-            )) {
-                eprintln!("failed to register Ctrl+Option+L: {err}");
+            let autostart = Autostart::new(autostart::for_current_user(&app.config().identifier));
+            autostart.reconcile_on_startup(settings.launch_on_login);
+            app.manage(autostart);
+
+            app.manage(Appearance::new(
+                settings.theme,
+                appearance::system_theme(app.handle()),
+            ));
+            appearance::apply_theme(app.handle(), settings.theme);
+
+            hotkeys::register_builtins(app.handle());
+            app.manage(HotkeyRegistry::default());
+            if let Err(err) = tauri::async_runtime::block_on(hotkeys::reload(app.handle())) {
+                eprintln!("failed to load hotkey bindings: {err}");
             }
 
+            tray::create(app.handle())?;
+            app.manage(TrayTicker::new(&settings));
+            tray_ticker::start(app.handle());
+            sync::start(app.handle());
+            http_api::start(app.handle());
+            control::start(app.handle());
+            deep_link::start(app.handle());
+            importers::ssh_config::start(app.handle());
+            linked_files::start(app.handle());
+            expiry::start(app.handle());
+
             Ok(())
         })
+        .on_window_event(|window, event| {
+            if let tauri::WindowEvent::ThemeChanged(theme) = event {
+                appearance::on_window_theme_changed(window.app_handle(), *theme);
+            }
+        })
         .invoke_handler(tauri::generate_handler![
             commands::app::ping,
             commands::app::get_app_info,
+            commands::autostart::get_autostart_status,
+            commands::backup::list_backups,
+            commands::backup::create_backup_now,
+            commands::backup::restore_backup,
             commands::settings::get_settings,
             commands::settings::set_settings,
+            commands::settings::get_settings_schema,
+            commands::settings::reset_settings,
+            commands::settings::get_supported_locales,
+            commands::http_api::get_http_api_token,
+            commands::http_api::regenerate_http_api_token,
+            commands::appearance::get_effective_theme,
             commands::categories::get_categories,
             commands::categories::create_category,
             commands::categories::update_category,
@@ -206,6 +174,34 @@ pub fn run() {
             commands::items::create_item,
             commands::items::update_item,
             commands::items::delete_item,
+            commands::items::set_item_expiry,
+            commands::duplicates::find_duplicate_items,
+            commands::duplicates::merge_items,
+            commands::revisions::get_item_history,
+            commands::revisions::diff_item_revisions,
+            commands::revisions::restore_item_revision,
+            commands::changes::get_changes_since,
+            commands::library::import_snippets,
+            commands::library::export_snippets,
+            commands::library::import_keymap,
+            commands::library::import_cheatsheets,
+            commands::library::import_ssh_hosts,
+            commands::library::list_bookmark_folders,
+            commands::library::import_bookmarks,
+            commands::links::get_category_links,
+            commands::links::link_category,
+            commands::links::unlink_category,
+            commands::links::sync_category_link,
+            commands::hotkeys::get_hotkey_bindings,
+            commands::hotkeys::create_hotkey_binding,
+            commands::hotkeys::delete_hotkey_binding,
+            commands::ticker::get_ticker_status,
+            commands::ticker::set_ticker_items,
+            commands::ticker::pause_ticker,
+            commands::ticker::resume_ticker,
+            commands::sync::sync_now,
+            commands::sync::list_sync_conflicts,
+            commands::sync::dismiss_sync_conflict,
             update_tray_title,
         ])
         .run(tauri::generate_context!())
@@ -0,0 +1,26 @@
+//! Keeps the running app in step with a library that changed behind its
+//! back, such as a restored backup.
+
+use tauri::AppHandle;
+
+use crate::events::{self, LibraryReloaded};
+use crate::hotkeys;
+use crate::linked_files;
+use crate::tray;
+use crate::tray_ticker;
+
+/// Refresh everything derived from the library: hotkeys, ticker, tray menu,
+/// linked-file watchers and open windows.
+pub async fn refresh(app: &AppHandle) {
+    if let Err(err) = hotkeys::reload(app).await {
+        eprintln!("failed to reload hotkey bindings: {err}");
+    }
+    if let Err(err) = tray_ticker::reload(app).await {
+        eprintln!("failed to reload ticker items: {err}");
+    }
+    tray::rebuild(app);
+    if let Err(err) = linked_files::restart(app).await {
+        eprintln!("failed to watch linked files: {err}");
+    }
+    events::emit(app, LibraryReloaded {});
+}
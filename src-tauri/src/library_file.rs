@@ -0,0 +1,583 @@
+//! The library as a file: Markdown for people, JSON for tools.
+//!
+//! In Markdown every heading starts a category and every list item is an
+//! item, `- Label: value`. Values wrapped in backticks are unwrapped, and a
+//! fenced code block right under `- Label` holds a multi-line value:
+//!
+//! ````markdown
+//! # Git
+//!
+//! - Undo last commit: `git reset HEAD~1`
+//! - Amend
+//!   ```
+//!   git add -A
+//!   git commit --amend --no-edit
+//!   ```
+//! ````
+//!
+//! Labels containing `: ` are written as `- **Label**: value`. An item that
+//! is only a link, `- [Grafana](https://grafana.example.com)`, is a link
+//! item.
+
+use serde::{Deserialize, Serialize};
+use sqlx::{SqliteConnection, SqlitePool};
+
+use crate::commands::categories::{
+    create_category_by_conn, get_categories_by_conn, get_categories_by_pool, CreateCategoryInput,
+};
+use crate::commands::items::{
+    create_item_by_conn, delete_item_by_conn, get_items_by_conn, get_items_by_pool,
+    update_item_by_conn, CreateItemInput, ItemKind, UpdateItemInput,
+};
+use crate::commands::library::{ImportMode, ImportSummary};
+use crate::commands::links::Writer;
+use crate::i18n;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LibraryFile {
+    pub categories: Vec<FileCategory>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileCategory {
+    pub name: String,
+    #[serde(default)]
+    pub items: Vec<FileItem>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileItem {
+    pub label: String,
+    #[serde(default)]
+    pub value: String,
+    #[serde(default, skip_serializing_if = "ItemKind::is_text")]
+    pub kind: ItemKind,
+}
+
+fn heading(line: &str) -> Option<&str> {
+    let rest = line.trim_start_matches('#');
+    (rest.len() < line.len() && rest.starts_with(' ')).then(|| rest.trim())
+}
+
+fn list_item(line: &str) -> Option<&str> {
+    let line = line.trim_start();
+    line.strip_prefix("- ")
+        .or_else(|| line.strip_prefix("* "))
+        .map(str::trim)
+}
+
+/// `(label, url)` of a list item that is just a Markdown link.
+fn link_item(text: &str) -> Option<(String, String)> {
+    let (label, url) = text
+        .strip_prefix('[')?
+        .strip_suffix(')')?
+        .split_once("](")?;
+    let plain = |s: &str| !s.is_empty() && !s.contains(['[', ']', '(', ')']);
+    (plain(label) && plain(url) && !url.contains(char::is_whitespace))
+        .then(|| (label.trim().to_string(), url.to_string()))
+}
+
+/// `(label, value)` of a list item's text.
+fn split_item(text: &str) -> (String, String) {
+    let (label, value) = match text
+        .strip_prefix("**")
+        .and_then(|rest| rest.split_once("**"))
+    {
+        Some((label, rest)) => {
+            let rest = rest.trim_start();
+            (label, rest.strip_prefix(':').unwrap_or(rest))
+        }
+        None => match text.split_once(": ") {
+            Some((label, value)) => (label, value),
+            None => (text.strip_suffix(':').unwrap_or(text), ""),
+        },
+    };
+    let value = value.trim();
+    let value = match value.strip_prefix('`').and_then(|v| v.strip_suffix('`')) {
+        Some(inner) if !inner.contains('`') => inner,
+        _ => value,
+    };
+    (label.trim().to_string(), value.to_string())
+}
+
+/// The fenced block starting at `lines[0]`, if there is one: its content,
+/// the number of lines it spans and whether its closing fence was found.
+fn fenced_block(lines: &[&str]) -> Option<(String, usize, bool)> {
+    let first = lines.first()?;
+    let indent = first.len() - first.trim_start().len();
+    let fence_len = first
+        .trim_start()
+        .bytes()
+        .take_while(|b| *b == b'`')
+        .count();
+    if fence_len < 3 {
+        return None;
+    }
+    let mut content = Vec::new();
+    for (i, line) in lines.iter().enumerate().skip(1) {
+        let trimmed = line.trim();
+        if trimmed.len() >= fence_len && trimmed.bytes().all(|b| b == b'`') {
+            return Some((content.join("\n"), i + 1, true));
+        }
+        let strip = line.bytes().take(indent).take_while(|b| *b == b' ').count();
+        content.push(&line[strip..]);
+    }
+    // Unclosed: the rest of the file.
+    Some((content.join("\n"), lines.len(), false))
+}
+
+pub fn parse_markdown(text: &str) -> LibraryFile {
+    parse(text, false).expect("lenient parsing cannot fail")
+}
+
+/// Like [`parse_markdown`], but a code block that is never closed is an
+/// error instead of swallowing the rest of the file. For files that are
+/// re-read on every save, where that is almost always a mistake.
+pub fn parse_markdown_strict(text: &str) -> Result<LibraryFile, String> {
+    parse(text, true)
+}
+
+fn parse(text: &str, strict: bool) -> Result<LibraryFile, String> {
+    let lines: Vec<&str> = text.lines().collect();
+    let mut file = LibraryFile::default();
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i];
+        i += 1;
+        if let Some(name) = heading(line) {
+            file.categories.push(FileCategory {
+                name: name.to_string(),
+                items: Vec::new(),
+            });
+            continue;
+        }
+        let Some(text) = list_item(line) else {
+            continue;
+        };
+        let (label, mut value, kind) = match link_item(text) {
+            Some((label, url)) => (label, url, ItemKind::Link),
+            None => {
+                let (label, value) = split_item(text);
+                (label, value, ItemKind::Text)
+            }
+        };
+        if label.is_empty() {
+            continue;
+        }
+        if value.is_empty() {
+            if let Some((block, used, closed)) = fenced_block(&lines[i..]) {
+                if strict && !closed {
+                    return Err(i18n::tf(
+                        "error.markdown_unclosed_fence",
+                        &[("line", &(i + 1))],
+                    ));
+                }
+                value = block;
+                i += used;
+            }
+        }
+        if file.categories.is_empty() {
+            file.categories.push(FileCategory {
+                name: i18n::t("library.imported_category"),
+                items: Vec::new(),
+            });
+        }
+        let category = file.categories.last_mut().expect("a category was pushed");
+        category.items.push(FileItem { label, value, kind });
+    }
+    Ok(file)
+}
+
+pub fn to_markdown(file: &LibraryFile) -> String {
+    let mut out = String::new();
+    for (index, category) in file.categories.iter().enumerate() {
+        if index > 0 {
+            out.push('\n');
+        }
+        out.push_str(&format!("# {}\n\n", category.name));
+        for item in &category.items {
+            let label = if item.label.contains(": ")
+                || item.label.starts_with("**")
+                || link_item(&item.label).is_some()
+            {
+                format!("**{}**", item.label)
+            } else {
+                item.label.clone()
+            };
+            let value = &item.value;
+            let link = format!("[{}]({value})", item.label);
+            let is_link = link_item(&link) == Some((item.label.clone(), value.clone()));
+            if item.kind == ItemKind::Link && is_link {
+                out.push_str(&format!("- {link}\n"));
+            } else if value.is_empty() {
+                out.push_str(&format!("- {label}\n"));
+            } else if value.contains('\n') || value.contains('`') || value.trim() != value {
+                let longest_run = value.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+                let fence = "`".repeat((longest_run + 1).max(3));
+                out.push_str(&format!("- {label}\n  {fence}\n"));
+                for line in value.lines() {
+                    out.push_str(&format!("  {line}\n"));
+                }
+                out.push_str(&format!("  {fence}\n"));
+            } else {
+                out.push_str(&format!("- {label}: `{value}`\n"));
+            }
+        }
+    }
+    out
+}
+
+/// The whole library in display order.
+pub async fn export_by_pool(db: &SqlitePool) -> Result<LibraryFile, String> {
+    let mut file = LibraryFile::default();
+    for category in get_categories_by_pool(db).await? {
+        let items = get_items_by_pool(db, category.id).await?;
+        file.categories.push(FileCategory {
+            name: category.name,
+            items: items
+                .into_iter()
+                .map(|item| FileItem {
+                    label: item.label,
+                    value: item.value,
+                    kind: item.kind,
+                })
+                .collect(),
+        });
+    }
+    Ok(file)
+}
+
+/// Add the file's items to the library. Categories are matched by name and
+/// created if missing; items already present are skipped, so importing the
+/// same file twice changes nothing. With [`ImportMode::Update`], an item
+/// whose label is already in its category gets the file's value instead of
+/// being added a second time; [`ImportMode::Replace`] also deletes the items
+/// of those categories that the file no longer has. A failure part-way
+/// leaves the library as it was.
+pub async fn import_by_pool(
+    db: &SqlitePool,
+    file: &LibraryFile,
+    mode: ImportMode,
+) -> Result<ImportSummary, String> {
+    let mut tx = db.begin().await.map_err(|e| e.to_string())?;
+    let mut summary = ImportSummary::default();
+    let mut categories = get_categories_by_conn(&mut tx).await?;
+    for incoming in &file.categories {
+        let category = match categories.iter().find(|c| c.name == incoming.name) {
+            Some(category) => category.clone(),
+            None => {
+                let category = create_category_by_conn(
+                    &mut tx,
+                    CreateCategoryInput {
+                        name: incoming.name.clone(),
+                    },
+                )
+                .await?;
+                summary.categories_created += 1;
+                categories.push(category.clone());
+                category
+            }
+        };
+        merge_items_by_conn(
+            &mut tx,
+            category.id,
+            &incoming.items,
+            mode,
+            Writer::User,
+            &mut summary,
+        )
+        .await?;
+    }
+    tx.commit().await.map_err(|e| e.to_string())?;
+    Ok(summary)
+}
+
+/// Bring the items of one category in line with `items`, as
+/// [`import_by_pool`] does for each category of a file. Pass a transaction
+/// to keep a failure from leaving the category half-updated.
+pub async fn merge_items_by_conn(
+    db: &mut SqliteConnection,
+    category_id: i64,
+    items: &[FileItem],
+    mode: ImportMode,
+    writer: Writer,
+    summary: &mut ImportSummary,
+) -> Result<(), String> {
+    let mut existing = get_items_by_conn(db, category_id).await?;
+    // Items already matched by an earlier one from the file, so that
+    // repeated labels each update their own item.
+    let mut matched: Vec<i64> = Vec::new();
+    for item in items {
+        if let Some(same) = existing
+            .iter()
+            .find(|e| e.label == item.label && e.value == item.value && !matched.contains(&e.id))
+        {
+            matched.push(same.id);
+            summary.items_skipped += 1;
+            continue;
+        }
+        if mode != ImportMode::Merge {
+            if let Some(stale) = existing
+                .iter_mut()
+                .find(|e| e.label == item.label && !matched.contains(&e.id))
+            {
+                update_item_by_conn(
+                    db,
+                    UpdateItemInput {
+                        id: stale.id,
+                        label: None,
+                        value: Some(item.value.clone()),
+                        sort_order: None,
+                    },
+                    writer,
+                )
+                .await?;
+                stale.value = item.value.clone();
+                matched.push(stale.id);
+                summary.items_updated += 1;
+                continue;
+            }
+        }
+        let created = create_item_by_conn(
+            db,
+            CreateItemInput {
+                category_id,
+                label: item.label.clone(),
+                value: Some(item.value.clone()),
+                kind: item.kind,
+            },
+            writer,
+        )
+        .await?;
+        matched.push(created.id);
+        existing.push(created);
+        summary.items_created += 1;
+    }
+    if mode == ImportMode::Replace {
+        for gone in existing.iter().filter(|e| !matched.contains(&e.id)) {
+            delete_item_by_conn(db, gone.id, writer).await?;
+            summary.items_deleted += 1;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn setup_db() -> SqlitePool {
+        let db = SqlitePool::connect("sqlite::memory:")
+            .await
+            .expect("connect sqlite memory");
+        sqlx::query(include_str!("../migrations/002_peeky_domain.sql"))
+            .execute(&db)
+            .await
+            .expect("run migration 002");
+        sqlx::query(include_str!("../migrations/009_item_kind.sql"))
+            .execute(&db)
+            .await
+            .expect("run migration 009");
+        sqlx::query(include_str!("../migrations/010_category_links.sql"))
+            .execute(&db)
+            .await
+            .expect("run migration 010");
+        sqlx::query(include_str!("../migrations/012_item_expiry.sql"))
+            .execute(&db)
+            .await
+            .expect("run migration 012");
+        db
+    }
+
+    fn item(label: &str, value: &str) -> FileItem {
+        FileItem {
+            label: label.to_string(),
+            value: value.to_string(),
+            kind: ItemKind::Text,
+        }
+    }
+
+    #[test]
+    fn parses_headings_items_and_fenced_values() {
+        let file = parse_markdown(
+            "Intro text is ignored.\n\
+             \n\
+             # Git\n\
+             \n\
+             - Undo last commit: `git reset HEAD~1`\n\
+             * Status: git status -sb\n\
+             - Amend\n\
+             \x20 ```\n\
+             \x20 git add -A\n\
+             \x20   git commit --amend\n\
+             \x20 ```\n\
+             ## Docker\n\
+             - **Ports: all**: docker ps\n\
+             - Empty:\n",
+        );
+
+        assert_eq!(file.categories.len(), 2);
+        assert_eq!(file.categories[0].name, "Git");
+        assert_eq!(
+            file.categories[0].items,
+            vec![
+                item("Undo last commit", "git reset HEAD~1"),
+                item("Status", "git status -sb"),
+                item("Amend", "git add -A\n  git commit --amend"),
+            ]
+        );
+        assert_eq!(
+            file.categories[1].items,
+            vec![item("Ports: all", "docker ps"), item("Empty", "")]
+        );
+    }
+
+    #[test]
+    fn markdown_round_trips() {
+        let file = LibraryFile {
+            categories: vec![
+                FileCategory {
+                    name: "Shell".to_string(),
+                    items: vec![
+                        item("Find", "rg -n"),
+                        item("Script", "set -e\n\necho ```done```"),
+                        item("Padded", "  indented"),
+                        item("Key: value", "a: b"),
+                        item("Blank", ""),
+                        FileItem {
+                            kind: ItemKind::Link,
+                            ..item("Grafana", "https://grafana.example.com/d/api")
+                        },
+                        item("[Guide](https://example.com)", ""),
+                    ],
+                },
+                FileCategory {
+                    name: "Empty".to_string(),
+                    items: Vec::new(),
+                },
+            ],
+        };
+        assert_eq!(parse_markdown(&to_markdown(&file)), file);
+    }
+
+    #[tokio::test]
+    async fn import_merges_into_existing_categories_once() {
+        let db = setup_db().await;
+        let file = parse_markdown("# Git\n- Status: `git status`\n- Log: `git log`\n");
+
+        let first = import_by_pool(&db, &file, ImportMode::Merge).await.unwrap();
+        assert_eq!((first.categories_created, first.items_created), (1, 2));
+        let again = import_by_pool(&db, &file, ImportMode::Merge).await.unwrap();
+        assert_eq!(
+            again,
+            ImportSummary {
+                categories_created: 0,
+                items_created: 0,
+                items_updated: 0,
+                items_skipped: 2,
+                items_deleted: 0
+            }
+        );
+        assert_eq!(export_by_pool(&db).await.unwrap(), file);
+    }
+
+    #[tokio::test]
+    async fn update_mode_changes_values_in_place() {
+        let db = setup_db().await;
+        let old = parse_markdown("# tar\n- Extract: `tar xf a.tar`\n- List: `tar tf a.tar`\n");
+        import_by_pool(&db, &old, ImportMode::Merge).await.unwrap();
+
+        let new = parse_markdown(
+            "# tar\n- Extract: `tar xvf a.tar`\n- List: `tar tf a.tar`\n- Create: `tar cf a.tar .`\n",
+        );
+        let merged = import_by_pool(&db, &new, ImportMode::Update).await.unwrap();
+        assert_eq!(
+            merged,
+            ImportSummary {
+                categories_created: 0,
+                items_created: 1,
+                items_updated: 1,
+                items_skipped: 1,
+                items_deleted: 0
+            }
+        );
+        assert_eq!(export_by_pool(&db).await.unwrap(), new);
+
+        // Merging instead would have kept the old value next to the new one.
+        let kept = import_by_pool(&db, &old, ImportMode::Merge).await.unwrap();
+        assert_eq!(kept.items_created, 1);
+    }
+
+    #[tokio::test]
+    async fn replace_mode_drops_items_the_file_no_longer_has() {
+        let db = setup_db().await;
+        let hosts = |text: &str| parse_markdown(&format!("# SSH Hosts\n{text}"));
+        import_by_pool(
+            &db,
+            &hosts("- web: `web.example.com`\n- db: `db.example.com`\n"),
+            ImportMode::Replace,
+        )
+        .await
+        .unwrap();
+        import_by_pool(
+            &db,
+            &parse_markdown("# Other\n- keep: `me`\n"),
+            ImportMode::Merge,
+        )
+        .await
+        .unwrap();
+
+        let latest = hosts("- web: `web2.example.com`\n");
+        let summary = import_by_pool(&db, &latest, ImportMode::Replace)
+            .await
+            .unwrap();
+        assert_eq!((summary.items_updated, summary.items_deleted), (1, 1));
+        let library = export_by_pool(&db).await.unwrap();
+        assert_eq!(library.categories[0], latest.categories[0]);
+        // Categories missing from the file are left alone.
+        assert_eq!(library.categories[1].items.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn failed_import_leaves_the_library_as_it_was() {
+        let db = setup_db().await;
+        import_by_pool(
+            &db,
+            &parse_markdown(
+                "# Git
+- Status: `git status`
+",
+            ),
+            ImportMode::Merge,
+        )
+        .await
+        .unwrap();
+        sqlx::query(
+            "CREATE TRIGGER reject_boom BEFORE INSERT ON items WHEN NEW.label = 'Boom'
+             BEGIN SELECT RAISE(ABORT, 'boom'); END",
+        )
+        .execute(&db)
+        .await
+        .unwrap();
+
+        let file = parse_markdown(
+            "# Git
+- Status: `git status -s`
+- Log: `git log`
+# Docker
+- Boom: `x`
+",
+        );
+        assert!(import_by_pool(&db, &file, ImportMode::Update)
+            .await
+            .is_err());
+        assert_eq!(
+            export_by_pool(&db).await.unwrap(),
+            parse_markdown(
+                "# Git
+- Status: `git status`
+"
+            )
+        );
+    }
+}
@@ -0,0 +1,341 @@
+//! Categories linked to a Markdown file, such as a team cheat sheet kept in
+//! a repository.
+//!
+//! A linked category's items are the file's list items, from every section
+//! in file order. The file's folder is watched and every save is diffed
+//! into the items like an import in [`ImportMode::Replace`], then the items
+//! are put in the file's order. A file that cannot be read or parsed leaves
+//! the items alone and records the error on the link, which the main window
+//! shows until the file is fixed.
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use sqlx::{SqliteConnection, SqlitePool};
+use tauri::{AppHandle, Manager};
+use tokio::sync::mpsc::{self, UnboundedReceiver};
+use tokio::sync::Mutex;
+
+use crate::commands::items::{get_items_by_conn, update_item_by_conn, UpdateItemInput};
+use crate::commands::library::{ImportMode, ImportSummary};
+use crate::commands::links::{
+    get_category_link_by_pool, get_category_links_by_pool, set_link_status_by_pool, CategoryLink,
+    Writer,
+};
+use crate::control;
+use crate::db::Db;
+use crate::events::{self, CategoryLinkSynced};
+use crate::i18n;
+use crate::library_file::{self, FileItem};
+
+/// Saves often arrive as several events; wait this long for them to stop.
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// The folders being watched, and the watcher doing it.
+static WATCHER: Mutex<Option<(BTreeSet<PathBuf>, RecommendedWatcher)>> = Mutex::const_new(None);
+
+/// The items of the Markdown file at `path`.
+pub fn read(path: &Path) -> Result<Vec<FileItem>, String> {
+    let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let items: Vec<FileItem> = library_file::parse_markdown_strict(&text)?
+        .categories
+        .into_iter()
+        .flat_map(|category| category.items)
+        .collect();
+    // Most likely a save caught half-way, which must not wipe the category.
+    if items.is_empty() {
+        return Err(i18n::t("error.linked_file_empty"));
+    }
+    Ok(items)
+}
+
+/// Give the category's items the file's order.
+async fn reorder_by_pool(
+    db: &mut SqliteConnection,
+    category_id: i64,
+    items: &[FileItem],
+) -> Result<bool, String> {
+    let mut existing = get_items_by_conn(db, category_id).await?;
+    let mut moved = false;
+    for (index, item) in items.iter().enumerate() {
+        let Some(position) = existing
+            .iter()
+            .position(|e| e.label == item.label && e.value == item.value)
+        else {
+            continue;
+        };
+        let current = existing.swap_remove(position);
+        if current.sort_order != index as i64 {
+            update_item_by_conn(
+                &mut *db,
+                UpdateItemInput {
+                    id: current.id,
+                    label: None,
+                    value: None,
+                    sort_order: Some(index as i64),
+                },
+                Writer::LinkedFile,
+            )
+            .await?;
+            moved = true;
+        }
+    }
+    Ok(moved)
+}
+
+/// Rewrite the linked category's items from its file and record the
+/// outcome on the link. Returns whether any item changed.
+pub async fn sync_by_pool(db: &SqlitePool, link: &CategoryLink) -> Result<bool, String> {
+    let items = match read(Path::new(&link.path)) {
+        Ok(items) => items,
+        Err(err) => {
+            set_link_status_by_pool(db, link.category_id, Some(&err)).await?;
+            return Err(err);
+        }
+    };
+    let mut summary = ImportSummary::default();
+    let mut tx = db.begin().await.map_err(|e| e.to_string())?;
+    library_file::merge_items_by_conn(
+        &mut tx,
+        link.category_id,
+        &items,
+        ImportMode::Replace,
+        Writer::LinkedFile,
+        &mut summary,
+    )
+    .await?;
+    let moved = reorder_by_pool(&mut tx, link.category_id, &items).await?;
+    tx.commit().await.map_err(|e| e.to_string())?;
+    set_link_status_by_pool(db, link.category_id, None).await?;
+    Ok(moved || summary.items_created + summary.items_updated + summary.items_deleted > 0)
+}
+
+/// Sync the category's link and tell the windows how it went.
+pub async fn sync(app: &AppHandle, category_id: i64) -> Result<CategoryLink, String> {
+    let db = app.state::<Db>().pool();
+    let not_linked = || i18n::tf("error.category_not_linked", &[("id", &category_id)]);
+    let link = get_category_link_by_pool(&db, category_id)
+        .await?
+        .ok_or_else(not_linked)?;
+    match sync_by_pool(&db, &link).await {
+        Ok(true) => control::reload_library(app),
+        Ok(false) => {}
+        Err(err) => eprintln!("failed to sync {}: {err}", link.path),
+    }
+    let link = get_category_link_by_pool(&db, category_id)
+        .await?
+        .ok_or_else(not_linked)?;
+    events::emit(app, CategoryLinkSynced { link: link.clone() });
+    Ok(link)
+}
+
+/// Whether an event for `changed` concerns the file at `linked`. Paths from
+/// the OS may name the folder differently, e.g. through a symlink.
+fn is_same_file(changed: &Path, linked: &Path) -> bool {
+    if changed == linked {
+        return true;
+    }
+    let folder = |path: &Path| path.parent().and_then(|dir| dir.canonicalize().ok());
+    changed.file_name() == linked.file_name()
+        && folder(changed).is_some_and(|dir| Some(dir) == folder(linked))
+}
+
+async fn watch(app: AppHandle, mut changes: UnboundedReceiver<PathBuf>) {
+    while let Some(first) = changes.recv().await {
+        let mut changed = BTreeSet::from([first]);
+        while let Ok(Some(path)) = tokio::time::timeout(DEBOUNCE, changes.recv()).await {
+            changed.insert(path);
+        }
+        let links = match get_category_links_by_pool(&app.state::<Db>().pool()).await {
+            Ok(links) => links,
+            Err(err) => {
+                eprintln!("failed to load linked files: {err}");
+                continue;
+            }
+        };
+        for link in links {
+            let linked = Path::new(&link.path);
+            if changed.iter().any(|path| is_same_file(path, linked)) {
+                if let Err(err) = sync(&app, link.category_id).await {
+                    eprintln!("failed to sync {}: {err}", link.path);
+                }
+            }
+        }
+    }
+}
+
+/// Watch the files of the current links, replacing the previous watcher
+/// unless it already watches the right folders.
+pub async fn restart(app: &AppHandle) -> Result<(), String> {
+    let mut watcher = WATCHER.lock().await;
+    let links = get_category_links_by_pool(&app.state::<Db>().pool()).await?;
+    // Editors often save by writing a new file and renaming it over the old
+    // one, which a watch on the file itself would not survive.
+    let folders: BTreeSet<PathBuf> = links
+        .iter()
+        .filter_map(|link| Some(Path::new(&link.path).parent()?.to_path_buf()))
+        .collect();
+    if watcher
+        .as_ref()
+        .is_some_and(|(watched, _)| *watched == folders)
+    {
+        return Ok(());
+    }
+    // Dropping the old watcher also ends its `watch` task.
+    *watcher = None;
+    if folders.is_empty() {
+        return Ok(());
+    }
+
+    let (sender, receiver) = mpsc::unbounded_channel();
+    let mut next = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        for path in event.map(|event| event.paths).unwrap_or_default() {
+            let _ = sender.send(path);
+        }
+    })
+    .map_err(|e| e.to_string())?;
+    for folder in &folders {
+        if let Err(err) = next.watch(folder, RecursiveMode::NonRecursive) {
+            eprintln!("failed to watch {}: {err}", folder.display());
+        }
+    }
+    *watcher = Some((folders, next));
+    tauri::async_runtime::spawn(watch(app.clone(), receiver));
+    Ok(())
+}
+
+/// Catch up with edits made while the app was closed, then watch.
+pub fn start(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let links = get_category_links_by_pool(&app.state::<Db>().pool()).await;
+        for link in links.unwrap_or_default() {
+            if let Err(err) = sync(&app, link.category_id).await {
+                eprintln!("failed to sync {}: {err}", link.path);
+            }
+        }
+        if let Err(err) = restart(&app).await {
+            eprintln!("failed to watch linked files: {err}");
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::categories::{create_category_by_pool, CreateCategoryInput};
+    use crate::commands::items::{
+        create_item_by_pool, get_items_by_pool, CreateItemInput, ItemKind,
+    };
+    use crate::commands::links::{ensure_not_linked_by_pool, link_category_by_pool};
+    use crate::db;
+
+    async fn setup_db(dir: &Path) -> SqlitePool {
+        let pool = db::create_pool(&dir.join("peeky.db").to_string_lossy())
+            .await
+            .expect("create pool");
+        db::migrator().run(&pool).await.expect("run migrations");
+        pool
+    }
+
+    async fn items(db: &SqlitePool, category_id: i64) -> Vec<(String, String)> {
+        get_items_by_pool(db, category_id)
+            .await
+            .expect("list items")
+            .into_iter()
+            .map(|item| (item.label, item.value))
+            .collect()
+    }
+
+    fn pair(label: &str, value: &str) -> (String, String) {
+        (label.to_string(), value.to_string())
+    }
+
+    #[tokio::test]
+    async fn sync_mirrors_the_file_and_keeps_items_on_errors() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let db = setup_db(dir.path()).await;
+        let category = create_category_by_pool(
+            &db,
+            CreateCategoryInput {
+                name: "Oncall".to_string(),
+            },
+        )
+        .await
+        .expect("create category");
+        create_item_by_pool(
+            &db,
+            CreateItemInput {
+                category_id: category.id,
+                label: "Old".to_string(),
+                value: Some("gone soon".to_string()),
+                kind: ItemKind::Text,
+            },
+        )
+        .await
+        .expect("create item");
+
+        let file = dir.path().join("oncall.md");
+        std::fs::write(
+            &file,
+            "# Oncall\n\n- Logs: `kubectl logs -f`\n\n## Paging\n\n- Ack: `pd ack`\n",
+        )
+        .unwrap();
+        let link = link_category_by_pool(&db, category.id, &file.to_string_lossy())
+            .await
+            .expect("link");
+        assert!(sync_by_pool(&db, &link).await.expect("sync"));
+        assert_eq!(
+            items(&db, category.id).await,
+            vec![pair("Logs", "kubectl logs -f"), pair("Ack", "pd ack")]
+        );
+        assert!(ensure_not_linked_by_pool(&db, category.id).await.is_err());
+
+        std::fs::write(&file, "- Ack: `pd ack`\n- Logs: `stern app`\n").unwrap();
+        assert!(sync_by_pool(&db, &link).await.expect("sync"));
+        assert_eq!(
+            items(&db, category.id).await,
+            vec![pair("Ack", "pd ack"), pair("Logs", "stern app")]
+        );
+        assert!(!sync_by_pool(&db, &link).await.expect("sync"));
+
+        std::fs::write(&file, "- Ack\n  ```\n  pd ack\n").unwrap();
+        assert!(sync_by_pool(&db, &link).await.is_err());
+        std::fs::write(&file, "").unwrap();
+        assert!(sync_by_pool(&db, &link).await.is_err());
+        let link = get_category_link_by_pool(&db, category.id)
+            .await
+            .expect("get link")
+            .expect("still linked");
+        assert!(link.error.is_some());
+        assert_eq!(items(&db, category.id).await.len(), 2);
+
+        std::fs::write(&file, "- Ack: `pd ack`\n").unwrap();
+        sync_by_pool(&db, &link).await.expect("sync");
+        let link = get_category_link_by_pool(&db, category.id)
+            .await
+            .expect("get link")
+            .expect("still linked");
+        assert_eq!(link.error, None);
+        assert!(link.synced_at.is_some());
+    }
+
+    #[test]
+    fn matches_event_paths_through_symlinked_folders() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let real = dir.path().join("real");
+        std::fs::create_dir(&real).unwrap();
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(&real, dir.path().join("link")).unwrap();
+
+        assert!(is_same_file(&real.join("a.md"), &real.join("a.md")));
+        assert!(!is_same_file(&real.join("a.md"), &real.join("b.md")));
+        #[cfg(unix)]
+        assert!(is_same_file(
+            &dir.path().join("link").join("a.md"),
+            &real.join("a.md")
+        ));
+    }
+}
@@ -0,0 +1,164 @@
+//! BCP 47 language tags and the locales Peeky ships translations for.
+//!
+//! Only the well-formedness rules of RFC 5646 are checked (subtag lengths
+//! and order), not the IANA registry; the supported-locale list does the
+//! rest.
+
+use crate::i18n;
+
+/// Locales with a translation catalog, as `(tag, native name)`.
+pub const SUPPORTED_LOCALES: &[(&str, &str)] = &[("en-US", "English (US)"), ("zh-CN", "简体中文")];
+
+pub const DEFAULT_LOCALE: &str = "en-US";
+
+fn is_alpha(s: &str) -> bool {
+    s.bytes().all(|b| b.is_ascii_alphabetic())
+}
+
+fn is_digit(s: &str) -> bool {
+    s.bytes().all(|b| b.is_ascii_digit())
+}
+
+fn is_alphanum(s: &str) -> bool {
+    s.bytes().all(|b| b.is_ascii_alphanumeric())
+}
+
+/// Parse a language tag and return it in canonical case (`zh-Hant-TW`).
+pub fn canonicalize(tag: &str) -> Result<String, String> {
+    let invalid = || i18n::tf("error.locale_invalid", &[("tag", &tag)]);
+    let subtags: Vec<&str> = tag.split('-').collect();
+    if subtags
+        .iter()
+        .any(|s| s.is_empty() || s.len() > 8 || !is_alphanum(s))
+    {
+        return Err(invalid());
+    }
+
+    let mut out = Vec::with_capacity(subtags.len());
+    let mut rest = subtags.as_slice();
+
+    // language: 2-3 letters, or 5-8 letters for registered languages.
+    match rest.split_first() {
+        Some((lang, tail)) if is_alpha(lang) && matches!(lang.len(), 2 | 3 | 5..=8) => {
+            out.push(lang.to_ascii_lowercase());
+            rest = tail;
+        }
+        _ => return Err(invalid()),
+    }
+
+    // script: 4 letters.
+    if let Some((script, tail)) = rest.split_first() {
+        if script.len() == 4 && is_alpha(script) {
+            let lower = script.to_ascii_lowercase();
+            out.push(lower[..1].to_ascii_uppercase() + &lower[1..]);
+            rest = tail;
+        }
+    }
+
+    // region: 2 letters or 3 digits.
+    if let Some((region, tail)) = rest.split_first() {
+        if (region.len() == 2 && is_alpha(region)) || (region.len() == 3 && is_digit(region)) {
+            out.push(region.to_ascii_uppercase());
+            rest = tail;
+        }
+    }
+
+    // variants: 5-8 alphanumerics, or a digit followed by 3 alphanumerics.
+    while let Some((variant, tail)) = rest.split_first() {
+        let is_variant = (5..=8).contains(&variant.len())
+            || (variant.len() == 4 && variant.as_bytes()[0].is_ascii_digit());
+        if !is_variant {
+            break;
+        }
+        out.push(variant.to_ascii_lowercase());
+        rest = tail;
+    }
+
+    // extensions (`u-...`) and private use (`x-...`).
+    while let Some((singleton, tail)) = rest.split_first() {
+        if singleton.len() != 1 {
+            return Err(invalid());
+        }
+        let private = singleton.eq_ignore_ascii_case("x");
+        // Extension subtags run until the next singleton; private use runs
+        // to the end of the tag.
+        let count = tail.iter().take_while(|s| private || s.len() > 1).count();
+        if count == 0 {
+            return Err(invalid());
+        }
+        out.push(singleton.to_ascii_lowercase());
+        out.extend(tail[..count].iter().map(|s| s.to_ascii_lowercase()));
+        rest = &tail[count..];
+        if private && !rest.is_empty() {
+            return Err(invalid());
+        }
+    }
+
+    Ok(out.join("-"))
+}
+
+/// Validator for the `locale` setting: a well-formed tag, spelled in
+/// canonical case, that Peeky has a translation for.
+pub fn validate_setting(tag: &str) -> Result<(), String> {
+    let canonical = canonicalize(tag)?;
+    match SUPPORTED_LOCALES
+        .iter()
+        .find(|(supported, _)| *supported == canonical)
+    {
+        Some(_) if canonical == tag => Ok(()),
+        Some(_) => Err(i18n::tf("error.locale_case", &[("canonical", &canonical)])),
+        None => {
+            let supported: Vec<&str> = SUPPORTED_LOCALES.iter().map(|(tag, _)| *tag).collect();
+            Err(i18n::tf(
+                "error.locale_unsupported",
+                &[("tag", &tag), ("supported", &supported.join(", "))],
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn canonicalizes_well_formed_tags() {
+        assert_eq!(canonicalize("en-us").unwrap(), "en-US");
+        assert_eq!(canonicalize("ZH-hant-tw").unwrap(), "zh-Hant-TW");
+        assert_eq!(canonicalize("es-419").unwrap(), "es-419");
+        assert_eq!(canonicalize("de-DE-1996").unwrap(), "de-DE-1996");
+        assert_eq!(
+            canonicalize("en-US-u-ca-gregory-x-Peeky").unwrap(),
+            "en-US-u-ca-gregory-x-peeky"
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_tags() {
+        for tag in [
+            "",
+            "e",
+            "en_US",
+            "en--US",
+            "english-US-extra-long",
+            "en-US-u",
+            "1en",
+            "en-x",
+        ] {
+            assert!(canonicalize(tag).is_err(), "{tag} should be rejected");
+        }
+    }
+
+    #[test]
+    fn setting_accepts_only_supported_canonical_locales() {
+        for (tag, _) in SUPPORTED_LOCALES {
+            validate_setting(tag).unwrap();
+        }
+        assert!(validate_setting(DEFAULT_LOCALE).is_ok());
+        assert!(validate_setting("zh-cn").unwrap_err().contains("zh-CN"));
+        assert!(validate_setting("fr-FR")
+            .unwrap_err()
+            .contains("Unsupported"));
+        assert!(validate_setting("not a tag").is_err());
+    }
+}
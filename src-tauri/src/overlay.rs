@@ -0,0 +1,122 @@
+use tauri::{AppHandle, Manager};
+
+use crate::events::{self, OverlayFilter};
+
+/// Configure the overlay NSWindow so it can appear above fullscreen apps.
+///
+/// Based on KeyClu's approach (reverse-engineered):
+/// 1. collectionBehavior: moveToActiveSpace + stationary + ignoresCycle
+///    + fullScreenAuxiliary + auxiliary
+/// 2. window level: floating (3)
+/// 3. Temporarily switch activation policy to Regular, activate the app,
+///    bring window to front, then switch back to Accessory.
+#[cfg(target_os = "macos")]
+fn configure_overlay_for_fullscreen(window: &tauri::WebviewWindow) {
+    let _ = window.with_webview(|webview| {
+        use objc2::rc::Retained;
+        use objc2::MainThreadMarker;
+        use objc2_app_kit::{
+            NSApplication, NSApplicationActivationPolicy, NSWindow, NSWindowCollectionBehavior,
+        };
+
+        unsafe {
+            let ns_window_ptr = webview.ns_window();
+            let ns_window: Retained<NSWindow> = Retained::retain(ns_window_ptr.cast()).unwrap();
+
+            // floating level (3) — matches KeyClu's primary approach
+            ns_window.setLevel(3);
+
+            // Replicate KeyClu's collectionBehavior:
+            //   moveToActiveSpace (1<<1) | stationary (1<<4) | ignoresCycle (1<<6)
+            //   | fullScreenAuxiliary (1<<8) | auxiliary (1<<17)
+            let behavior = NSWindowCollectionBehavior::MoveToActiveSpace
+                | NSWindowCollectionBehavior::Stationary
+                | NSWindowCollectionBehavior::IgnoresCycle
+                | NSWindowCollectionBehavior::FullScreenAuxiliary
+                | NSWindowCollectionBehavior::Auxiliary;
+            ns_window.setCollectionBehavior(behavior);
+
+            ns_window.setCanHide(false);
+
+            // with_webview callback runs on the main thread
+            let mtm = MainThreadMarker::new().unwrap();
+
+            // Temporarily switch to Regular policy so the app can activate
+            // and steal focus even over a fullscreen app, then revert to Accessory.
+            let app = NSApplication::sharedApplication(mtm);
+            app.setActivationPolicy(NSApplicationActivationPolicy::Regular);
+            #[allow(deprecated)]
+            app.activateIgnoringOtherApps(true);
+
+            ns_window.orderFrontRegardless();
+            ns_window.makeKeyAndOrderFront(None);
+
+            // Switch back to Accessory so the Dock icon stays hidden.
+            app.setActivationPolicy(NSApplicationActivationPolicy::Accessory);
+        }
+    });
+}
+
+/// Deactivate overlay: hide the window and ensure Accessory policy.
+#[cfg(target_os = "macos")]
+fn deactivate_overlay(window: &tauri::WebviewWindow) {
+    let _ = window.hide();
+    let _ = window.with_webview(|_webview| {
+        use objc2::MainThreadMarker;
+        use objc2_app_kit::{NSApplication, NSApplicationActivationPolicy};
+
+        let mtm = MainThreadMarker::new().unwrap();
+        let app = NSApplication::sharedApplication(mtm);
+        app.setActivationPolicy(NSApplicationActivationPolicy::Accessory);
+        app.deactivate();
+    });
+}
+
+/// Show the overlay window on top of everything, including fullscreen apps.
+pub fn show(app: &AppHandle) {
+    show_category(app, None);
+}
+
+/// Show the overlay limited to a single category (or all when `None`).
+pub fn show_category(app: &AppHandle, category_id: Option<i64>) {
+    let Some(w) = app.get_webview_window("overlay") else {
+        return;
+    };
+    events::emit_to(app, "overlay", OverlayFilter { category_id });
+    let _ = w.show();
+    #[cfg(target_os = "macos")]
+    configure_overlay_for_fullscreen(&w);
+    #[cfg(not(target_os = "macos"))]
+    {
+        let _ = w.set_visible_on_all_workspaces(true);
+        let _ = w.set_always_on_top(true);
+        let _ = w.set_focus();
+    }
+}
+
+/// Hide the overlay window and hand focus back to the previous app.
+pub fn hide(app: &AppHandle) {
+    let Some(w) = app.get_webview_window("overlay") else {
+        return;
+    };
+    #[cfg(target_os = "macos")]
+    deactivate_overlay(&w);
+    #[cfg(not(target_os = "macos"))]
+    {
+        let _ = w.hide();
+    }
+}
+
+pub fn is_visible(app: &AppHandle) -> bool {
+    app.get_webview_window("overlay")
+        .and_then(|w| w.is_visible().ok())
+        .unwrap_or(false)
+}
+
+pub fn toggle(app: &AppHandle) {
+    if is_visible(app) {
+        hide(app);
+    } else {
+        show(app);
+    }
+}
@@ -0,0 +1,322 @@
+//! Schema for the key/value settings store.
+//!
+//! Each setting is declared once in [`SCHEMA`] with its type, default and
+//! validation; adding an option means adding an entry here and a field on
+//! `AppSettings`. Stored values are JSON. When the shape of stored settings
+//! changes (a key is renamed, a value re-encoded), append a step to
+//! [`MIGRATIONS`]; the version reached is recorded in `settings_meta`.
+
+use serde_json::{Map, Value};
+
+use crate::i18n;
+use crate::locale;
+use crate::sync;
+
+/// Extra check for a text setting beyond being a string.
+pub type TextValidator = fn(&str) -> Result<(), String>;
+
+#[derive(Debug, Clone, Copy)]
+pub enum SettingType {
+    Bool {
+        default: bool,
+    },
+    Integer {
+        default: i64,
+        min: i64,
+        max: i64,
+    },
+    Choice {
+        default: &'static str,
+        choices: &'static [&'static str],
+    },
+    Text {
+        default: &'static str,
+        validate: Option<TextValidator>,
+    },
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct SettingDef {
+    pub key: &'static str,
+    pub ty: SettingType,
+}
+
+pub const SCHEMA: &[SettingDef] = &[
+    SettingDef {
+        key: "locale",
+        ty: SettingType::Text {
+            default: locale::DEFAULT_LOCALE,
+            validate: Some(locale::validate_setting),
+        },
+    },
+    SettingDef {
+        key: "launch_on_login",
+        ty: SettingType::Bool { default: false },
+    },
+    SettingDef {
+        key: "theme",
+        ty: SettingType::Choice {
+            default: "system",
+            choices: &["system", "light", "dark"],
+        },
+    },
+    SettingDef {
+        key: "hold_modifier",
+        ty: SettingType::Choice {
+            default: "command",
+            choices: &["none", "command", "option", "control", "shift"],
+        },
+    },
+    SettingDef {
+        key: "hold_duration_ms",
+        ty: SettingType::Integer {
+            default: 1000,
+            min: 200,
+            max: 5000,
+        },
+    },
+    SettingDef {
+        key: "hold_hide_on_release",
+        ty: SettingType::Bool { default: true },
+    },
+    SettingDef {
+        key: "ticker_interval_secs",
+        ty: SettingType::Integer {
+            default: 10,
+            min: 2,
+            max: 3600,
+        },
+    },
+    SettingDef {
+        key: "ticker_max_chars",
+        ty: SettingType::Integer {
+            default: 24,
+            min: 8,
+            max: 100,
+        },
+    },
+    SettingDef {
+        key: "backup_interval_hours",
+        ty: SettingType::Integer {
+            default: 24,
+            min: 1,
+            max: 168,
+        },
+    },
+    SettingDef {
+        key: "backup_retention",
+        ty: SettingType::Integer {
+            default: 7,
+            min: 1,
+            max: 100,
+        },
+    },
+    SettingDef {
+        key: "sync_folder",
+        ty: SettingType::Text {
+            default: "",
+            validate: Some(sync::validate_folder_setting),
+        },
+    },
+    SettingDef {
+        key: "sync_interval_minutes",
+        ty: SettingType::Integer {
+            default: 5,
+            min: 1,
+            max: 1440,
+        },
+    },
+    SettingDef {
+        key: "http_api_enabled",
+        ty: SettingType::Bool { default: false },
+    },
+    SettingDef {
+        key: "http_api_port",
+        ty: SettingType::Integer {
+            default: 17_890,
+            min: 1024,
+            max: 65_535,
+        },
+    },
+    SettingDef {
+        key: "ssh_hosts_sync",
+        ty: SettingType::Bool { default: false },
+    },
+    // The default is repeated in migration 010's trigger.
+    SettingDef {
+        key: "item_revision_limit",
+        ty: SettingType::Integer {
+            default: 20,
+            min: 1,
+            max: 1000,
+        },
+    },
+    SettingDef {
+        key: "expired_items",
+        ty: SettingType::Choice {
+            default: "delete",
+            choices: &["delete", "move"],
+        },
+    },
+];
+
+pub fn find(key: &str) -> Option<&'static SettingDef> {
+    SCHEMA.iter().find(|def| def.key == key)
+}
+
+impl SettingDef {
+    pub fn default_value(&self) -> Value {
+        match self.ty {
+            SettingType::Bool { default } => Value::from(default),
+            SettingType::Integer { default, .. } => Value::from(default),
+            SettingType::Choice { default, .. } | SettingType::Text { default, .. } => {
+                Value::from(default)
+            }
+        }
+    }
+
+    pub fn validate(&self, value: &Value) -> Result<(), String> {
+        let key = self.key;
+        match (self.ty, value) {
+            (SettingType::Bool { .. }, Value::Bool(_)) => Ok(()),
+            (SettingType::Integer { min, max, .. }, Value::Number(n)) => match n.as_i64() {
+                Some(n) if (min..=max).contains(&n) => Ok(()),
+                _ => Err(i18n::tf(
+                    "error.setting_range",
+                    &[("key", &key), ("min", &min), ("max", &max)],
+                )),
+            },
+            (SettingType::Choice { choices, .. }, Value::String(s)) => {
+                if choices.contains(&s.as_str()) {
+                    Ok(())
+                } else {
+                    Err(i18n::tf(
+                        "error.setting_choice",
+                        &[("key", &key), ("choices", &choices.join(", "))],
+                    ))
+                }
+            }
+            (SettingType::Text { validate, .. }, Value::String(s)) => match validate {
+                Some(validate) => validate(s),
+                None => Ok(()),
+            },
+            (SettingType::Bool { .. }, _) => Err(i18n::tf("error.setting_bool", &[("key", &key)])),
+            (SettingType::Integer { .. }, _) => {
+                Err(i18n::tf("error.setting_integer", &[("key", &key)]))
+            }
+            (SettingType::Choice { .. } | SettingType::Text { .. }, _) => {
+                Err(i18n::tf("error.setting_string", &[("key", &key)]))
+            }
+        }
+    }
+}
+
+/// Every setting at its default value.
+pub fn defaults() -> Map<String, Value> {
+    SCHEMA
+        .iter()
+        .map(|def| (def.key.to_string(), def.default_value()))
+        .collect()
+}
+
+/// Upgrades stored settings by one version, in place.
+pub type Migration = fn(&mut Map<String, Value>);
+
+/// `MIGRATIONS[i]` upgrades stored settings from version `i + 1` to `i + 2`.
+pub const MIGRATIONS: &[Migration] = &[];
+
+/// Run every step after `from` and return the resulting version. Settings
+/// written by a newer build are left untouched.
+pub fn migrate(settings: &mut Map<String, Value>, from: i64, migrations: &[Migration]) -> i64 {
+    let mut version = from;
+    while let Some(step) = usize::try_from(version - 1)
+        .ok()
+        .and_then(|index| migrations.get(index))
+    {
+        step(settings);
+        version += 1;
+    }
+    version
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::commands::appearance::Theme;
+    use crate::commands::settings::AppSettings;
+    use crate::hold_trigger;
+
+    #[test]
+    fn schema_matches_app_settings() {
+        let fields = match serde_json::to_value(AppSettings::default()).unwrap() {
+            Value::Object(fields) => fields,
+            other => panic!("unexpected settings shape: {other}"),
+        };
+        assert_eq!(fields, defaults());
+        for def in SCHEMA {
+            def.validate(&def.default_value()).unwrap();
+        }
+    }
+
+    #[test]
+    fn validates_type_and_range() {
+        let duration = find("hold_duration_ms").unwrap();
+        assert!(duration.validate(&json!(600)).is_ok());
+        assert!(duration.validate(&json!(50)).is_err());
+        assert!(duration.validate(&json!("600")).is_err());
+        assert!(duration.validate(&json!(600.5)).is_err());
+
+        let modifier = find("hold_modifier").unwrap();
+        assert!(modifier.validate(&json!("hyper")).is_err());
+        let SettingType::Choice { choices, .. } = modifier.ty else {
+            panic!("hold_modifier should be a choice");
+        };
+        for choice in choices {
+            hold_trigger::parse_modifier_setting(choice).unwrap();
+        }
+
+        let theme = find("theme").unwrap();
+        let SettingType::Choice { choices, .. } = theme.ty else {
+            panic!("theme should be a choice");
+        };
+        let names: Vec<Value> = [Theme::System, Theme::Light, Theme::Dark]
+            .into_iter()
+            .map(|theme| serde_json::to_value(theme).unwrap())
+            .collect();
+        assert_eq!(choices.iter().map(|c| json!(c)).collect::<Vec<_>>(), names);
+
+        assert!(find("locale").unwrap().validate(&json!("fr-FR")).is_err());
+        assert!(find("launch_on_login")
+            .unwrap()
+            .validate(&json!(1))
+            .is_err());
+        assert!(find("font_size").is_none());
+    }
+
+    #[test]
+    fn migrate_runs_pending_steps_in_order() {
+        fn rename_theme(settings: &mut Map<String, Value>) {
+            if let Some(theme) = settings.remove("color_scheme") {
+                settings.insert("theme".to_string(), theme);
+            }
+        }
+        fn darken(settings: &mut Map<String, Value>) {
+            if settings.get("theme") == Some(&json!("night")) {
+                settings.insert("theme".to_string(), json!("dark"));
+            }
+        }
+        let steps: &[Migration] = &[rename_theme, darken];
+
+        let mut settings = Map::new();
+        settings.insert("color_scheme".to_string(), json!("night"));
+        assert_eq!(migrate(&mut settings, 1, steps), 3);
+        assert_eq!(settings.get("theme"), Some(&json!("dark")));
+        assert!(!settings.contains_key("color_scheme"));
+
+        // Already current, or from a newer build: nothing to do.
+        assert_eq!(migrate(&mut settings, 3, steps), 3);
+        assert_eq!(migrate(&mut settings, 7, steps), 7);
+    }
+}
@@ -0,0 +1,1029 @@
+//! Two-way sync of the library through a shared folder.
+//!
+//! Every category and item gets a `sync_id` and is mirrored as one JSON file
+//! per entity (`<folder>/items/<sync_id>.json`); deletions are written as
+//! tombstones. Any tool that copies files between machines (Syncthing, a
+//! cloud drive) can then carry the library between devices.
+//!
+//! Each run compares three versions of every entity: the one in the
+//! database, the one in the folder and the one both agreed on at the last
+//! run (`sync_base`). [`resolve`] keeps whichever side changed; when both
+//! did, fields changed on one side only are merged and fields changed on
+//! both go to the newer edit by `(updated_at, device_id)`, with the losing
+//! version recorded in `sync_conflicts`.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sqlx::{Sqlite, SqlitePool, Transaction};
+use tauri::{AppHandle, Manager};
+use tokio::sync::Mutex;
+
+use crate::commands::items::ItemKind;
+use crate::commands::settings::get_settings_by_pool;
+use crate::commands::sync::{SyncEntity, SyncReport, SyncSide};
+use crate::db::Db;
+use crate::events::{self, SyncCompleted};
+use crate::i18n;
+use crate::library;
+
+/// Serializes runs started by the scheduler and by `sync_now`.
+static RUNNING: Mutex<()> = Mutex::const_new(());
+
+/// Setting validator: empty (sync off) or an absolute path.
+pub fn validate_folder_setting(value: &str) -> Result<(), String> {
+    if value.is_empty() || Path::new(value).is_absolute() {
+        Ok(())
+    } else {
+        Err(i18n::t("error.sync_folder_relative"))
+    }
+}
+
+/// One entity as exchanged through the folder.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Record {
+    pub sync_id: String,
+    /// When the entity was last edited, as `YYYY-MM-DD HH:MM:SS` UTC.
+    pub updated_at: String,
+    /// Device that made that edit.
+    pub device_id: String,
+    #[serde(default)]
+    pub deleted: bool,
+    #[serde(default)]
+    pub fields: Map<String, Value>,
+}
+
+impl Record {
+    fn same_content(&self, other: &Record) -> bool {
+        self.deleted == other.deleted && self.fields == other.fields
+    }
+
+    fn is_newer_than(&self, other: &Record) -> bool {
+        (&self.updated_at, &self.device_id) > (&other.updated_at, &other.device_id)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Resolution {
+    pub record: Record,
+    /// Set when both sides edited the same data; the side whose edit won.
+    pub conflict: Option<SyncSide>,
+}
+
+/// Decide the synced version of an entity from its last synced version and
+/// the local and remote ones. `None` if it exists nowhere.
+pub fn resolve(
+    base: Option<&Record>,
+    local: Option<&Record>,
+    remote: Option<&Record>,
+) -> Option<Resolution> {
+    let (local, remote) = match (local, remote) {
+        (None, None) => return None,
+        (Some(only), None) | (None, Some(only)) => {
+            return Some(Resolution {
+                record: only.clone(),
+                conflict: None,
+            })
+        }
+        (Some(local), Some(remote)) => (local, remote),
+    };
+    let keep = |record: &Record| {
+        Some(Resolution {
+            record: record.clone(),
+            conflict: None,
+        })
+    };
+    let (newer, newer_side) = if remote.is_newer_than(local) {
+        (remote, SyncSide::Remote)
+    } else {
+        (local, SyncSide::Local)
+    };
+    if local.same_content(remote) {
+        return keep(newer);
+    }
+    let changed = |record: &Record| !base.is_some_and(|base| base.same_content(record));
+    match (changed(local), changed(remote)) {
+        (true, false) => return keep(local),
+        (false, true) => return keep(remote),
+        _ => {}
+    }
+
+    // An edit beats a concurrent delete.
+    if local.deleted || remote.deleted {
+        let (edited, side) = if local.deleted {
+            (remote, SyncSide::Remote)
+        } else {
+            (local, SyncSide::Local)
+        };
+        return Some(Resolution {
+            record: edited.clone(),
+            conflict: Some(side),
+        });
+    }
+
+    let empty = Map::new();
+    let base_fields = base
+        .filter(|base| !base.deleted)
+        .map_or(&empty, |b| &b.fields);
+    let keys: BTreeSet<&String> = local
+        .fields
+        .keys()
+        .chain(remote.fields.keys())
+        .chain(base_fields.keys())
+        .collect();
+    let mut fields = Map::new();
+    let mut conflicted = false;
+    for key in keys {
+        let (l, r, b) = (
+            local.fields.get(key),
+            remote.fields.get(key),
+            base_fields.get(key),
+        );
+        let value = if l == r || r == b {
+            l
+        } else if l == b {
+            r
+        } else {
+            conflicted = true;
+            newer.fields.get(key)
+        };
+        if let Some(value) = value {
+            fields.insert(key.clone(), value.clone());
+        }
+    }
+    Some(Resolution {
+        record: Record {
+            sync_id: local.sync_id.clone(),
+            updated_at: newer.updated_at.clone(),
+            device_id: newer.device_id.clone(),
+            deleted: false,
+            fields,
+        },
+        conflict: conflicted.then_some(newer_side),
+    })
+}
+
+/// Table of an entity, which is also its directory in the folder.
+fn table(entity: SyncEntity) -> &'static str {
+    match entity {
+        SyncEntity::Category => "categories",
+        SyncEntity::Item => "items",
+    }
+}
+
+/// Sync ids become file names, so anything else in the folder is ignored.
+fn is_valid_sync_id(id: &str) -> bool {
+    (1..=64).contains(&id.len())
+        && id
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_')
+}
+
+fn read_folder(dir: &Path) -> Result<BTreeMap<String, Record>, String> {
+    let mut records = BTreeMap::new();
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(records),
+        Err(err) => return Err(err.to_string()),
+    };
+    for entry in entries {
+        let path = entry.map_err(|e| e.to_string())?.path();
+        let Some(stem) = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_suffix(".json"))
+        else {
+            continue;
+        };
+        if !is_valid_sync_id(stem) {
+            continue;
+        }
+        // A file another device is still writing is picked up next time.
+        let Ok(text) = fs::read_to_string(&path) else {
+            continue;
+        };
+        match serde_json::from_str::<Record>(&text) {
+            Ok(record) if record.sync_id == stem => {
+                records.insert(record.sync_id.clone(), record);
+            }
+            _ => eprintln!("skipping unreadable sync file {}", path.display()),
+        }
+    }
+    Ok(records)
+}
+
+fn record_path(dir: &Path, sync_id: &str) -> PathBuf {
+    dir.join(format!("{sync_id}.json"))
+}
+
+/// Write the record to a temporary file next to its place and return the
+/// temporary's path. Renaming it into place once the database has committed
+/// means other devices never see half a record, nor one this device then
+/// failed to keep.
+fn stage_record(dir: &Path, record: &Record) -> Result<PathBuf, String> {
+    fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    let json = serde_json::to_string_pretty(record).map_err(|e| e.to_string())?;
+    let temp = dir.join(format!(".{}.json.tmp", record.sync_id));
+    fs::write(&temp, json).map_err(|e| e.to_string())?;
+    Ok(temp)
+}
+
+async fn device_id(tx: &mut Transaction<'_, Sqlite>) -> Result<String, String> {
+    sqlx::query_scalar("SELECT device_id FROM sync_device WHERE id = 1")
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+async fn load_base(
+    tx: &mut Transaction<'_, Sqlite>,
+    entity: SyncEntity,
+) -> Result<BTreeMap<String, Record>, String> {
+    let rows: Vec<(String,)> = sqlx::query_as("SELECT record FROM sync_base WHERE entity = ?")
+        .bind(entity.as_str())
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(rows
+        .into_iter()
+        .filter_map(|(json,)| serde_json::from_str::<Record>(&json).ok())
+        .map(|record| (record.sync_id.clone(), record))
+        .collect())
+}
+
+type ItemRow = (
+    String,
+    String,
+    String,
+    String,
+    String,
+    i64,
+    ItemKind,
+    Option<String>,
+);
+
+/// Rows as `(sync_id, updated_at, fields)`.
+async fn load_rows(
+    tx: &mut Transaction<'_, Sqlite>,
+    entity: SyncEntity,
+) -> Result<Vec<(String, String, Map<String, Value>)>, String> {
+    let rows = match entity {
+        SyncEntity::Category => sqlx::query_as::<_, (String, String, String, i64)>(
+            "SELECT sync_id, updated_at, name, sort_order FROM categories",
+        )
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|(sync_id, updated_at, name, sort_order)| {
+            let mut fields = Map::new();
+            fields.insert("name".into(), name.into());
+            fields.insert("sort_order".into(), sort_order.into());
+            (sync_id, updated_at, fields)
+        })
+        .collect(),
+        SyncEntity::Item => sqlx::query_as::<_, ItemRow>(
+            "SELECT i.sync_id, i.updated_at, c.sync_id, i.label, i.value, i.sort_order, i.kind,
+                    i.expires_at
+                 FROM items i JOIN categories c ON c.id = i.category_id",
+        )
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(
+            |(sync_id, updated_at, category, label, value, sort_order, kind, expires_at)| {
+                let mut fields = Map::new();
+                fields.insert("category".into(), category.into());
+                fields.insert("label".into(), label.into());
+                fields.insert("value".into(), value.into());
+                fields.insert("sort_order".into(), sort_order.into());
+                // Only links carry a kind, so text items keep their records.
+                if kind == ItemKind::Link {
+                    fields.insert("kind".into(), "link".into());
+                }
+                if let Some(expires_at) = expires_at {
+                    fields.insert("expires_at".into(), expires_at.into());
+                }
+                (sync_id, updated_at, fields)
+            },
+        )
+        .collect(),
+    };
+    Ok(rows)
+}
+
+/// The database's version of every entity. Unchanged rows keep the
+/// metadata of the last sync; entities deleted since become tombstones.
+async fn load_local(
+    tx: &mut Transaction<'_, Sqlite>,
+    entity: SyncEntity,
+    base: &BTreeMap<String, Record>,
+    device_id: &str,
+    now: &str,
+) -> Result<BTreeMap<String, Record>, String> {
+    let mut records = BTreeMap::new();
+    for (sync_id, updated_at, fields) in load_rows(tx, entity).await? {
+        let record = match base.get(&sync_id) {
+            Some(base) if !base.deleted && base.fields == fields => base.clone(),
+            _ => Record {
+                sync_id: sync_id.clone(),
+                updated_at,
+                device_id: device_id.to_string(),
+                deleted: false,
+                fields,
+            },
+        };
+        records.insert(sync_id, record);
+    }
+    for (sync_id, base) in base {
+        if records.contains_key(sync_id) {
+            continue;
+        }
+        let tombstone = if base.deleted {
+            base.clone()
+        } else {
+            Record {
+                sync_id: sync_id.clone(),
+                updated_at: now.to_string(),
+                device_id: device_id.to_string(),
+                deleted: true,
+                fields: Map::new(),
+            }
+        };
+        records.insert(sync_id.clone(), tombstone);
+    }
+    Ok(records)
+}
+
+/// Sync ids of the categories holding an item edited here since the last
+/// sync, which a remote delete of the category must not take with it.
+async fn categories_with_local_edits(
+    tx: &mut Transaction<'_, Sqlite>,
+    device_id: &str,
+    now: &str,
+) -> Result<BTreeSet<String>, String> {
+    let base = load_base(tx, SyncEntity::Item).await?;
+    let local = load_local(tx, SyncEntity::Item, &base, device_id, now).await?;
+    Ok(local
+        .values()
+        .filter(|record| {
+            !record.deleted
+                && !base
+                    .get(&record.sync_id)
+                    .is_some_and(|b| b.same_content(record))
+        })
+        .map(|record| text_field(record, "category").to_string())
+        .collect())
+}
+
+fn text_field<'a>(record: &'a Record, key: &str) -> &'a str {
+    record.fields.get(key).and_then(Value::as_str).unwrap_or("")
+}
+
+fn int_field(record: &Record, key: &str) -> i64 {
+    record.fields.get(key).and_then(Value::as_i64).unwrap_or(0)
+}
+
+/// Why [`apply`] left a record unwritten.
+enum Skip {
+    /// The item's category is not known here.
+    UnknownCategory,
+    /// The item is in a category linked to a file, which only that file's
+    /// sync writes.
+    LinkedCategory,
+}
+
+async fn is_linked(tx: &mut Transaction<'_, Sqlite>, category_id: i64) -> Result<bool, String> {
+    sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM category_links WHERE category_id = ?)")
+        .bind(category_id)
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Make the database match `record`, or say why nothing was written.
+async fn apply(
+    tx: &mut Transaction<'_, Sqlite>,
+    entity: SyncEntity,
+    record: &Record,
+) -> Result<Option<Skip>, String> {
+    if entity == SyncEntity::Item {
+        let current: Option<i64> =
+            sqlx::query_scalar("SELECT category_id FROM items WHERE sync_id = ?")
+                .bind(&record.sync_id)
+                .fetch_optional(&mut **tx)
+                .await
+                .map_err(|e| e.to_string())?;
+        if let Some(current) = current {
+            if is_linked(tx, current).await? {
+                return Ok(Some(Skip::LinkedCategory));
+            }
+        }
+    }
+    if record.deleted {
+        sqlx::query(&format!("DELETE FROM {} WHERE sync_id = ?", table(entity)))
+            .bind(&record.sync_id)
+            .execute(&mut **tx)
+            .await
+            .map_err(|e| e.to_string())?;
+        return Ok(None);
+    }
+    match entity {
+        SyncEntity::Category => {
+            let name = text_field(record, "name");
+            let sort_order = int_field(record, "sort_order");
+            let result = sqlx::query(
+                "UPDATE categories SET name = ?, sort_order = ?, updated_at = ? WHERE sync_id = ?",
+            )
+            .bind(name)
+            .bind(sort_order)
+            .bind(&record.updated_at)
+            .bind(&record.sync_id)
+            .execute(&mut **tx)
+            .await
+            .map_err(|e| e.to_string())?;
+            if result.rows_affected() == 0 {
+                sqlx::query(
+                    "INSERT INTO categories (name, sort_order, updated_at, sync_id)
+                     VALUES (?, ?, ?, ?)",
+                )
+                .bind(name)
+                .bind(sort_order)
+                .bind(&record.updated_at)
+                .bind(&record.sync_id)
+                .execute(&mut **tx)
+                .await
+                .map_err(|e| e.to_string())?;
+            }
+        }
+        SyncEntity::Item => {
+            let category_id: Option<i64> =
+                sqlx::query_scalar("SELECT id FROM categories WHERE sync_id = ?")
+                    .bind(text_field(record, "category"))
+                    .fetch_optional(&mut **tx)
+                    .await
+                    .map_err(|e| e.to_string())?;
+            let Some(category_id) = category_id else {
+                return Ok(Some(Skip::UnknownCategory));
+            };
+            if is_linked(tx, category_id).await? {
+                return Ok(Some(Skip::LinkedCategory));
+            }
+            let (label, value) = (text_field(record, "label"), text_field(record, "value"));
+            let sort_order = int_field(record, "sort_order");
+            let kind = match text_field(record, "kind") {
+                "link" => ItemKind::Link,
+                _ => ItemKind::Text,
+            };
+            let expires_at = record.fields.get("expires_at").and_then(Value::as_str);
+            let result = sqlx::query(
+                "UPDATE items SET category_id = ?, label = ?, value = ?, sort_order = ?,
+                 kind = ?, expires_at = ?, updated_at = ? WHERE sync_id = ?",
+            )
+            .bind(category_id)
+            .bind(label)
+            .bind(value)
+            .bind(sort_order)
+            .bind(kind)
+            .bind(expires_at)
+            .bind(&record.updated_at)
+            .bind(&record.sync_id)
+            .execute(&mut **tx)
+            .await
+            .map_err(|e| e.to_string())?;
+            if result.rows_affected() == 0 {
+                sqlx::query(
+                    "INSERT INTO items (category_id, label, value, sort_order, kind, expires_at,
+                                        updated_at, sync_id)
+                     VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+                )
+                .bind(category_id)
+                .bind(label)
+                .bind(value)
+                .bind(sort_order)
+                .bind(kind)
+                .bind(expires_at)
+                .bind(&record.updated_at)
+                .bind(&record.sync_id)
+                .execute(&mut **tx)
+                .await
+                .map_err(|e| e.to_string())?;
+            }
+        }
+    }
+    Ok(None)
+}
+
+async fn save_base(
+    tx: &mut Transaction<'_, Sqlite>,
+    entity: SyncEntity,
+    record: &Record,
+) -> Result<(), String> {
+    let json = serde_json::to_string(record).map_err(|e| e.to_string())?;
+    sqlx::query("INSERT OR REPLACE INTO sync_base (entity, sync_id, record) VALUES (?, ?, ?)")
+        .bind(entity.as_str())
+        .bind(&record.sync_id)
+        .bind(json)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+async fn record_conflict(
+    tx: &mut Transaction<'_, Sqlite>,
+    entity: SyncEntity,
+    local: &Record,
+    remote: &Record,
+    kept: SyncSide,
+) -> Result<(), String> {
+    let to_json = |record: &Record| serde_json::to_string(record).map_err(|e| e.to_string());
+    sqlx::query(
+        "INSERT INTO sync_conflicts (entity, sync_id, local, remote, kept) VALUES (?, ?, ?, ?, ?)",
+    )
+    .bind(entity.as_str())
+    .bind(&local.sync_id)
+    .bind(to_json(local)?)
+    .bind(to_json(remote)?)
+    .bind(kept.as_str())
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Sync the database with `folder`, which must exist. The folder is only
+/// written once the database has committed.
+pub async fn sync_by_pool(db: &SqlitePool, folder: &Path) -> Result<SyncReport, String> {
+    if !folder.is_dir() {
+        return Err(i18n::tf(
+            "error.sync_folder_missing",
+            &[("path", &folder.display())],
+        ));
+    }
+    let mut report = SyncReport::default();
+    // `(temporary, destination)` of every record to push.
+    let mut staged: Vec<(PathBuf, PathBuf)> = Vec::new();
+    let result = merge_by_pool(db, folder, &mut report, &mut staged).await;
+    if let Err(err) = result {
+        for (temp, _) in &staged {
+            let _ = fs::remove_file(temp);
+        }
+        return Err(err);
+    }
+    for (temp, path) in staged {
+        fs::rename(&temp, path).map_err(|e| e.to_string())?;
+    }
+    Ok(report)
+}
+
+/// The database side of [`sync_by_pool`], in one transaction. Records for
+/// the folder are only staged.
+async fn merge_by_pool(
+    db: &SqlitePool,
+    folder: &Path,
+    report: &mut SyncReport,
+    staged: &mut Vec<(PathBuf, PathBuf)>,
+) -> Result<(), String> {
+    let mut tx = db.begin().await.map_err(|e| e.to_string())?;
+    for entity in SyncEntity::ALL {
+        sqlx::query(&format!(
+            "UPDATE {} SET sync_id = lower(hex(randomblob(16))) WHERE sync_id IS NULL",
+            table(entity)
+        ))
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    }
+    let device_id = device_id(&mut tx).await?;
+    let now: String = sqlx::query_scalar("SELECT datetime('now')")
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let edited_categories = categories_with_local_edits(&mut tx, &device_id, &now).await?;
+
+    // Categories first, so pulled items find their category.
+    for entity in SyncEntity::ALL {
+        let dir = folder.join(table(entity));
+        let base = load_base(&mut tx, entity).await?;
+        let local = load_local(&mut tx, entity, &base, &device_id, &now).await?;
+        let remote = read_folder(&dir)?;
+
+        let ids: BTreeSet<&String> = local.keys().chain(remote.keys()).collect();
+        for id in ids {
+            let (local, remote) = (local.get(id), remote.get(id));
+            let Some(mut resolution) = resolve(base.get(id), local, remote) else {
+                continue;
+            };
+            // Like an edited item beating its own delete, a category whose
+            // items were edited here outlives a delete from elsewhere.
+            if let Some(local) = local.filter(|local| {
+                entity == SyncEntity::Category
+                    && resolution.record.deleted
+                    && !local.deleted
+                    && edited_categories.contains(id)
+            }) {
+                resolution = Resolution {
+                    record: local.clone(),
+                    conflict: Some(SyncSide::Local),
+                };
+            }
+            let record = &resolution.record;
+            let changes_local = match local {
+                Some(local) => !local.same_content(record),
+                None => !record.deleted,
+            };
+            if changes_local {
+                if let Some(skip) = apply(&mut tx, entity, record).await? {
+                    let reason = match skip {
+                        Skip::UnknownCategory => "its category is not known here",
+                        Skip::LinkedCategory => "its category is linked to a file",
+                    };
+                    eprintln!("skipping synced item {id}: {reason}");
+                    report.skipped += 1;
+                    continue;
+                }
+                report.pulled += 1;
+            }
+            if remote != Some(record) {
+                let temp = stage_record(&dir, record)?;
+                staged.push((temp, record_path(&dir, &record.sync_id)));
+                report.pushed += 1;
+            }
+            save_base(&mut tx, entity, record).await?;
+            if let (Some(kept), Some(local), Some(remote)) = (resolution.conflict, local, remote) {
+                record_conflict(&mut tx, entity, local, remote, kept).await?;
+                report.conflicts += 1;
+            }
+        }
+    }
+    tx.commit().await.map_err(|e| e.to_string())
+}
+
+/// Sync with the configured folder and refresh everything that shows the
+/// library if anything was pulled in.
+pub async fn sync_now(app: &AppHandle) -> Result<SyncReport, String> {
+    let _running = RUNNING.lock().await;
+    let pool = app.state::<Db>().pool();
+    let settings = get_settings_by_pool(&pool).await?;
+    if settings.sync_folder.is_empty() {
+        return Err(i18n::t("error.sync_folder_unset"));
+    }
+    let report = sync_by_pool(&pool, Path::new(&settings.sync_folder)).await?;
+
+    if report.pulled > 0 {
+        library::refresh(app).await;
+    }
+    events::emit(
+        app,
+        SyncCompleted {
+            report: report.clone(),
+        },
+    );
+    Ok(report)
+}
+
+/// Sync in the background every `sync_interval_minutes` while a folder is
+/// set.
+pub fn start(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let settings = get_settings_by_pool(&app.state::<Db>().pool()).await;
+            let minutes = settings
+                .as_ref()
+                .map_or(5, |settings| settings.sync_interval_minutes.max(1) as u64);
+            if settings.is_ok_and(|settings| !settings.sync_folder.is_empty()) {
+                if let Err(err) = sync_now(&app).await {
+                    eprintln!("folder sync failed: {err}");
+                }
+            }
+            tokio::time::sleep(Duration::from_secs(minutes * 60)).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::SystemClock;
+    use crate::commands::categories::{
+        create_category_by_pool, delete_category_by_pool, CreateCategoryInput,
+    };
+    use crate::commands::items::{
+        create_item_by_pool, delete_item_by_pool, get_all_items_by_pool, set_item_expiry_by_pool,
+        update_item_by_pool, CreateItemInput, UpdateItemInput,
+    };
+    use crate::commands::sync::list_sync_conflicts_by_pool;
+    use crate::db;
+    use tempfile::TempDir;
+
+    /// A device: its own database in its own directory.
+    async fn machine() -> (TempDir, SqlitePool) {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let pool = db::create_pool(&dir.path().join("peeky.db").to_string_lossy())
+            .await
+            .expect("create pool");
+        db::migrator().run(&pool).await.expect("run migrations");
+        (dir, pool)
+    }
+
+    async fn items(db: &SqlitePool) -> Vec<(String, String)> {
+        get_all_items_by_pool(db, &SystemClock)
+            .await
+            .expect("list items")
+            .into_iter()
+            .map(|item| (item.label, item.value))
+            .collect()
+    }
+
+    async fn item_id(db: &SqlitePool, label: &str) -> i64 {
+        sqlx::query_scalar("SELECT id FROM items WHERE label = ?")
+            .bind(label)
+            .fetch_one(db)
+            .await
+            .expect("find item")
+    }
+
+    /// Pretend the item was last edited at `at`, so tests control which
+    /// edit is newer.
+    async fn edit_item(db: &SqlitePool, label: &str, input: UpdateItemInput, at: &str) {
+        let id = item_id(db, label).await;
+        update_item_by_pool(db, UpdateItemInput { id, ..input })
+            .await
+            .expect("update item");
+        sqlx::query("UPDATE items SET updated_at = ? WHERE id = ?")
+            .bind(at)
+            .bind(id)
+            .execute(db)
+            .await
+            .expect("set updated_at");
+    }
+
+    /// Two devices sharing a folder, both holding one item `ssh`.
+    async fn paired() -> (TempDir, (TempDir, SqlitePool), (TempDir, SqlitePool)) {
+        let folder = tempfile::tempdir().expect("create sync folder");
+        let (a, b) = (machine().await, machine().await);
+        let category = create_category_by_pool(
+            &a.1,
+            CreateCategoryInput {
+                name: "Servers".to_string(),
+            },
+        )
+        .await
+        .unwrap();
+        create_item_by_pool(
+            &a.1,
+            CreateItemInput {
+                category_id: category.id,
+                label: "ssh".to_string(),
+                value: Some("ssh prod".to_string()),
+                kind: ItemKind::Text,
+            },
+        )
+        .await
+        .unwrap();
+        sync_by_pool(&a.1, folder.path()).await.unwrap();
+        sync_by_pool(&b.1, folder.path()).await.unwrap();
+        (folder, a, b)
+    }
+
+    fn update(label: Option<&str>, value: Option<&str>) -> UpdateItemInput {
+        UpdateItemInput {
+            id: 0,
+            label: label.map(str::to_string),
+            value: value.map(str::to_string),
+            sort_order: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn library_propagates_between_devices() {
+        let (folder, (_a_dir, a), (_b_dir, b)) = paired().await;
+        assert_eq!(items(&b).await, vec![("ssh".into(), "ssh prod".into())]);
+
+        // A second run with nothing changed moves nothing.
+        let report = sync_by_pool(&b, folder.path()).await.unwrap();
+        assert_eq!((report.pulled, report.pushed), (0, 0));
+        let report = sync_by_pool(&a, folder.path()).await.unwrap();
+        assert_eq!((report.pulled, report.pushed), (0, 0));
+    }
+
+    #[tokio::test]
+    async fn edits_to_different_fields_are_merged() {
+        let (folder, (_a_dir, a), (_b_dir, b)) = paired().await;
+        edit_item(&a, "ssh", update(Some("prod"), None), "2030-01-01 10:00:00").await;
+        edit_item(
+            &b,
+            "ssh",
+            update(None, Some("ssh -A prod")),
+            "2030-01-01 11:00:00",
+        )
+        .await;
+
+        sync_by_pool(&a, folder.path()).await.unwrap();
+        let report = sync_by_pool(&b, folder.path()).await.unwrap();
+        sync_by_pool(&a, folder.path()).await.unwrap();
+
+        assert_eq!(report.conflicts, 0);
+        let merged = vec![("prod".to_string(), "ssh -A prod".to_string())];
+        assert_eq!(items(&a).await, merged);
+        assert_eq!(items(&b).await, merged);
+    }
+
+    #[tokio::test]
+    async fn same_field_edits_keep_the_newer_and_record_a_conflict() {
+        let (folder, (_a_dir, a), (_b_dir, b)) = paired().await;
+        edit_item(
+            &a,
+            "ssh",
+            update(None, Some("ssh stage")),
+            "2030-01-01 12:00:00",
+        )
+        .await;
+        edit_item(
+            &b,
+            "ssh",
+            update(None, Some("ssh dev")),
+            "2030-01-01 11:00:00",
+        )
+        .await;
+
+        sync_by_pool(&a, folder.path()).await.unwrap();
+        let report = sync_by_pool(&b, folder.path()).await.unwrap();
+        sync_by_pool(&a, folder.path()).await.unwrap();
+
+        assert_eq!(report.conflicts, 1);
+        assert_eq!(items(&b).await, vec![("ssh".into(), "ssh stage".into())]);
+        assert_eq!(items(&a).await, vec![("ssh".into(), "ssh stage".into())]);
+        let conflicts = list_sync_conflicts_by_pool(&b).await.unwrap();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].kept, SyncSide::Remote);
+        assert_eq!(conflicts[0].local["fields"]["value"], "ssh dev");
+    }
+
+    #[tokio::test]
+    async fn deletions_propagate_and_lose_to_edits() {
+        let (folder, (_a_dir, a), (_b_dir, b)) = paired().await;
+        delete_item_by_pool(&a, item_id(&a, "ssh").await)
+            .await
+            .unwrap();
+        sync_by_pool(&a, folder.path()).await.unwrap();
+        sync_by_pool(&b, folder.path()).await.unwrap();
+        assert!(items(&b).await.is_empty());
+
+        let (folder, (_a_dir, a), (_b_dir, b)) = paired().await;
+        delete_item_by_pool(&a, item_id(&a, "ssh").await)
+            .await
+            .unwrap();
+        edit_item(
+            &b,
+            "ssh",
+            update(None, Some("ssh dev")),
+            "2000-01-01 00:00:00",
+        )
+        .await;
+        sync_by_pool(&a, folder.path()).await.unwrap();
+        sync_by_pool(&b, folder.path()).await.unwrap();
+        sync_by_pool(&a, folder.path()).await.unwrap();
+        assert_eq!(items(&a).await, vec![("ssh".into(), "ssh dev".into())]);
+        assert_eq!(items(&b).await, vec![("ssh".into(), "ssh dev".into())]);
+    }
+
+    #[tokio::test]
+    async fn items_of_unknown_categories_are_reported_and_left_for_later() {
+        let folder = tempfile::tempdir().expect("create sync folder");
+        let (_dir, db) = machine().await;
+        let mut fields = Map::new();
+        fields.insert("category".into(), "not-synced-yet".into());
+        fields.insert("label".into(), "vpn".into());
+        let orphan = Record {
+            sync_id: "orphan".to_string(),
+            updated_at: "2030-01-01 00:00:00".to_string(),
+            device_id: "other".to_string(),
+            deleted: false,
+            fields,
+        };
+        let items_dir = folder.path().join("items");
+        let temp = stage_record(&items_dir, &orphan).unwrap();
+        fs::rename(temp, record_path(&items_dir, "orphan")).unwrap();
+
+        let report = sync_by_pool(&db, folder.path()).await.unwrap();
+        assert_eq!((report.pulled, report.skipped), (0, 1));
+        assert!(items(&db).await.is_empty());
+        // Nothing half-written is left behind.
+        let names: Vec<_> = fs::read_dir(&items_dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        assert_eq!(names, vec!["orphan.json"]);
+    }
+
+    #[tokio::test]
+    async fn items_of_linked_categories_are_left_to_their_file() {
+        let (folder, (_a_dir, a), (_b_dir, b)) = paired().await;
+        let category_id: i64 = sqlx::query_scalar("SELECT id FROM categories")
+            .fetch_one(&b)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO category_links (category_id, path) VALUES (?, '/servers.md')")
+            .bind(category_id)
+            .execute(&b)
+            .await
+            .unwrap();
+        edit_item(
+            &a,
+            "ssh",
+            update(None, Some("ssh stage")),
+            "2030-01-01 12:00:00",
+        )
+        .await;
+        let category_id = sqlx::query_scalar("SELECT id FROM categories")
+            .fetch_one(&a)
+            .await
+            .unwrap();
+        create_item_by_pool(
+            &a,
+            CreateItemInput {
+                category_id,
+                label: "vpn".to_string(),
+                value: Some("vpn up".to_string()),
+                kind: ItemKind::Text,
+            },
+        )
+        .await
+        .unwrap();
+
+        sync_by_pool(&a, folder.path()).await.unwrap();
+        let report = sync_by_pool(&b, folder.path()).await.unwrap();
+        assert_eq!((report.pulled, report.skipped), (0, 2));
+        assert_eq!(items(&b).await, vec![("ssh".into(), "ssh prod".into())]);
+    }
+
+    #[tokio::test]
+    async fn category_deletes_keep_items_edited_on_the_other_device() {
+        let (folder, (_a_dir, a), (_b_dir, b)) = paired().await;
+        let category_id = sqlx::query_scalar("SELECT id FROM categories")
+            .fetch_one(&a)
+            .await
+            .unwrap();
+        delete_category_by_pool(&a, category_id).await.unwrap();
+        edit_item(
+            &b,
+            "ssh",
+            update(None, Some("ssh dev")),
+            "2000-01-01 00:00:00",
+        )
+        .await;
+
+        sync_by_pool(&a, folder.path()).await.unwrap();
+        let report = sync_by_pool(&b, folder.path()).await.unwrap();
+        sync_by_pool(&a, folder.path()).await.unwrap();
+
+        // The category and the item each lost their delete.
+        assert_eq!(report.conflicts, 2);
+        assert_eq!(items(&b).await, vec![("ssh".into(), "ssh dev".into())]);
+        assert_eq!(items(&a).await, vec![("ssh".into(), "ssh dev".into())]);
+        let conflicts = list_sync_conflicts_by_pool(&b).await.unwrap();
+        let category = conflicts
+            .iter()
+            .find(|conflict| conflict.entity == SyncEntity::Category)
+            .expect("category conflict");
+        assert_eq!(category.kept, SyncSide::Local);
+        assert_eq!(category.remote["deleted"], true);
+    }
+
+    #[tokio::test]
+    async fn expiry_propagates_between_devices() {
+        let (folder, (_a_dir, a), (_b_dir, b)) = paired().await;
+        let id = item_id(&a, "ssh").await;
+        set_item_expiry_by_pool(&a, id, Some("2099-01-01 00:00:00"))
+            .await
+            .unwrap();
+
+        sync_by_pool(&a, folder.path()).await.unwrap();
+        sync_by_pool(&b, folder.path()).await.unwrap();
+
+        let expires_at: Option<String> =
+            sqlx::query_scalar("SELECT expires_at FROM items WHERE label = 'ssh'")
+                .fetch_one(&b)
+                .await
+                .unwrap();
+        assert_eq!(expires_at.as_deref(), Some("2099-01-01 00:00:00"));
+    }
+
+    #[tokio::test]
+    async fn missing_folder_is_an_error() {
+        let (dir, pool) = machine().await;
+        assert!(sync_by_pool(&pool, &dir.path().join("absent"))
+            .await
+            .is_err());
+    }
+}
@@ -0,0 +1,159 @@
+//! The menu-bar tray icon and its menu.
+
+use tauri::menu::{
+    CheckMenuItem, IsMenuItem, Menu, MenuEvent, MenuItem, MenuItemKind, PredefinedMenuItem, Submenu,
+};
+use tauri::tray::TrayIconBuilder;
+use tauri::{AppHandle, Listener, Manager, Wry};
+
+use crate::clock::SystemClock;
+use crate::commands::categories::get_categories_by_pool;
+use crate::commands::items::get_all_items_by_pool;
+use crate::db::Db;
+use crate::events::{AppEvent, LibraryChanged};
+use crate::hotkeys;
+use crate::i18n;
+use crate::overlay;
+use crate::tray_menu::{self, MenuAction, MenuEntry};
+
+pub const TRAY_ID: &str = "main-tray";
+
+pub fn create(app: &AppHandle) -> tauri::Result<()> {
+    let tray_icon = app.default_window_icon().cloned().unwrap();
+    TrayIconBuilder::with_id(TRAY_ID)
+        .icon(tray_icon)
+        .icon_as_template(true)
+        .tooltip(i18n::t("app.name"))
+        .title(i18n::t("app.name"))
+        .menu(&native_menu(app, &tray_menu::build(&[], &[], false))?)
+        .show_menu_on_left_click(true)
+        .on_menu_event(on_menu_event)
+        .build(app)?;
+
+    let handle = app.clone();
+    app.listen(LibraryChanged::NAME, move |_| rebuild(&handle));
+    rebuild(app);
+    Ok(())
+}
+
+pub fn set_title(app: &AppHandle, title: &str) {
+    if let Some(tray) = app.tray_by_id(TRAY_ID) {
+        let _ = tray.set_title(Some(title));
+    }
+}
+
+/// Re-read tray text from the catalog after the locale changed.
+pub fn refresh_locale(app: &AppHandle) {
+    if let Some(tray) = app.tray_by_id(TRAY_ID) {
+        let _ = tray.set_tooltip(Some(i18n::t("app.name")));
+    }
+    rebuild(app);
+}
+
+/// Regenerate the menu from the library in the background.
+pub fn rebuild(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(err) = rebuild_now(&app).await {
+            eprintln!("failed to rebuild tray menu: {err}");
+        }
+    });
+}
+
+async fn rebuild_now(app: &AppHandle) -> Result<(), String> {
+    let db = app.state::<Db>().pool();
+    let categories = get_categories_by_pool(&db).await?;
+    let items = get_all_items_by_pool(&db, &SystemClock).await?;
+    let entries = tray_menu::build(&categories, &items, hotkeys::is_paused(app));
+
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return Ok(());
+    };
+    let menu = native_menu(app, &entries).map_err(|e| e.to_string())?;
+    tray.set_menu(Some(menu)).map_err(|e| e.to_string())
+}
+
+fn native_menu(app: &AppHandle, entries: &[MenuEntry]) -> tauri::Result<Menu<Wry>> {
+    let items = native_items(app, entries)?;
+    Menu::with_items(app, &as_refs(&items))
+}
+
+fn native_items(app: &AppHandle, entries: &[MenuEntry]) -> tauri::Result<Vec<MenuItemKind<Wry>>> {
+    entries
+        .iter()
+        .map(|entry| native_item(app, entry))
+        .collect()
+}
+
+fn native_item(app: &AppHandle, entry: &MenuEntry) -> tauri::Result<MenuItemKind<Wry>> {
+    Ok(match entry {
+        MenuEntry::Action { action, label } => MenuItemKind::MenuItem(MenuItem::with_id(
+            app,
+            action.id(),
+            label,
+            true,
+            None::<&str>,
+        )?),
+        MenuEntry::Toggle {
+            action,
+            label,
+            checked,
+        } => MenuItemKind::Check(CheckMenuItem::with_id(
+            app,
+            action.id(),
+            label,
+            true,
+            *checked,
+            None::<&str>,
+        )?),
+        MenuEntry::Submenu { label, entries } => {
+            let items = native_items(app, entries)?;
+            MenuItemKind::Submenu(Submenu::with_items(app, label, true, &as_refs(&items))?)
+        }
+        MenuEntry::Disabled { label } => {
+            MenuItemKind::MenuItem(MenuItem::new(app, label, false, None::<&str>)?)
+        }
+        MenuEntry::Separator => MenuItemKind::Predefined(PredefinedMenuItem::separator(app)?),
+    })
+}
+
+fn as_refs(items: &[MenuItemKind<Wry>]) -> Vec<&dyn IsMenuItem<Wry>> {
+    items
+        .iter()
+        .map(|item| item as &dyn IsMenuItem<Wry>)
+        .collect()
+}
+
+fn on_menu_event(app: &AppHandle, event: MenuEvent) {
+    let Some(action) = MenuAction::from_id(event.id().as_ref()) else {
+        return;
+    };
+    match action {
+        MenuAction::CopyItem(item_id) => {
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(err) = hotkeys::copy_item(&app, item_id).await {
+                    eprintln!("failed to copy item {item_id}: {err}");
+                }
+            });
+        }
+        MenuAction::ShowOverlay => overlay::show(app),
+        MenuAction::Manage => {
+            if let Some(window) = app.get_webview_window("main") {
+                let _ = window.show();
+                let _ = window.set_focus();
+            }
+        }
+        MenuAction::TogglePause => {
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                let paused = !hotkeys::is_paused(&app);
+                if let Err(err) = hotkeys::set_paused(&app, paused).await {
+                    eprintln!("failed to pause shortcuts: {err}");
+                }
+                rebuild(&app);
+            });
+        }
+        MenuAction::Quit => app.exit(0),
+    }
+}
@@ -0,0 +1,261 @@
+//! Pure model of the tray menu, generated from the library so it can be
+//! tested without a running app. `tray` turns it into native menu items.
+
+use crate::commands::categories::Category;
+use crate::commands::items::ItemWithCategory;
+use crate::i18n;
+
+/// Longer labels are cut off so the menu stays a sensible width.
+const MAX_LABEL_CHARS: usize = 48;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MenuAction {
+    CopyItem(i64),
+    ShowOverlay,
+    Manage,
+    TogglePause,
+    Quit,
+}
+
+impl MenuAction {
+    /// Native menu id, parsed back by [`MenuAction::from_id`].
+    pub fn id(self) -> String {
+        match self {
+            MenuAction::CopyItem(id) => format!("copy-item:{id}"),
+            MenuAction::ShowOverlay => "show-overlay".to_string(),
+            MenuAction::Manage => "manage".to_string(),
+            MenuAction::TogglePause => "pause-shortcuts".to_string(),
+            MenuAction::Quit => "quit".to_string(),
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        if let Some(item_id) = id.strip_prefix("copy-item:") {
+            return item_id.parse().ok().map(MenuAction::CopyItem);
+        }
+        match id {
+            "show-overlay" => Some(MenuAction::ShowOverlay),
+            "manage" => Some(MenuAction::Manage),
+            "pause-shortcuts" => Some(MenuAction::TogglePause),
+            "quit" => Some(MenuAction::Quit),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MenuEntry {
+    Action {
+        action: MenuAction,
+        label: String,
+    },
+    Toggle {
+        action: MenuAction,
+        label: String,
+        checked: bool,
+    },
+    Submenu {
+        label: String,
+        entries: Vec<MenuEntry>,
+    },
+    /// Greyed-out placeholder text.
+    Disabled {
+        label: String,
+    },
+    Separator,
+}
+
+/// Truncate and escape `&`, which native menus treat as a mnemonic marker.
+fn menu_label(text: &str) -> String {
+    let label = if text.chars().count() > MAX_LABEL_CHARS {
+        let cut: String = text.chars().take(MAX_LABEL_CHARS - 1).collect();
+        format!("{}…", cut.trim_end())
+    } else {
+        text.to_string()
+    };
+    label.replace('&', "&&")
+}
+
+/// Categories in order, each with its items, followed by the app actions.
+/// `items` must be sorted by category, as `get_all_items_by_pool` returns
+/// them.
+pub fn build(categories: &[Category], items: &[ItemWithCategory], paused: bool) -> Vec<MenuEntry> {
+    let mut entries: Vec<MenuEntry> = categories
+        .iter()
+        .map(|category| {
+            let mut children: Vec<MenuEntry> = items
+                .iter()
+                .filter(|item| item.category_id == category.id)
+                .map(|item| MenuEntry::Action {
+                    action: MenuAction::CopyItem(item.id),
+                    label: menu_label(&item.label),
+                })
+                .collect();
+            if children.is_empty() {
+                children.push(MenuEntry::Disabled {
+                    label: i18n::t("tray.menu.no_items"),
+                });
+            }
+            MenuEntry::Submenu {
+                label: menu_label(&category.name),
+                entries: children,
+            }
+        })
+        .collect();
+
+    if entries.is_empty() {
+        entries.push(MenuEntry::Disabled {
+            label: i18n::t("tray.menu.no_categories"),
+        });
+    }
+
+    entries.extend([
+        MenuEntry::Separator,
+        MenuEntry::Action {
+            action: MenuAction::ShowOverlay,
+            label: i18n::t("tray.menu.show_overlay"),
+        },
+        MenuEntry::Action {
+            action: MenuAction::Manage,
+            label: i18n::t("tray.menu.manage"),
+        },
+        MenuEntry::Toggle {
+            action: MenuAction::TogglePause,
+            label: i18n::t("tray.menu.pause_shortcuts"),
+            checked: paused,
+        },
+        MenuEntry::Separator,
+        MenuEntry::Action {
+            action: MenuAction::Quit,
+            label: i18n::t("tray.menu.quit"),
+        },
+    ]);
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::items::ItemKind;
+
+    fn category(id: i64, name: &str) -> Category {
+        Category {
+            id,
+            name: name.to_string(),
+            sort_order: id,
+            created_at: String::new(),
+            updated_at: String::new(),
+        }
+    }
+
+    fn item(id: i64, category: &Category, label: &str) -> ItemWithCategory {
+        ItemWithCategory {
+            id,
+            category_id: category.id,
+            label: label.to_string(),
+            value: format!("value {id}"),
+            sort_order: id,
+            kind: ItemKind::Text,
+            category_name: category.name.clone(),
+            category_sort_order: category.sort_order,
+        }
+    }
+
+    fn action(action: MenuAction, label: &str) -> MenuEntry {
+        MenuEntry::Action {
+            action,
+            label: label.to_string(),
+        }
+    }
+
+    #[test]
+    fn lists_categories_with_their_items_then_actions() {
+        let git = category(1, "Git");
+        let ssh = category(2, "SSH & VPN");
+        let empty = category(3, "Empty");
+        let items = vec![
+            item(10, &git, "Rebase"),
+            item(11, &git, "Amend"),
+            item(20, &ssh, "Bastion"),
+        ];
+
+        let menu = build(&[git, ssh, empty], &items, false);
+
+        assert_eq!(
+            menu[..3],
+            [
+                MenuEntry::Submenu {
+                    label: "Git".to_string(),
+                    entries: vec![
+                        action(MenuAction::CopyItem(10), "Rebase"),
+                        action(MenuAction::CopyItem(11), "Amend"),
+                    ],
+                },
+                MenuEntry::Submenu {
+                    label: "SSH && VPN".to_string(),
+                    entries: vec![action(MenuAction::CopyItem(20), "Bastion")],
+                },
+                MenuEntry::Submenu {
+                    label: "Empty".to_string(),
+                    entries: vec![MenuEntry::Disabled {
+                        label: "No items".to_string()
+                    }],
+                },
+            ]
+        );
+        assert_eq!(
+            menu[3..],
+            [
+                MenuEntry::Separator,
+                action(MenuAction::ShowOverlay, "Show overlay"),
+                action(MenuAction::Manage, "Manage…"),
+                MenuEntry::Toggle {
+                    action: MenuAction::TogglePause,
+                    label: "Pause shortcuts".to_string(),
+                    checked: false,
+                },
+                MenuEntry::Separator,
+                action(MenuAction::Quit, "Quit"),
+            ]
+        );
+    }
+
+    #[test]
+    fn empty_library_shows_placeholder_and_pause_state() {
+        let menu = build(&[], &[], true);
+
+        assert_eq!(
+            menu[0],
+            MenuEntry::Disabled {
+                label: "No categories".to_string()
+            }
+        );
+        assert!(menu.contains(&MenuEntry::Toggle {
+            action: MenuAction::TogglePause,
+            label: "Pause shortcuts".to_string(),
+            checked: true,
+        }));
+    }
+
+    #[test]
+    fn long_labels_are_truncated() {
+        let label = menu_label(&"x".repeat(100));
+        assert_eq!(label.chars().count(), MAX_LABEL_CHARS);
+        assert!(label.ends_with('…'));
+    }
+
+    #[test]
+    fn action_ids_round_trip() {
+        for action in [
+            MenuAction::CopyItem(42),
+            MenuAction::ShowOverlay,
+            MenuAction::Manage,
+            MenuAction::TogglePause,
+            MenuAction::Quit,
+        ] {
+            assert_eq!(MenuAction::from_id(&action.id()), Some(action));
+        }
+        assert_eq!(MenuAction::from_id("copy-item:abc"), None);
+        assert_eq!(MenuAction::from_id("nope"), None);
+    }
+}
@@ -0,0 +1,340 @@
+//! Tray title ticker: cycles the tray title through the pinned items.
+//!
+//! [`Ticker`] is a pure scheduler driven by [`Ticker::tick`] with the current
+//! time, so rotation can be tested with a manual clock. [`start`] runs it on
+//! a background thread against the system clock and writes each new title
+//! to the tray.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use tauri::{AppHandle, Listener, Manager};
+
+use crate::clock::{Clock, SystemClock};
+use crate::commands::settings::{get_settings_by_pool, AppSettings};
+use crate::commands::ticker::{get_ticker_items_by_pool, TickerStatus};
+use crate::db::Db;
+use crate::events::{AppEvent, LibraryChanged};
+use crate::i18n;
+use crate::tray;
+
+/// How often the background thread checks whether a title is due.
+const TICK_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Title for an item: `label: value` when it fits, otherwise the value
+/// alone, otherwise the value cut to `max_chars` with an ellipsis. Only the
+/// first non-blank line of the value is used, with whitespace collapsed.
+pub fn format_title(label: &str, value: &str, max_chars: usize) -> String {
+    let collapse = |text: &str| text.split_whitespace().collect::<Vec<_>>().join(" ");
+    let label = collapse(label);
+    let value = value
+        .lines()
+        .map(collapse)
+        .find(|line| !line.is_empty())
+        .unwrap_or_default();
+
+    let fits = |text: &str| text.chars().count() <= max_chars;
+    let full = match (label.is_empty(), value.is_empty()) {
+        (_, true) => label.clone(),
+        (true, false) => value.clone(),
+        (false, false) => format!("{label}: {value}"),
+    };
+    if fits(&full) {
+        return full;
+    }
+    let text = if value.is_empty() { label } else { value };
+    if fits(&text) {
+        return text;
+    }
+    let cut: String = text.chars().take(max_chars.saturating_sub(1)).collect();
+    format!("{}…", cut.trim_end())
+}
+
+#[derive(Debug)]
+pub struct Ticker {
+    titles: Vec<String>,
+    /// Title shown when there is nothing to rotate through.
+    fallback: String,
+    interval: Duration,
+    paused: bool,
+    index: usize,
+    /// When the next title is due; `None` shows the current one on the next
+    /// tick.
+    next_at: Option<Instant>,
+    /// Whether the tray currently shows a ticker title rather than the
+    /// fallback.
+    rotating: bool,
+}
+
+impl Ticker {
+    pub fn new(fallback: String, interval: Duration) -> Self {
+        Self {
+            titles: Vec::new(),
+            fallback,
+            interval,
+            paused: false,
+            index: 0,
+            next_at: None,
+            rotating: false,
+        }
+    }
+
+    /// Replace the titles, restarting from the first one if they changed.
+    pub fn set_titles(&mut self, titles: Vec<String>) {
+        if titles == self.titles {
+            return;
+        }
+        self.titles = titles;
+        self.index = 0;
+        self.next_at = None;
+    }
+
+    pub fn set_interval(&mut self, interval: Duration) {
+        if interval != self.interval {
+            self.interval = interval;
+            self.next_at = None;
+        }
+    }
+
+    /// Record the title to fall back to. Returns it if it should be shown
+    /// right away because the ticker is not rotating.
+    pub fn set_fallback(&mut self, title: String) -> Option<String> {
+        self.fallback = title;
+        (!self.rotating).then(|| self.fallback.clone())
+    }
+
+    /// Pausing freezes the current title; resuming shows it again at once
+    /// and continues the rotation from there.
+    pub fn set_paused(&mut self, paused: bool) {
+        if self.paused && !paused {
+            self.next_at = None;
+        }
+        self.paused = paused;
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn current(&self) -> Option<&str> {
+        if !self.rotating {
+            return None;
+        }
+        self.titles.get(self.index).map(String::as_str)
+    }
+
+    /// Advance to `now`, returning the title to show if it changed.
+    pub fn tick(&mut self, now: Instant) -> Option<String> {
+        if self.titles.is_empty() {
+            if !self.rotating {
+                return None;
+            }
+            self.rotating = false;
+            return Some(self.fallback.clone());
+        }
+        if self.paused {
+            return None;
+        }
+        match self.next_at {
+            Some(due) if now < due => return None,
+            Some(_) => self.index = (self.index + 1) % self.titles.len(),
+            None => {}
+        }
+        self.next_at = Some(now + self.interval);
+        self.rotating = true;
+        Some(self.titles[self.index].clone())
+    }
+}
+
+/// Managed state wrapping [`Ticker`].
+pub struct TrayTicker(Mutex<Ticker>);
+
+impl TrayTicker {
+    pub fn new(settings: &AppSettings) -> Self {
+        Self(Mutex::new(Ticker::new(
+            i18n::t("app.name"),
+            interval(settings),
+        )))
+    }
+
+    pub fn set_paused(&self, paused: bool) {
+        self.0.lock().unwrap().set_paused(paused);
+    }
+
+    pub fn status(&self, item_ids: Vec<i64>) -> TickerStatus {
+        let ticker = self.0.lock().unwrap();
+        TickerStatus {
+            paused: ticker.is_paused(),
+            item_ids,
+            current: ticker.current().map(str::to_string),
+        }
+    }
+}
+
+fn interval(settings: &AppSettings) -> Duration {
+    Duration::from_secs(settings.ticker_interval_secs.max(1) as u64)
+}
+
+/// Reload the pinned items and ticker settings from the database.
+pub async fn reload(app: &AppHandle) -> Result<(), String> {
+    let db = app.state::<Db>().pool();
+    let settings = get_settings_by_pool(&db).await?;
+    let max_chars = settings.ticker_max_chars.max(1) as usize;
+    let titles = get_ticker_items_by_pool(&db, &SystemClock)
+        .await?
+        .iter()
+        .map(|item| format_title(&item.label, &item.value, max_chars))
+        .collect();
+
+    let ticker = app.state::<TrayTicker>();
+    let mut ticker = ticker.inner().0.lock().unwrap();
+    ticker.set_interval(interval(&settings));
+    ticker.set_titles(titles);
+    Ok(())
+}
+
+/// Set the title shown while nothing is pinned (`update_tray_title`).
+pub fn set_static_title(app: &AppHandle, title: String) {
+    let ticker = app.state::<TrayTicker>();
+    let shown = ticker.inner().0.lock().unwrap().set_fallback(title);
+    if let Some(title) = shown {
+        tray::set_title(app, &title);
+    }
+}
+
+/// Load the pinned items, follow library changes and start rotating.
+pub fn start(app: &AppHandle) {
+    if let Err(err) = tauri::async_runtime::block_on(reload(app)) {
+        eprintln!("failed to load ticker items: {err}");
+    }
+
+    let handle = app.clone();
+    app.listen(LibraryChanged::NAME, move |_| {
+        let app = handle.clone();
+        tauri::async_runtime::spawn(async move {
+            if let Err(err) = reload(&app).await {
+                eprintln!("failed to reload ticker items: {err}");
+            }
+        });
+    });
+
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let clock = SystemClock;
+        loop {
+            std::thread::sleep(TICK_INTERVAL);
+            let title = app
+                .state::<TrayTicker>()
+                .inner()
+                .0
+                .lock()
+                .unwrap()
+                .tick(clock.now());
+            if let Some(title) = title {
+                tray::set_title(&app, &title);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+
+    fn ticker(titles: &[&str]) -> Ticker {
+        let mut ticker = Ticker::new("Peeky".to_string(), Duration::from_secs(10));
+        ticker.set_titles(titles.iter().map(|t| t.to_string()).collect());
+        ticker
+    }
+
+    #[test]
+    fn rotates_through_titles_at_the_interval() {
+        let clock = ManualClock::new();
+        let mut ticker = ticker(&["On-call: 555-0100", "Sprint 42"]);
+
+        assert_eq!(
+            ticker.tick(clock.now()).as_deref(),
+            Some("On-call: 555-0100")
+        );
+        clock.advance_ms(9_999);
+        assert_eq!(ticker.tick(clock.now()), None);
+        clock.advance_ms(1);
+        assert_eq!(ticker.tick(clock.now()).as_deref(), Some("Sprint 42"));
+        clock.advance_ms(10_000);
+        assert_eq!(
+            ticker.tick(clock.now()).as_deref(),
+            Some("On-call: 555-0100")
+        );
+        assert_eq!(ticker.current(), Some("On-call: 555-0100"));
+    }
+
+    #[test]
+    fn pause_freezes_and_resume_continues() {
+        let clock = ManualClock::new();
+        let mut ticker = ticker(&["a", "b", "c"]);
+        ticker.tick(clock.now());
+
+        ticker.set_paused(true);
+        clock.advance_ms(60_000);
+        assert_eq!(ticker.tick(clock.now()), None);
+        assert_eq!(ticker.current(), Some("a"));
+
+        ticker.set_paused(false);
+        assert_eq!(ticker.tick(clock.now()).as_deref(), Some("a"));
+        clock.advance_ms(10_000);
+        assert_eq!(ticker.tick(clock.now()).as_deref(), Some("b"));
+    }
+
+    #[test]
+    fn emptied_rotation_restores_the_fallback() {
+        let clock = ManualClock::new();
+        let mut ticker = ticker(&["a"]);
+        assert_eq!(
+            ticker.set_fallback("Static".to_string()),
+            Some("Static".to_string())
+        );
+
+        ticker.tick(clock.now());
+        // While rotating, a new static title waits for the rotation to end.
+        assert_eq!(ticker.set_fallback("Later".to_string()), None);
+
+        ticker.set_titles(Vec::new());
+        assert_eq!(ticker.tick(clock.now()).as_deref(), Some("Later"));
+        assert_eq!(ticker.tick(clock.now()), None);
+        assert_eq!(ticker.current(), None);
+    }
+
+    #[test]
+    fn changed_titles_restart_but_identical_reloads_do_not() {
+        let clock = ManualClock::new();
+        let mut ticker = ticker(&["a", "b"]);
+        ticker.tick(clock.now());
+        clock.advance_ms(10_000);
+        assert_eq!(ticker.tick(clock.now()).as_deref(), Some("b"));
+
+        ticker.set_titles(vec!["a".to_string(), "b".to_string()]);
+        assert_eq!(ticker.tick(clock.now()), None);
+
+        ticker.set_titles(vec!["c".to_string(), "a".to_string()]);
+        assert_eq!(ticker.tick(clock.now()).as_deref(), Some("c"));
+    }
+
+    #[test]
+    fn titles_are_truncated_to_fit() {
+        assert_eq!(format_title("On-call", "555-0100", 24), "On-call: 555-0100");
+        // Too long with the label: the value alone.
+        assert_eq!(format_title("On-call phone", "555-0100", 16), "555-0100");
+        // Still too long: cut with an ellipsis.
+        assert_eq!(
+            format_title("Sprint", "Sprint 42 ends on Friday", 12),
+            "Sprint 42 e…"
+        );
+        // Only the first non-blank line, whitespace collapsed.
+        assert_eq!(
+            format_title("Note", "\n  first   line \nsecond", 40),
+            "Note: first line"
+        );
+        assert_eq!(format_title("Label only", "", 40), "Label only");
+    }
+}
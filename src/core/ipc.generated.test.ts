@@ -1,13 +1,23 @@
 import { describe, it, expect } from 'vitest';
-import { COMMAND_NAMES } from './ipc.generated';
+import { COMMAND_NAMES, EVENT_NAMES } from './ipc.generated';
 
 describe('ipc generated types', () => {
   it('should include all registered command names', () => {
     expect(COMMAND_NAMES).toEqual([
       'ping',
       'get_app_info',
+      'get_autostart_status',
+      'list_backups',
+      'create_backup_now',
+      'restore_backup',
       'get_settings',
       'set_settings',
+      'get_settings_schema',
+      'reset_settings',
+      'get_supported_locales',
+      'get_http_api_token',
+      'regenerate_http_api_token',
+      'get_effective_theme',
       'get_categories',
       'create_category',
       'update_category',
@@ -18,7 +28,49 @@ describe('ipc generated types', () => {
       'create_item',
       'update_item',
       'delete_item',
+      'set_item_expiry',
+      'find_duplicate_items',
+      'merge_items',
+      'get_item_history',
+      'diff_item_revisions',
+      'restore_item_revision',
+      'get_changes_since',
+      'import_snippets',
+      'export_snippets',
+      'import_keymap',
+      'import_cheatsheets',
+      'import_ssh_hosts',
+      'list_bookmark_folders',
+      'import_bookmarks',
+      'get_category_links',
+      'link_category',
+      'unlink_category',
+      'sync_category_link',
+      'get_hotkey_bindings',
+      'create_hotkey_binding',
+      'delete_hotkey_binding',
+      'get_ticker_status',
+      'set_ticker_items',
+      'pause_ticker',
+      'resume_ticker',
+      'sync_now',
+      'list_sync_conflicts',
+      'dismiss_sync_conflict',
       'update_tray_title',
     ]);
   });
+
+  it('should include all backend event names', () => {
+    expect(EVENT_NAMES).toEqual([
+      'library-changed',
+      'settings-changed',
+      'overlay-filter',
+      'select-category',
+      'effective-theme-changed',
+      'database-restored',
+      'sync-completed',
+      'library-reloaded',
+      'category-link-synced',
+    ]);
+  });
 });
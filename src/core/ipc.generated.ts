@@ -13,7 +13,45 @@ export interface AppInfo {
 export interface AppSettings {
   locale: string;
   launch_on_login: boolean;
-  theme: string;
+  theme: Theme;
+  hold_modifier: string;
+  hold_duration_ms: number;
+  hold_hide_on_release: boolean;
+  ticker_interval_secs: number;
+  ticker_max_chars: number;
+  backup_interval_hours: number;
+  backup_retention: number;
+  sync_folder: string;
+  sync_interval_minutes: number;
+  http_api_enabled: boolean;
+  http_api_port: number;
+  ssh_hosts_sync: boolean;
+  item_revision_limit: number;
+  expired_items: ExpiredItemAction;
+}
+
+export type AutostartDrift = "removed_externally" | "added_externally" | "outdated";
+
+export interface AutostartStatus {
+  supported: boolean;
+  enabled: boolean;
+  installed: boolean;
+  drift: AutostartDrift | null;
+  path: string | null;
+}
+
+export interface BackupInfo {
+  name: string;
+  kind: BackupKind;
+  created_at: string;
+  size_bytes: number;
+}
+
+export type BackupKind = "automatic" | "manual" | "pre_restore";
+
+export interface BookmarkFolder {
+  name: string;
+  bookmarks: number;
 }
 
 export interface Category {
@@ -24,14 +62,93 @@ export interface Category {
   updated_at: string;
 }
 
+export interface CategoryLink {
+  category_id: number;
+  path: string;
+  error: string | null;
+  synced_at: string | null;
+}
+
+export interface CategoryLinkSynced {
+  link: CategoryLink;
+}
+
+export type ChangeKind = "created" | "updated" | "deleted" | "reordered";
+
+export interface ChangeSet {
+  cursor: number;
+  categories: Category[];
+  items: Item[];
+  deleted: Tombstone[];
+  has_more: boolean;
+  reset: boolean;
+}
+
+export type CheatsheetFormat = "tldr" | "cheat";
+
 export interface CreateCategoryInput {
   name: string;
 }
 
+export interface CreateHotkeyBindingInput {
+  shortcut: string;
+  action: string;
+  category_id: number | null;
+  item_id: number | null;
+}
+
 export interface CreateItemInput {
   category_id: number;
   label: string;
   value: string | null;
+  kind: ItemKind;
+}
+
+export interface DatabaseRestored {
+  backup: string;
+  safety_backup: string;
+}
+
+export interface DiffLine {
+  tag: DiffTag;
+  text: string;
+}
+
+export type DiffTag = "equal" | "delete" | "insert";
+
+export interface DuplicateGroup {
+  kind: DuplicateMatch;
+  similarity: number;
+  items: ItemWithCategory[];
+}
+
+export type DuplicateMatch = "exact" | "normalized" | "fuzzy";
+
+export type EffectiveTheme = "light" | "dark";
+
+export interface EffectiveThemeChanged {
+  theme: EffectiveTheme;
+}
+
+export type ExpiredItemAction = "delete" | "move";
+
+export interface HotkeyBinding {
+  id: number;
+  shortcut: string;
+  action: string;
+  category_id: number | null;
+  item_id: number | null;
+  created_at: string;
+}
+
+export type ImportMode = "merge" | "update" | "replace";
+
+export interface ImportSummary {
+  categories_created: number;
+  items_created: number;
+  items_updated: number;
+  items_skipped: number;
+  items_deleted: number;
 }
 
 export interface Item {
@@ -42,6 +159,18 @@ export interface Item {
   sort_order: number;
   created_at: string;
   updated_at: string;
+  expires_at: string | null;
+  kind: ItemKind;
+}
+
+export type ItemKind = "text" | "link";
+
+export interface ItemRevision {
+  id: number;
+  item_id: number;
+  label: string;
+  value: string;
+  created_at: string;
 }
 
 export interface ItemWithCategory {
@@ -52,12 +181,102 @@ export interface ItemWithCategory {
   sort_order: number;
   category_name: string;
   category_sort_order: number;
+  kind: ItemKind;
+}
+
+export type KeymapFormat = "vscode" | "jetbrains";
+
+export interface LibraryChanged {
+  entity: LibraryEntity;
+  kind: ChangeKind;
+  ids: number[];
+  category_id: number | null;
+}
+
+export type LibraryEntity = "category" | "item";
+
+export interface LibraryReloaded {
+}
+
+export interface MergeItemsInput {
+  keep_id: number;
+  merge_ids: number[];
+  combine_values: boolean;
+}
+
+export interface OverlayFilter {
+  category_id: number | null;
+}
+
+export interface RevisionDiff {
+  old_label: string;
+  new_label: string;
+  lines: DiffLine[];
+}
+
+export interface SelectCategory {
+  category: Category;
+}
+
+export type SettingKind = "bool" | "integer" | "choice" | "text";
+
+export interface SettingSchema {
+  key: string;
+  kind: SettingKind;
+  default: unknown;
+  min: number | null;
+  max: number | null;
+  choices: string[] | null;
+}
+
+export interface SettingsChanged {
+  settings: AppSettings;
+  changed: string[];
+}
+
+export type SnippetFormat = "alfred" | "raycast" | "espanso";
+
+export interface SupportedLocale {
+  tag: string;
+  name: string;
+}
+
+export interface SyncCompleted {
+  report: SyncReport;
+}
+
+export interface SyncConflict {
+  id: number;
+  entity: SyncEntity;
+  sync_id: string;
+  local: unknown;
+  remote: unknown;
+  kept: SyncSide;
+  detected_at: string;
+}
+
+export type SyncEntity = "category" | "item";
+
+export interface SyncReport {
+  pulled: number;
+  pushed: number;
+  conflicts: number;
+  skipped: number;
 }
 
-export interface SetSettingsInput {
-  locale: string | null;
-  launch_on_login: boolean | null;
-  theme: string | null;
+export type SyncSide = "local" | "remote";
+
+export type Theme = "system" | "light" | "dark";
+
+export interface TickerStatus {
+  paused: boolean;
+  item_ids: number[];
+  current: string | null;
+}
+
+export interface Tombstone {
+  entity: LibraryEntity;
+  id: number;
 }
 
 export interface UpdateCategoryInput {
@@ -76,8 +295,18 @@ export interface UpdateItemInput {
 export const COMMAND_NAMES = [
   "ping",
   "get_app_info",
+  "get_autostart_status",
+  "list_backups",
+  "create_backup_now",
+  "restore_backup",
   "get_settings",
   "set_settings",
+  "get_settings_schema",
+  "reset_settings",
+  "get_supported_locales",
+  "get_http_api_token",
+  "regenerate_http_api_token",
+  "get_effective_theme",
   "get_categories",
   "create_category",
   "update_category",
@@ -88,14 +317,52 @@ export const COMMAND_NAMES = [
   "create_item",
   "update_item",
   "delete_item",
+  "set_item_expiry",
+  "find_duplicate_items",
+  "merge_items",
+  "get_item_history",
+  "diff_item_revisions",
+  "restore_item_revision",
+  "get_changes_since",
+  "import_snippets",
+  "export_snippets",
+  "import_keymap",
+  "import_cheatsheets",
+  "import_ssh_hosts",
+  "list_bookmark_folders",
+  "import_bookmarks",
+  "get_category_links",
+  "link_category",
+  "unlink_category",
+  "sync_category_link",
+  "get_hotkey_bindings",
+  "create_hotkey_binding",
+  "delete_hotkey_binding",
+  "get_ticker_status",
+  "set_ticker_items",
+  "pause_ticker",
+  "resume_ticker",
+  "sync_now",
+  "list_sync_conflicts",
+  "dismiss_sync_conflict",
   "update_tray_title",
 ] as const;
 
 export interface CommandArgs {
   ping: Record<string, never>;
   get_app_info: Record<string, never>;
+  get_autostart_status: Record<string, never>;
+  list_backups: Record<string, never>;
+  create_backup_now: Record<string, never>;
+  restore_backup: { name: string };
   get_settings: Record<string, never>;
-  set_settings: { input: SetSettingsInput };
+  set_settings: { input: Record<string, unknown> };
+  get_settings_schema: Record<string, never>;
+  reset_settings: Record<string, never>;
+  get_supported_locales: Record<string, never>;
+  get_http_api_token: Record<string, never>;
+  regenerate_http_api_token: Record<string, never>;
+  get_effective_theme: Record<string, never>;
   get_categories: Record<string, never>;
   create_category: { input: CreateCategoryInput };
   update_category: { input: UpdateCategoryInput };
@@ -106,14 +373,52 @@ export interface CommandArgs {
   create_item: { input: CreateItemInput };
   update_item: { input: UpdateItemInput };
   delete_item: { id: number };
+  set_item_expiry: { id: number; expiresAt: string | null };
+  find_duplicate_items: { threshold: number | null };
+  merge_items: { input: MergeItemsInput };
+  get_item_history: { itemId: number };
+  diff_item_revisions: { itemId: number; from: number | null; to: number | null };
+  restore_item_revision: { revisionId: number };
+  get_changes_since: { cursor: number; limit: number | null };
+  import_snippets: { format: SnippetFormat; path: string };
+  export_snippets: { format: SnippetFormat; dir: string };
+  import_keymap: { format: KeymapFormat; path: string };
+  import_cheatsheets: { format: CheatsheetFormat; dir: string; mode: ImportMode };
+  import_ssh_hosts: { path: string | null; mode: ImportMode };
+  list_bookmark_folders: { path: string };
+  import_bookmarks: { path: string; folders: string[] | null };
+  get_category_links: Record<string, never>;
+  link_category: { categoryId: number; path: string };
+  unlink_category: { categoryId: number };
+  sync_category_link: { categoryId: number };
+  get_hotkey_bindings: Record<string, never>;
+  create_hotkey_binding: { input: CreateHotkeyBindingInput };
+  delete_hotkey_binding: { id: number };
+  get_ticker_status: Record<string, never>;
+  set_ticker_items: { itemIds: number[] };
+  pause_ticker: Record<string, never>;
+  resume_ticker: Record<string, never>;
+  sync_now: Record<string, never>;
+  list_sync_conflicts: Record<string, never>;
+  dismiss_sync_conflict: { id: number };
   update_tray_title: { title: string };
 }
 
 export interface CommandReturns {
   ping: string;
   get_app_info: AppInfo;
+  get_autostart_status: AutostartStatus;
+  list_backups: BackupInfo[];
+  create_backup_now: BackupInfo;
+  restore_backup: BackupInfo;
   get_settings: AppSettings;
   set_settings: AppSettings;
+  get_settings_schema: SettingSchema[];
+  reset_settings: AppSettings;
+  get_supported_locales: SupportedLocale[];
+  get_http_api_token: string;
+  regenerate_http_api_token: string;
+  get_effective_theme: EffectiveTheme;
   get_categories: Category[];
   create_category: Category;
   update_category: Category;
@@ -124,6 +429,58 @@ export interface CommandReturns {
   create_item: Item;
   update_item: Item;
   delete_item: void;
+  set_item_expiry: Item;
+  find_duplicate_items: DuplicateGroup[];
+  merge_items: Item;
+  get_item_history: ItemRevision[];
+  diff_item_revisions: RevisionDiff;
+  restore_item_revision: Item;
+  get_changes_since: ChangeSet;
+  import_snippets: ImportSummary;
+  export_snippets: string[];
+  import_keymap: ImportSummary;
+  import_cheatsheets: ImportSummary;
+  import_ssh_hosts: ImportSummary;
+  list_bookmark_folders: BookmarkFolder[];
+  import_bookmarks: ImportSummary;
+  get_category_links: CategoryLink[];
+  link_category: CategoryLink;
+  unlink_category: void;
+  sync_category_link: CategoryLink;
+  get_hotkey_bindings: HotkeyBinding[];
+  create_hotkey_binding: HotkeyBinding;
+  delete_hotkey_binding: void;
+  get_ticker_status: TickerStatus;
+  set_ticker_items: TickerStatus;
+  pause_ticker: TickerStatus;
+  resume_ticker: TickerStatus;
+  sync_now: SyncReport;
+  list_sync_conflicts: SyncConflict[];
+  dismiss_sync_conflict: void;
   update_tray_title: void;
 }
 
+export const EVENT_NAMES = [
+  "library-changed",
+  "settings-changed",
+  "overlay-filter",
+  "select-category",
+  "effective-theme-changed",
+  "database-restored",
+  "sync-completed",
+  "library-reloaded",
+  "category-link-synced",
+] as const;
+
+export interface EventPayloads {
+  "library-changed": LibraryChanged;
+  "settings-changed": SettingsChanged;
+  "overlay-filter": OverlayFilter;
+  "select-category": SelectCategory;
+  "effective-theme-changed": EffectiveThemeChanged;
+  "database-restored": DatabaseRestored;
+  "sync-completed": SyncCompleted;
+  "library-reloaded": LibraryReloaded;
+  "category-link-synced": CategoryLinkSynced;
+}
+
@@ -6,12 +6,14 @@
  */
 
 import { invoke } from '@tauri-apps/api/core';
-import type { CommandArgs, CommandReturns } from './ipc.generated';
-export type { CommandArgs, CommandReturns } from './ipc.generated';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+import type { CommandArgs, CommandReturns, EventPayloads } from './ipc.generated';
+export type { CommandArgs, CommandReturns, EventPayloads } from './ipc.generated';
 
 export type CommandName = keyof CommandArgs;
 export type CommandArg<C extends CommandName> = CommandArgs[C];
 export type CommandReturn<C extends CommandName> = CommandReturns[C];
+export type EventName = keyof EventPayloads;
 
 /**
  * Type-safe Tauri invoke wrapper
@@ -20,7 +22,7 @@ export type CommandReturn<C extends CommandName> = CommandReturns[C];
  * ```typescript
  * const info = await typedInvoke("get_app_info", {});
  * const settings = await typedInvoke("get_settings", {});
- * await typedInvoke("set_settings", { input: { locale: "zh-CN", launch_on_login: null, theme: null } });
+ * await typedInvoke("set_settings", { input: { locale: "zh-CN", theme: "dark" } });
  * ```
  */
 export async function typedInvoke<C extends CommandName>(
@@ -30,6 +32,23 @@ export async function typedInvoke<C extends CommandName>(
   return invoke(command, args as Record<string, unknown>);
 }
 
+/**
+ * Type-safe Tauri event listener for backend events
+ *
+ * @example
+ * ```typescript
+ * const unlisten = await typedListen("library-changed", (change) => {
+ *   console.log(change.entity, change.kind, change.ids);
+ * });
+ * ```
+ */
+export function typedListen<E extends EventName>(
+  event: E,
+  handler: (payload: EventPayloads[E]) => void
+): Promise<UnlistenFn> {
+  return listen<EventPayloads[E]>(event, (e) => handler(e.payload));
+}
+
 /**
  * Legacy wrapper for backward compatibility
  *
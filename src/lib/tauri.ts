@@ -6,10 +6,13 @@
 
 export {
   typedInvoke,
+  typedListen,
   tauriInvoke,
   type CommandName,
+  type EventName,
   type CommandArg,
   type CommandReturn,
   type CommandArgs,
   type CommandReturns,
+  type EventPayloads,
 } from '@/core/ipc';
@@ -0,0 +1,13 @@
+import { typedListen } from '@/lib/tauri';
+import { getEffectiveTheme } from '@/modules/appearance';
+import type { EffectiveTheme } from '@/core/ipc.generated';
+
+function applyThemeClass(theme: EffectiveTheme) {
+  document.documentElement.classList.toggle('dark', theme === 'dark');
+}
+
+/** Keep the `dark` class on <html> in sync with the backend's resolved theme. */
+export function followEffectiveTheme() {
+  getEffectiveTheme().then(applyThemeClass).catch(() => {});
+  return typedListen('effective-theme-changed', ({ theme }) => applyThemeClass(theme));
+}
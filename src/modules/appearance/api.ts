@@ -0,0 +1,5 @@
+import { typedInvoke } from '@/lib/tauri';
+
+export function getEffectiveTheme() {
+  return typedInvoke('get_effective_theme', {});
+}
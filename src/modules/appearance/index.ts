@@ -0,0 +1 @@
+export { getEffectiveTheme } from './api';
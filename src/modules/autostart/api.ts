@@ -0,0 +1,5 @@
+import { typedInvoke } from '@/lib/tauri';
+
+export function getAutostartStatus() {
+  return typedInvoke('get_autostart_status', {});
+}
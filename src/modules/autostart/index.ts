@@ -0,0 +1 @@
+export { getAutostartStatus } from './api';
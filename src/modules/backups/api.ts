@@ -0,0 +1,13 @@
+import { typedInvoke } from '@/lib/tauri';
+
+export function listBackups() {
+  return typedInvoke('list_backups', {});
+}
+
+export function createBackupNow() {
+  return typedInvoke('create_backup_now', {});
+}
+
+export function restoreBackup(name: string) {
+  return typedInvoke('restore_backup', { name });
+}
@@ -0,0 +1 @@
+export { listBackups, createBackupNow, restoreBackup } from './api';
@@ -0,0 +1,10 @@
+import { typedInvoke } from '@/lib/tauri';
+
+export function listBookmarkFolders(path: string) {
+  return typedInvoke('list_bookmark_folders', { path });
+}
+
+/** Import a bookmarks HTML file; `folders` limits it to those folders. */
+export function importBookmarks(path: string, folders: string[] | null) {
+  return typedInvoke('import_bookmarks', { path, folders });
+}
@@ -0,0 +1 @@
+export { importBookmarks, listBookmarkFolders } from './api';
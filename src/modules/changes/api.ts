@@ -0,0 +1,5 @@
+import { typedInvoke } from '@/lib/tauri';
+
+export function getChangesSince(cursor: number, limit?: number) {
+  return typedInvoke('get_changes_since', { cursor, limit: limit ?? null });
+}
@@ -0,0 +1 @@
+export { getChangesSince } from './api';
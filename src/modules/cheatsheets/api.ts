@@ -0,0 +1,10 @@
+import { typedInvoke } from '@/lib/tauri';
+import type { CheatsheetFormat, ImportMode } from '@/core/ipc.generated';
+
+export function importCheatsheets(
+  format: CheatsheetFormat,
+  dir: string,
+  mode: ImportMode,
+) {
+  return typedInvoke('import_cheatsheets', { format, dir, mode });
+}
@@ -0,0 +1 @@
+export { importCheatsheets } from './api';
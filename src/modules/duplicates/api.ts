@@ -0,0 +1,11 @@
+import { typedInvoke } from '@/lib/tauri';
+import type { MergeItemsInput } from '@/core/ipc.generated';
+
+/** Groups of look-alike items; `threshold` (0.5–1) tunes fuzzy matching. */
+export function findDuplicateItems(threshold: number | null = null) {
+  return typedInvoke('find_duplicate_items', { threshold });
+}
+
+export function mergeItems(input: MergeItemsInput) {
+  return typedInvoke('merge_items', { input });
+}
@@ -0,0 +1 @@
+export { findDuplicateItems, mergeItems } from './api';
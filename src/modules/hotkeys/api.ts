@@ -0,0 +1,14 @@
+import { typedInvoke } from '@/lib/tauri';
+import type { CreateHotkeyBindingInput } from '@/core/ipc.generated';
+
+export function getHotkeyBindings() {
+  return typedInvoke('get_hotkey_bindings', {});
+}
+
+export function createHotkeyBinding(input: CreateHotkeyBindingInput) {
+  return typedInvoke('create_hotkey_binding', { input });
+}
+
+export function deleteHotkeyBinding(id: number) {
+  return typedInvoke('delete_hotkey_binding', { id });
+}
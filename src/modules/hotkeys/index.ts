@@ -0,0 +1,5 @@
+export {
+  getHotkeyBindings,
+  createHotkeyBinding,
+  deleteHotkeyBinding,
+} from './api';
@@ -20,3 +20,8 @@ export function updateItem(input: UpdateItemInput) {
 export function deleteItem(id: number) {
   return typedInvoke('delete_item', { id });
 }
+
+/** Expire the item at `expiresAt`, or never for `null`. */
+export function setItemExpiry(id: number, expiresAt: string | null) {
+  return typedInvoke('set_item_expiry', { id, expiresAt });
+}
@@ -4,4 +4,5 @@ export {
   createItem,
   updateItem,
   deleteItem,
+  setItemExpiry,
 } from './api';
@@ -0,0 +1,6 @@
+import { typedInvoke } from '@/lib/tauri';
+import type { KeymapFormat } from '@/core/ipc.generated';
+
+export function importKeymap(format: KeymapFormat, path: string) {
+  return typedInvoke('import_keymap', { format, path });
+}
@@ -0,0 +1 @@
+export { importKeymap } from './api';
@@ -0,0 +1,18 @@
+import { typedInvoke } from '@/lib/tauri';
+
+export function getCategoryLinks() {
+  return typedInvoke('get_category_links', {});
+}
+
+/** Mirror a Markdown file into a category, replacing its items. */
+export function linkCategory(categoryId: number, path: string) {
+  return typedInvoke('link_category', { categoryId, path });
+}
+
+export function unlinkCategory(categoryId: number) {
+  return typedInvoke('unlink_category', { categoryId });
+}
+
+export function syncCategoryLink(categoryId: number) {
+  return typedInvoke('sync_category_link', { categoryId });
+}
@@ -0,0 +1,6 @@
+export {
+  getCategoryLinks,
+  linkCategory,
+  syncCategoryLink,
+  unlinkCategory,
+} from './api';
@@ -0,0 +1,19 @@
+import { typedInvoke } from '@/lib/tauri';
+
+/** Earlier versions of an item, newest first. */
+export function getItemHistory(itemId: number) {
+  return typedInvoke('get_item_history', { itemId });
+}
+
+/** Line diff between two revisions; `null` is the current version. */
+export function diffItemRevisions(
+  itemId: number,
+  from: number | null,
+  to: number | null = null,
+) {
+  return typedInvoke('diff_item_revisions', { itemId, from, to });
+}
+
+export function restoreItemRevision(revisionId: number) {
+  return typedInvoke('restore_item_revision', { revisionId });
+}
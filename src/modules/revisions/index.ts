@@ -0,0 +1,5 @@
+export {
+  diffItemRevisions,
+  getItemHistory,
+  restoreItemRevision,
+} from './api';
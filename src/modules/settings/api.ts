@@ -1,10 +1,33 @@
 import { typedInvoke } from '@/lib/tauri';
-import type { CommandArg } from '@/core/ipc';
+import type { AppSettings } from '@/core/ipc.generated';
 
 export function getSettings() {
   return typedInvoke('get_settings', {});
 }
 
-export function setSettings(input: CommandArg<'set_settings'>['input']) {
+/** Write the given keys; `null` resets a key to its default. */
+export function setSettings(input: { [K in keyof AppSettings]?: AppSettings[K] | null }) {
   return typedInvoke('set_settings', { input });
 }
+
+export function getSupportedLocales() {
+  return typedInvoke('get_supported_locales', {});
+}
+
+export function getSettingsSchema() {
+  return typedInvoke('get_settings_schema', {});
+}
+
+export function resetSettings() {
+  return typedInvoke('reset_settings', {});
+}
+
+/** The HTTP API token, which the settings leave out; empty while unset. */
+export function getHttpApiToken() {
+  return typedInvoke('get_http_api_token', {});
+}
+
+/** Replace the HTTP API token with a new random one and return it. */
+export function regenerateHttpApiToken() {
+  return typedInvoke('regenerate_http_api_token', {});
+}
@@ -1 +1,9 @@
-export { getSettings, setSettings } from './api';
+export {
+  getSettings,
+  setSettings,
+  getSupportedLocales,
+  getSettingsSchema,
+  resetSettings,
+  getHttpApiToken,
+  regenerateHttpApiToken,
+} from './api';
@@ -0,0 +1,10 @@
+import { typedInvoke } from '@/lib/tauri';
+import type { SnippetFormat } from '@/core/ipc.generated';
+
+export function importSnippets(format: SnippetFormat, path: string) {
+  return typedInvoke('import_snippets', { format, path });
+}
+
+export function exportSnippets(format: SnippetFormat, dir: string) {
+  return typedInvoke('export_snippets', { format, dir });
+}
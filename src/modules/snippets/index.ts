@@ -0,0 +1 @@
+export { importSnippets, exportSnippets } from './api';
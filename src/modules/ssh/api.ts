@@ -0,0 +1,7 @@
+import { typedInvoke } from '@/lib/tauri';
+import type { ImportMode } from '@/core/ipc.generated';
+
+/** Import SSH config hosts; `path` defaults to `~/.ssh/config`. */
+export function importSshHosts(path: string | null, mode: ImportMode) {
+  return typedInvoke('import_ssh_hosts', { path, mode });
+}
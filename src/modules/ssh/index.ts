@@ -0,0 +1 @@
+export { importSshHosts } from './api';
@@ -0,0 +1,13 @@
+import { typedInvoke } from '@/lib/tauri';
+
+export function syncNow() {
+  return typedInvoke('sync_now', {});
+}
+
+export function listSyncConflicts() {
+  return typedInvoke('list_sync_conflicts', {});
+}
+
+export function dismissSyncConflict(id: number) {
+  return typedInvoke('dismiss_sync_conflict', { id });
+}
@@ -0,0 +1 @@
+export { syncNow, listSyncConflicts, dismissSyncConflict } from './api';
@@ -0,0 +1,17 @@
+import { typedInvoke } from '@/lib/tauri';
+
+export function getTickerStatus() {
+  return typedInvoke('get_ticker_status', {});
+}
+
+export function setTickerItems(itemIds: number[]) {
+  return typedInvoke('set_ticker_items', { itemIds });
+}
+
+export function pauseTicker() {
+  return typedInvoke('pause_ticker', {});
+}
+
+export function resumeTicker() {
+  return typedInvoke('resume_ticker', {});
+}
@@ -0,0 +1,6 @@
+export {
+  getTickerStatus,
+  setTickerItems,
+  pauseTicker,
+  resumeTicker,
+} from './api';
@@ -1,4 +1,6 @@
-import { useState } from 'react';
+import { useEffect, useState } from 'react';
+import { useQueryClient } from '@tanstack/react-query';
+import { typedListen } from '@/lib/tauri';
 import { Separator } from '@/components/ui/separator';
 import { CategoryList } from './components/CategoryList';
 import { ItemList } from './components/ItemList';
@@ -6,6 +8,43 @@ import type { Category } from '@/core/ipc.generated';
 
 function App() {
   const [selectedCategory, setSelectedCategory] = useState<Category | null>(null);
+  const queryClient = useQueryClient();
+
+  // Keep lists fresh when another window (or the backend) changes the library
+  useEffect(() => {
+    const unlisten = typedListen('library-changed', (change) => {
+      queryClient.invalidateQueries({ queryKey: ['categories'] });
+      queryClient.invalidateQueries({ queryKey: ['all-items'] });
+      if (change.category_id !== null) {
+        queryClient.invalidateQueries({ queryKey: ['items', change.category_id] });
+      } else {
+        queryClient.invalidateQueries({ queryKey: ['items'] });
+      }
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [queryClient]);
+
+  // Another program changed the database directly; refetch everything
+  useEffect(() => {
+    const unlisten = typedListen('library-reloaded', () => {
+      queryClient.invalidateQueries();
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [queryClient]);
+
+  // `peeky://open` links select a category
+  useEffect(() => {
+    const unlisten = typedListen('select-category', ({ category }) => {
+      setSelectedCategory(category);
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
 
   return (
     <div className="flex h-screen bg-gradient-to-br from-slate-50 via-stone-50 to-emerald-50 text-slate-900">
@@ -33,6 +33,7 @@ export function ItemList({ category }: ItemListProps) {
         category_id: category.id,
         label: newLabel.trim(),
         value: newValue.trim() || null,
+        kind: 'text',
       }),
     onSuccess: () => {
       invalidate();
@@ -1,11 +1,14 @@
 import { useEffect, useState, useMemo } from 'react';
-import { useQuery } from '@tanstack/react-query';
+import { useQuery, useQueryClient } from '@tanstack/react-query';
 import { getCurrentWebviewWindow } from '@tauri-apps/api/webviewWindow';
+import { typedListen } from '@/lib/tauri';
 import { getAllItems } from '@/modules/items';
 import { CategoryColumn } from './components/CategoryColumn';
 
 function App() {
   const [visible, setVisible] = useState(true);
+  const [categoryFilter, setCategoryFilter] = useState<number | null>(null);
+  const queryClient = useQueryClient();
 
   const { data: items = [] } = useQuery({
     queryKey: ['all-items'],
@@ -17,6 +20,7 @@ function App() {
   const grouped = useMemo(() => {
     const map = new Map<number, { name: string; items: typeof items }>();
     for (const item of items) {
+      if (categoryFilter !== null && item.category_id !== categoryFilter) continue;
       let group = map.get(item.category_id);
       if (!group) {
         group = { name: item.category_name, items: [] };
@@ -25,7 +29,30 @@ function App() {
       group.items.push(item);
     }
     return Array.from(map.values());
-  }, [items]);
+  }, [items, categoryFilter]);
+
+  // Category hotkeys open the overlay filtered to a single category
+  useEffect(() => {
+    const unlisten = typedListen('overlay-filter', (filter) => {
+      setCategoryFilter(filter.category_id);
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  // Refetch when the library is edited in another window or program
+  useEffect(() => {
+    const refetch = () => {
+      queryClient.invalidateQueries({ queryKey: ['all-items'] });
+    };
+    const unlistenChanged = typedListen('library-changed', refetch);
+    const unlistenReloaded = typedListen('library-reloaded', refetch);
+    return () => {
+      unlistenChanged.then((fn) => fn());
+      unlistenReloaded.then((fn) => fn());
+    };
+  }, [queryClient]);
 
   // Close overlay on Esc
   useEffect(() => {
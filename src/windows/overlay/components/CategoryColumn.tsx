@@ -16,7 +16,9 @@ export function CategoryColumn({ categoryName, items }: CategoryColumnProps) {
           <div key={item.id} className="flex items-baseline justify-between gap-4">
             <span className="text-sm text-gray-800">{item.label}</span>
             {item.value && (
-              <span className="shrink-0 font-mono text-sm text-gray-400">
+              <span
+                className={`shrink-0 font-mono text-sm text-gray-400 ${item.kind === 'link' ? 'underline' : ''}`}
+              >
                 {item.value}
               </span>
             )}
@@ -2,9 +2,11 @@ import React from 'react';
 import ReactDOM from 'react-dom/client';
 import { QueryClient, QueryClientProvider } from '@tanstack/react-query';
 import App from './App';
+import { followEffectiveTheme } from '@/lib/theme';
 import '@/styles/globals.css';
 
 const queryClient = new QueryClient();
+followEffectiveTheme();
 
 ReactDOM.createRoot(document.getElementById('root')!).render(
   <React.StrictMode>